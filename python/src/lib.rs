@@ -16,8 +16,9 @@ fn to_style(s: &str) -> Result<Style> {
         "strict" => Ok(Style::Strict),
         "default" => Ok(Style::Default),
         "detailed" => Ok(Style::Detailed),
+        "annotated" => Ok(Style::Annotated),
         other => bail!(
-            "unknown style: {} (expected 'strict' | 'default' | 'detailed')",
+            "unknown style: {} (expected 'strict' | 'default' | 'detailed' | 'annotated')",
             other
         ),
     }
@@ -30,8 +31,11 @@ fn map_output_template(format: &str, style: Style) -> Result<OutputTemplate> {
         "yaml" | "yml" => Ok(OutputTemplate::Yaml),
         "text" => Ok(OutputTemplate::Text),
         "code" => Ok(OutputTemplate::Code),
+        "markdown" | "md" => Ok(OutputTemplate::Markdown),
+        "html" => Ok(OutputTemplate::Html),
+        "gron" => Ok(OutputTemplate::Gron),
         other => bail!(
-            "unknown format: {} (expected 'auto' | 'json' | 'yaml' | 'text' | 'code')",
+            "unknown format: {} (expected 'auto' | 'json' | 'yaml' | 'text' | 'code' | 'markdown' | 'html' | 'gron')",
             other
         ),
     }
@@ -69,6 +73,7 @@ fn render_config_with_sampler(
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..RenderConfig::default()
     })
 }
 
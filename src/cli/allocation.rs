@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use ignore::overrides::{Override, OverrideBuilder};
+
+use crate::cli::args::{AllocationArg, Cli, SortArg};
+use crate::sorting::{FRECENFILE_TOP_N, frecency_ranks};
+
+/// Budget shares and grep-match ordering for fileset entries, from
+/// `--allocation`, `--weight` overrides and `--sort grep-matches`. Shares
+/// by grep hits are left to the library, which counts the matches it keeps.
+pub(crate) fn fileset_layout(
+    cli: &Cli,
+    entries: &[(String, Vec<u8>)],
+    grep_cfg: &headson::GrepConfig,
+) -> Result<headson::FilesetLayout> {
+    let mut weights = policy_weights(cli.allocation, entries, grep_cfg)?;
+    for spec in &cli.weights {
+        apply_override(spec, entries, &mut weights)?;
+    }
+    let sort_by_matches = match (cli.sort, cli.reverse) {
+        (SortArg::GrepMatches, false) => Some(headson::MatchSort::MostFirst),
+        (SortArg::GrepMatches, true) => Some(headson::MatchSort::FewestFirst),
+        _ => None,
+    };
+    Ok(headson::FilesetLayout {
+        weights,
+        weigh_by_matches: matches!(cli.allocation, AllocationArg::GrepHits),
+        sort_by_matches,
+    })
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "Weights are relative shares; precision beyond 2^52 is irrelevant"
+)]
+fn policy_weights(
+    allocation: AllocationArg,
+    entries: &[(String, Vec<u8>)],
+    grep_cfg: &headson::GrepConfig,
+) -> Result<Vec<Option<f64>>> {
+    Ok(match allocation {
+        AllocationArg::Equal => vec![Some(1.0); entries.len()],
+        AllocationArg::Size => entries
+            .iter()
+            .map(|(_, bytes)| Some(bytes.len().max(1) as f64))
+            .collect(),
+        // Ranked files weigh from FRECENFILE_TOP_N + 1 (most frecent) down;
+        // files outside the ranking (or outside git) weigh 1.
+        AllocationArg::Frecency => {
+            let paths: Vec<&Path> =
+                entries.iter().map(|(name, _)| Path::new(name)).collect();
+            frecency_ranks(&paths)
+                .into_iter()
+                .map(|rank| {
+                    Some(rank.map_or(1.0, |r| {
+                        FRECENFILE_TOP_N.saturating_sub(r) as f64 + 1.0
+                    }))
+                })
+                .collect()
+        }
+        AllocationArg::GrepHits => {
+            if !grep_cfg.is_active() {
+                bail!(
+                    "--allocation grep-hits requires --grep, --weak-grep or --where"
+                );
+            }
+            vec![None; entries.len()]
+        }
+    })
+}
+
+fn apply_override(
+    spec: &str,
+    entries: &[(String, Vec<u8>)],
+    weights: &mut [Option<f64>],
+) -> Result<()> {
+    let (matcher, weight) = parse_weight(spec)?;
+    for ((name, _), slot) in entries.iter().zip(weights.iter_mut()) {
+        if matcher.matched(Path::new(name), false).is_whitelist() {
+            *slot = Some(weight);
+        }
+    }
+    Ok(())
+}
+
+fn parse_weight(spec: &str) -> Result<(Override, f64)> {
+    let invalid = || {
+        format!(
+            "invalid --weight '{spec}': expected GLOB=WEIGHT with WEIGHT > 0"
+        )
+    };
+    let Some((glob, raw)) = spec.rsplit_once('=') else {
+        bail!(invalid());
+    };
+    let weight: f64 = raw.trim().parse().with_context(invalid)?;
+    if glob.is_empty() || !weight.is_finite() || weight <= 0.0 {
+        bail!(invalid());
+    }
+    let mut builder = OverrideBuilder::new(".");
+    builder.add(glob).with_context(invalid)?;
+    Ok((builder.build().with_context(invalid)?, weight))
+}
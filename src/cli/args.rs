@@ -1,13 +1,22 @@
 use std::path::PathBuf;
 
-use clap::{ArgAction, Parser, ValueEnum};
+use clap::{ArgAction, ArgGroup, Args, Parser, Subcommand, ValueEnum};
+
+use crate::sorting::SortKey;
 
 /// Top-level CLI flags and enums.
 #[derive(Parser, Debug)]
 #[command(
     name = "hson",
     version,
-    about = "Get a small but useful preview of JSON or YAML"
+    about = "Get a small but useful preview of JSON or YAML",
+    args_conflicts_with_subcommands = true,
+    disable_help_subcommand = true,
+    group(
+        ArgGroup::new("strong_grep")
+            .args(["grep", "predicates"])
+            .multiple(true)
+    )
 )]
 pub struct Cli {
     #[arg(short = 'c', long = "bytes")]
@@ -56,12 +65,33 @@ pub struct Cli {
         help = "Render filesets in a directory tree layout with inline previews"
     )]
     pub tree: bool,
+    #[arg(
+        long = "tree-stats",
+        default_value_t = false,
+        requires = "tree",
+        help = "Annotate --tree entries with file size and line/element counts, and directories with totals. Free unless --count-headers is set."
+    )]
+    pub tree_stats: bool,
     #[arg(
         long = "no-sort",
         default_value_t = false,
+        conflicts_with_all = ["sort", "reverse"],
         help = "Keep input order for filesets (skip frecency/mtime sorting)."
     )]
     pub no_sort: bool,
+    #[arg(
+        long = "sort",
+        value_enum,
+        default_value_t = SortArg::Frecency,
+        help = "Fileset ordering: frecency (default; frequent+recent git files first, mtime fallback) | mtime (newest first) | path | size (largest first) | git-churn (most commits first) | grep-matches (most --grep/--weak-grep/--where matches first). Also orders the --tree view."
+    )]
+    pub sort: SortArg,
+    #[arg(
+        long = "reverse",
+        default_value_t = false,
+        help = "Reverse the fileset order chosen by --sort"
+    )]
+    pub reverse: bool,
     #[arg(
         short = 'm',
         long = "compact",
@@ -76,6 +106,45 @@ pub struct Cli {
         help = "Maximum string length to display"
     )]
     pub string_cap: usize,
+    #[arg(
+        long = "expand-embedded",
+        default_value_t = false,
+        help = "Parse string values holding serialized JSON (or multi-line YAML) objects/arrays into real subtrees, marked as embedded. Budgets, sampling and grep apply inside them."
+    )]
+    pub expand_embedded: bool,
+    #[arg(
+        long = "strict-parse",
+        default_value_t = false,
+        conflicts_with = "recover",
+        help = "Fail on fileset files that do not parse as their format, instead of previewing them as text"
+    )]
+    pub strict_parse: bool,
+    #[arg(
+        long = "recover",
+        default_value_t = false,
+        help = "Read malformed JSON up to its first error and close what is still open, marking the cut, instead of failing (or, in a fileset, previewing the file as text)"
+    )]
+    pub recover: bool,
+    #[arg(
+        long = "line-numbers",
+        default_value_t = false,
+        help = "Prefix JSON/YAML preview lines with the line they start on in the source file, so omitted stretches are easy to find in the original"
+    )]
+    pub line_numbers: bool,
+    #[arg(
+        long = "key-paths",
+        default_value_t = false,
+        help = "End each JSON/YAML preview line with a comment naming the path of the value it starts (e.g. `// $.items[412].spec.image`), so values stay locatable when their parents are elided. Strict and annotated JSON have no comments and get none"
+    )]
+    pub key_paths: bool,
+    #[arg(
+        long = "fail-on",
+        value_enum,
+        value_name = "CONDITION",
+        value_delimiter = ',',
+        help = "Exit non-zero (after printing the preview) when an input failed to parse and was recovered or shown as text, the output was truncated by the budget, an input was ignored, or grep found no match: parse-error|truncated|ignored|no-match (repeatable or comma-separated). Exit codes: 3 parse-error, 4 ignored, 5 no-match, 6 truncated."
+    )]
+    pub fail_on: Vec<FailOnArg>,
     #[arg(
         short = 'C',
         long = "global-bytes",
@@ -90,6 +159,19 @@ pub struct Cli {
         help = "Total line budget across all inputs. Fileset headers/summary lines do not consume this budget."
     )]
     pub global_lines: Option<usize>,
+    #[arg(
+        long = "allocation",
+        value_enum,
+        default_value_t = AllocationArg::Equal,
+        help = "How filesets share a global budget: equal (default; round-robin) | size (by file size) | frecency (by git frecency rank) | grep-hits (by --grep/--weak-grep/--where matches)"
+    )]
+    pub allocation: AllocationArg,
+    #[arg(
+        long = "weight",
+        value_name = "GLOB=WEIGHT",
+        help = "Override the global-budget weight of files matching GLOB (repeatable; the last matching pattern wins), e.g. --weight '*.lock=0.1'"
+    )]
+    pub weights: Vec<String>,
     #[arg(
         long = "tail",
         default_value_t = false,
@@ -108,7 +190,7 @@ pub struct Cli {
         long = "format",
         value_enum,
         default_value_t = OutputFormat::Auto,
-        help = "Output format: auto|json|yaml|text (filesets: auto is per-file)."
+        help = "Output format: auto|json|yaml|text|markdown|html|gron (filesets: auto is per-file). markdown fences each file's auto preview in a code block under a heading; --tree becomes a nested list. html writes one self-contained page with collapsible objects and arrays. gron flattens the preview to one `$.path.to[3] = value` line per value, for grepping."
     )]
     pub format: OutputFormat,
     #[arg(
@@ -116,7 +198,7 @@ pub struct Cli {
         long = "template",
         value_enum,
         default_value_t = StyleArg::Default,
-        help = "Output style: strict|default|detailed."
+        help = "Output style: strict|default|detailed|annotated. annotated is strict JSON that records omissions as data: {\"$omitted_items\": N} array elements, \"$omitted_keys\" object keys and a …[+N chars] suffix on cut strings."
     )]
     pub style: StyleArg,
     #[arg(long = "indent", default_value = "  ")]
@@ -147,9 +229,72 @@ pub struct Cli {
         value_name = "INPUT",
         value_hint = clap::ValueHint::FilePath,
         num_args = 0..,
-        help = "Optional file paths. If omitted, reads input from stdin. Multiple input files are supported. Directories and binary files are ignored with a notice on stderr."
+        help = "Optional file or directory paths. If omitted, reads input from stdin. Multiple inputs are supported; directories are walked recursively (respecting .gitignore). Binary files are ignored with a notice on stderr."
     )]
     pub inputs: Vec<PathBuf>,
+    #[arg(
+        long = "files-from",
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Read input paths from PATH (one per line), or from stdin with '-'. Listed paths are used like positional inputs; stdin is then never read as a document."
+    )]
+    pub files_from: Option<PathBuf>,
+    #[arg(
+        short = '0',
+        long = "null",
+        default_value_t = false,
+        requires = "files_from",
+        help = "Paths in the --files-from list are NUL-separated (e.g. find -print0, git ls-files -z)."
+    )]
+    pub null_separated: bool,
+    #[arg(
+        long = "rev",
+        value_name = "REV",
+        help = "Read inputs from the tree of git revision REV instead of the working directory. Paths and globs select files in that tree; without them, everything below the current directory is used."
+    )]
+    pub rev: Option<String>,
+    #[arg(
+        long = "changed",
+        value_name = "BASE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "HEAD",
+        help = "Only use files added or modified relative to git ref BASE (default HEAD; pass as --changed=BASE). Compares against --rev when given, else the working tree. Without paths or globs, all changed files below the current directory are used."
+    )]
+    pub changed: Option<String>,
+    #[arg(
+        long = "max-depth",
+        value_name = "NUM",
+        help = "Limit directory/glob traversal to NUM levels below each starting path; 0 only uses explicitly given paths."
+    )]
+    pub max_depth: Option<usize>,
+    #[arg(
+        long = "hidden",
+        default_value_t = false,
+        help = "Include hidden files and directories when walking directories and globs."
+    )]
+    pub hidden: bool,
+    #[arg(
+        long = "no-ignore",
+        default_value_t = false,
+        help = "Do not respect ignore files (.gitignore, .ignore, git excludes) when walking directories and globs."
+    )]
+    pub no_ignore: bool,
+    #[arg(
+        long = "exclude",
+        value_name = "GLOB",
+        action = ArgAction::Append,
+        help = "Skip files and directories matching GLOB (gitignore syntax) when walking directories and globs. Repeatable."
+    )]
+    pub excludes: Vec<String>,
+    #[arg(
+        long = "type",
+        value_enum,
+        value_name = "TYPE",
+        action = ArgAction::Append,
+        help = "Only pick up files of TYPE (json|yaml|code) when walking directories and globs. Repeatable; explicit file paths are always used."
+    )]
+    pub types: Vec<FileTypeArg>,
     #[arg(
         short = 'i',
         long = "input-format",
@@ -157,12 +302,27 @@ pub struct Cli {
         help = "Input ingestion format: json|yaml|text. Default is json for stdin/filesets; auto-detected for single-file auto runs."
     )]
     pub input_format: Option<InputFormat>,
+    #[arg(
+        long = "map-format",
+        value_name = "GLOB=FORMAT",
+        help = "Ingest and render files matching GLOB as FORMAT (json|yaml|text|code) instead of guessing from the name (repeatable; the last matching pattern wins), e.g. --map-format '*.json.tmpl=yaml'. Ignored when -i/--input-format or a --format other than auto, markdown, html or gron is given."
+    )]
+    pub map_formats: Vec<String>,
     #[arg(
         long = "debug",
         default_value_t = false,
         help = "Dump pruned internal tree (JSON) to stderr for the final render attempt"
     )]
     pub debug: bool,
+    #[arg(
+        long = "output",
+        value_enum,
+        value_name = "MODE",
+        default_value_t = OutputModeArg::Text,
+        conflicts_with_all = ["grep_report", "color"],
+        help = "What to print on stdout: text (default; the preview) | json-envelope (a JSON document with the uncolored preview plus per-file stats, omitted counts, binding budgets, ignored files, notices and grep matches)"
+    )]
+    pub output: OutputModeArg,
     #[arg(
         long = "grep",
         value_name = "REGEX",
@@ -177,23 +337,176 @@ pub struct Cli {
         help = "Bias priority toward regex matches without guaranteeing inclusion or expanding budgets."
     )]
     pub weak_grep: Option<String>,
+    #[arg(
+        long = "where",
+        value_name = "PREDICATE",
+        action = ArgAction::Append,
+        conflicts_with = "weak_grep",
+        help = "Guarantee inclusion of leaves whose value satisfies PREDICATE, e.g. 'latency_ms > 500' or '$.items[*].ok == false'. Repeatable (any predicate may match); combines with --grep."
+    )]
+    pub predicates: Vec<String>,
     #[arg(
         long = "grep-show",
         value_enum,
         default_value_t = GrepShowArg::Matching,
-        requires = "grep",
+        requires = "strong_grep",
         conflicts_with = "weak_grep",
-        help = "When using --grep, control fileset inclusion: matching (default) | all"
+        help = "When using --grep or --where, control what is shown: matching (default; drop files without matches) | all (keep every file) | names (list matching files with match counts, skip rendering) | summary (only matches and their ancestors, no extra context)"
     )]
     pub grep_show: GrepShowArg,
+    #[arg(
+        long = "grep-report",
+        value_enum,
+        value_name = "FORMAT",
+        help = "Report grep matches per file: summary (footer after the preview) | json (machine-readable report instead of the preview). Requires --grep, --weak-grep or --where."
+    )]
+    pub grep_report: Option<GrepReportArg>,
+    #[arg(
+        long = "grep-color",
+        value_enum,
+        value_name = "MODE",
+        default_value_t = GrepColorArg::HighlightOnly,
+        help = "How grep matches are colored: highlight-only (default; syntax colors suppressed) | combined (keep syntax colors, matches in inverse video)"
+    )]
+    pub grep_color: GrepColorArg,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Render only what changed between two JSON or YAML documents
+    Diff(DiffArgs),
+}
+
+/// Flags of `hson diff`: two sides, a per-file budget and JSON/YAML
+/// rendering options.
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    #[arg(
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        num_args = 1..=2,
+        required = true,
+        help = "OLD NEW: files on disk or REV:path for a file in a git revision. With --rev REV, a single path is compared to its working-tree version."
+    )]
+    pub paths: Vec<PathBuf>,
+    #[arg(
+        long = "rev",
+        value_name = "REV",
+        help = "Compare REV:PATH with the working-tree PATH"
+    )]
+    pub rev: Option<String>,
+    #[arg(
+        short = 'c',
+        long = "bytes",
+        conflicts_with_all = ["chars", "lines"],
+        help = "Byte budget"
+    )]
+    pub bytes: Option<usize>,
+    #[arg(
+        short = 'u',
+        long = "chars",
+        value_name = "CHARS",
+        conflicts_with = "lines",
+        help = "Unicode character budget"
+    )]
+    pub chars: Option<usize>,
+    #[arg(
+        short = 'n',
+        long = "lines",
+        value_name = "LINES",
+        help = "Line budget"
+    )]
+    pub lines: Option<usize>,
+    #[arg(
+        long = "string-cap",
+        default_value_t = 500,
+        help = "Maximum string length to display"
+    )]
+    pub string_cap: usize,
+    #[arg(
+        short = 'f',
+        long = "format",
+        value_enum,
+        default_value_t = DiffFormatArg::Auto,
+        help = "Output format: auto (YAML when the new side is YAML, else JSON) | json | yaml"
+    )]
+    pub format: DiffFormatArg,
+    #[arg(
+        short = 'i',
+        long = "input-format",
+        value_enum,
+        help = "Input ingestion format for both sides: auto (default; yaml for .yaml/.yml names, json otherwise) | json | yaml"
+    )]
+    pub input_format: Option<DiffFormatArg>,
+    #[arg(
+        short = 't',
+        long = "template",
+        value_enum,
+        default_value_t = StyleArg::Default,
+        help = "Output style: strict|default|detailed|annotated"
+    )]
+    pub style: StyleArg,
+    #[arg(long = "indent", default_value = "  ")]
+    pub indent: String,
+    #[arg(
+        short = 'm',
+        long = "compact",
+        default_value_t = false,
+        conflicts_with_all = ["no_space", "no_newline", "indent"],
+        help = "Compact output with no added whitespace. Not very human-readable."
+    )]
+    pub compact: bool,
+    #[arg(long = "no-space", default_value_t = false)]
+    pub no_space: bool,
+    #[arg(
+        long = "no-newline",
+        default_value_t = false,
+        conflicts_with = "lines",
+        help = "Do not add newlines in the output. Incompatible with --lines."
+    )]
+    pub no_newline: bool,
+    #[arg(
+        long = "color",
+        action = ArgAction::SetTrue,
+        conflicts_with = "no_color",
+        help = "Force enable ANSI colors in output"
+    )]
+    pub color: bool,
+    #[arg(
+        long = "no-color",
+        action = ArgAction::SetTrue,
+        conflicts_with = "color",
+        help = "Disable ANSI colors in output"
+    )]
+    pub no_color: bool,
+    #[arg(
+        long = "fail-on",
+        value_enum,
+        value_name = "CONDITION",
+        value_delimiter = ',',
+        help = "Exit non-zero (after printing the diff) when a condition holds; see hson --help"
+    )]
+    pub fail_on: Vec<FailOnArg>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 pub enum OutputFormat {
     Auto,
     Json,
     Yaml,
     Text,
+    Markdown,
+    Html,
+    Gron,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum DiffFormatArg {
+    Auto,
+    Json,
+    Yaml,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -201,6 +514,7 @@ pub enum StyleArg {
     Strict,
     Default,
     Detailed,
+    Annotated,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -210,15 +524,77 @@ pub enum InputFormat {
     Text,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum MapFormatArg {
+    Json,
+    Yaml,
+    Text,
+    Code,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum FileTypeArg {
+    Json,
+    Yaml,
+    Code,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum AllocationArg {
+    Equal,
+    Size,
+    Frecency,
+    GrepHits,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SortArg {
+    Frecency,
+    Mtime,
+    Path,
+    Size,
+    GitChurn,
+    GrepMatches,
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum GrepShowArg {
     Matching,
     All,
+    Names,
+    Summary,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum GrepColorArg {
+    HighlightOnly,
+    Combined,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum FailOnArg {
+    ParseError,
+    Truncated,
+    Ignored,
+    NoMatch,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum OutputModeArg {
+    Text,
+    JsonEnvelope,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum GrepReportArg {
+    Summary,
+    Json,
 }
 
 pub fn get_render_config_from(cli: &Cli) -> headson::RenderConfig {
     let template = base_template(cli);
-    let (indent_unit, space, newline) = whitespace_from(cli);
+    let (indent_unit, space, newline) =
+        whitespace(cli.compact, cli.no_space, cli.no_newline, &cli.indent);
     let color_mode = color_mode_from_flags(cli);
     let color_enabled = headson::resolve_color_enabled(color_mode);
     let (show_fileset_headers, fileset_tree, count_fileset_headers_in_budgets) =
@@ -237,8 +613,12 @@ pub fn get_render_config_from(cli: &Cli) -> headson::RenderConfig {
         primary_source_name: None,
         show_fileset_headers,
         fileset_tree,
+        fileset_tree_stats: cli.tree_stats,
         count_fileset_headers_in_budgets,
         grep_highlight: None,
+        grep_active: false,
+        grep_color: map_grep_color(cli.grep_color),
+        key_paths: cli.key_paths,
     }
 }
 
@@ -250,21 +630,25 @@ fn base_template(cli: &Cli) -> headson::OutputTemplate {
         }
         OutputFormat::Yaml => headson::OutputTemplate::Yaml,
         OutputFormat::Text => headson::OutputTemplate::Text,
+        OutputFormat::Markdown => headson::OutputTemplate::Markdown,
+        OutputFormat::Html => headson::OutputTemplate::Html,
+        OutputFormat::Gron => headson::OutputTemplate::Gron,
     }
 }
 
-fn whitespace_from(cli: &Cli) -> (String, String, String) {
-    let space = if cli.compact || cli.no_space { "" } else { " " }.to_string();
-    let newline = if cli.compact || cli.no_newline {
-        ""
-    } else {
-        "\n"
-    }
-    .to_string();
-    let indent_unit = if cli.compact {
+// Indent unit, space and newline for the whitespace flags.
+pub(crate) fn whitespace(
+    compact: bool,
+    no_space: bool,
+    no_newline: bool,
+    indent: &str,
+) -> (String, String, String) {
+    let space = if compact || no_space { "" } else { " " }.to_string();
+    let newline = if compact || no_newline { "" } else { "\n" }.to_string();
+    let indent_unit = if compact {
         String::new()
     } else {
-        cli.indent.clone()
+        indent.to_string()
     };
     (indent_unit, space, newline)
 }
@@ -272,7 +656,10 @@ fn whitespace_from(cli: &Cli) -> (String, String, String) {
 fn color_mode_from_flags(cli: &Cli) -> headson::ColorMode {
     if cli.color {
         headson::ColorMode::On
-    } else if cli.no_color {
+    } else if cli.no_color
+        || cli.output == OutputModeArg::JsonEnvelope
+        || matches!(cli.format, OutputFormat::Markdown | OutputFormat::Html)
+    {
         headson::ColorMode::Off
     } else {
         headson::ColorMode::Auto
@@ -290,6 +677,25 @@ pub fn map_style(s: StyleArg) -> headson::Style {
         StyleArg::Strict => headson::Style::Strict,
         StyleArg::Default => headson::Style::Default,
         StyleArg::Detailed => headson::Style::Detailed,
+        StyleArg::Annotated => headson::Style::Annotated,
+    }
+}
+
+pub(crate) fn map_sort(sort: SortArg) -> SortKey {
+    match sort {
+        SortArg::Frecency => SortKey::Frecency,
+        SortArg::Mtime => SortKey::Mtime,
+        SortArg::Path => SortKey::Path,
+        SortArg::Size => SortKey::Size,
+        SortArg::GitChurn => SortKey::GitChurn,
+        SortArg::GrepMatches => SortKey::GrepMatches,
+    }
+}
+
+fn map_grep_color(color: GrepColorArg) -> headson::GrepColor {
+    match color {
+        GrepColorArg::HighlightOnly => headson::GrepColor::HighlightOnly,
+        GrepColorArg::Combined => headson::GrepColor::Combined,
     }
 }
 
@@ -297,5 +703,7 @@ pub(crate) fn map_grep_show(show: GrepShowArg) -> headson::GrepShow {
     match show {
         GrepShowArg::Matching => headson::GrepShow::Matching,
         GrepShowArg::All => headson::GrepShow::All,
+        GrepShowArg::Names => headson::GrepShow::Names,
+        GrepShowArg::Summary => headson::GrepShow::Summary,
     }
 }
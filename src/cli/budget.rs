@@ -12,8 +12,20 @@ pub(crate) fn compute_effective(
     cli: &Cli,
     input_count: usize,
 ) -> EffectiveBudgets {
-    let mut per_slot = per_slot_budget(cli);
-    let explicit_global = explicit_global_budget(cli);
+    compute_effective_for(
+        per_slot_budget(cli.bytes, cli.chars, cli.lines),
+        explicit_global_budget(cli),
+        input_count,
+    )
+}
+
+// Effective budgets for explicit per-file and global caps, defaulting to
+// the per-file byte budget when neither is given.
+pub(crate) fn compute_effective_for(
+    mut per_slot: Option<Budget>,
+    explicit_global: Option<Budget>,
+    input_count: usize,
+) -> EffectiveBudgets {
     if per_slot.is_none() && explicit_global.is_none() {
         per_slot = Some(Budget {
             kind: BudgetKind::Bytes,
@@ -51,20 +63,24 @@ pub(crate) fn validate(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
-fn per_slot_budget(cli: &Cli) -> Option<Budget> {
-    cli.bytes
+pub(crate) fn per_slot_budget(
+    bytes: Option<usize>,
+    chars: Option<usize>,
+    lines: Option<usize>,
+) -> Option<Budget> {
+    bytes
         .map(|b| Budget {
             kind: BudgetKind::Bytes,
             cap: b,
         })
         .or_else(|| {
-            cli.chars.map(|c| Budget {
+            chars.map(|c| Budget {
                 kind: BudgetKind::Chars,
                 cap: c,
             })
         })
         .or_else(|| {
-            cli.lines.map(|l| Budget {
+            lines.map(|l| Budget {
                 kind: BudgetKind::Lines,
                 cap: l,
             })
@@ -105,13 +121,18 @@ pub(crate) fn build_priority_config(
     } else {
         ArraySamplerStrategy::Default
     };
-    PriorityConfig::for_budget(
+    let mut prio = PriorityConfig::for_budget(
         cli.string_cap,
         effective.per_file_for_priority,
         cli.tail,
         sampler,
         effective.line_only,
-    )
+    );
+    prio.expand_embedded = cli.expand_embedded;
+    prio.strict_parse = cli.strict_parse;
+    prio.recover_json = cli.recover;
+    prio.source_lines = cli.line_numbers;
+    prio
 }
 
 #[cfg(test)]
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::cli::git::GitScope;
+use crate::cli::walk::{
+    InputSet, WalkOptions, build_exclude_matcher, is_excluded, push_unique,
+};
+
+// `--changed`: keep only collected paths changed since `base`; with no other
+// sources, every changed file below the current directory becomes an input.
+// Returns whether the inputs were taken from the change list.
+pub(crate) fn limit_to_changed(
+    set: &mut InputSet,
+    base: &str,
+    walk: WalkOptions,
+    seeded: bool,
+) -> Result<bool> {
+    let scope = GitScope::discover(&set.cwd)?;
+    let changed = scope.changed_paths(base, None)?;
+    if seeded {
+        let cwd = set.cwd.clone();
+        set.paths
+            .retain(|p| changed.contains(&scope.worktree_path(&cwd, p)));
+        return Ok(false);
+    }
+    let excludes = build_exclude_matcher(walk)?;
+    let mut below: Vec<PathBuf> = changed
+        .into_iter()
+        .filter(|p| p.starts_with(scope.prefix()))
+        .map(|p| scope.display_path(&p))
+        .filter(|p| p.is_file() && walk.accepts_type(p))
+        .filter(|p| !is_excluded(&excludes, p))
+        .collect();
+    below.sort();
+    for path in &below {
+        push_unique(set, path);
+    }
+    Ok(true)
+}
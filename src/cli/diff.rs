@@ -0,0 +1,158 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::cli::args::{DiffArgs, DiffFormatArg, map_style, whitespace};
+use crate::cli::budget;
+use crate::cli::git::GitScope;
+use crate::cli::notice::Notice;
+use crate::cli::run::{IgnoreNotices, RunOutput};
+
+// One side of `hson diff`: a display name and its contents.
+struct Side {
+    name: String,
+    bytes: Vec<u8>,
+}
+
+/// `hson diff OLD NEW`: render only what changed between two documents.
+/// Each side is a file on disk or `REV:path` for a file in a git revision;
+/// with `--rev REV` a single path compares `REV:path` to the working tree.
+pub(crate) fn run_diff(args: &DiffArgs) -> Result<RunOutput> {
+    let cwd =
+        env::current_dir().context("failed to read current directory")?;
+    let (old, new) = match (args.rev.as_deref(), args.paths.as_slice()) {
+        (Some(rev), [path]) => {
+            (read_revision_side(&cwd, rev, path)?, read_side(&cwd, path)?)
+        }
+        (None, [old, new]) => (read_side(&cwd, old)?, read_side(&cwd, new)?),
+        (Some(_), _) => bail!("diff --rev REV takes exactly one path"),
+        (None, _) => bail!("diff takes exactly two inputs: OLD NEW"),
+    };
+    let old_format = diff_input_format(args, &old.name);
+    let new_format = diff_input_format(args, &new.name);
+    let mut cfg = render_config(args);
+    cfg.template = diff_template(args.format, new_format, cfg.style);
+    cfg.primary_source_name = Some(new.name.clone());
+    let effective = budget::compute_effective_for(
+        budget::per_slot_budget(args.bytes, args.chars, args.lines),
+        None,
+        1,
+    );
+    let prio = headson::PriorityConfig::for_budget(
+        args.string_cap,
+        effective.per_file_for_priority,
+        false,
+        headson::ArraySamplerStrategy::Default,
+        effective.line_only,
+    );
+    let cfg = budget::render_config_for_budgets(cfg, &effective);
+    let out = headson::headson_diff(
+        input_kind(old_format, old.bytes),
+        input_kind(new_format, new.bytes),
+        &cfg,
+        &prio,
+        effective.budgets,
+    )?;
+    let mut notices = IgnoreNotices::new();
+    if out.changes == 0 {
+        notices.push(Notice::info(format!(
+            "No differences: {} {}",
+            old.name, new.name
+        )));
+    }
+    Ok(RunOutput {
+        text: out.text,
+        notices,
+        truncated: out.truncated,
+    })
+}
+
+fn render_config(args: &DiffArgs) -> headson::RenderConfig {
+    let (indent_unit, space, newline) =
+        whitespace(args.compact, args.no_space, args.no_newline, &args.indent);
+    let color_mode = if args.color {
+        headson::ColorMode::On
+    } else if args.no_color {
+        headson::ColorMode::Off
+    } else {
+        headson::ColorMode::Auto
+    };
+    headson::RenderConfig {
+        indent_unit,
+        space,
+        newline,
+        color_mode,
+        color_enabled: headson::resolve_color_enabled(color_mode),
+        style: map_style(args.style),
+        ..headson::RenderConfig::default()
+    }
+}
+
+// A path that exists on disk wins over the `REV:path` reading, so file names
+// containing a colon keep working.
+fn read_side(cwd: &Path, spec: &Path) -> Result<Side> {
+    if cwd.join(spec).is_file() {
+        let bytes = fs::read(cwd.join(spec))
+            .with_context(|| format!("failed to read {}", spec.display()))?;
+        return Ok(Side {
+            name: spec.display().to_string(),
+            bytes,
+        });
+    }
+    let text = spec.to_string_lossy();
+    match text.split_once(':') {
+        Some((rev, path)) if !rev.is_empty() => {
+            read_revision_side(cwd, rev, Path::new(path))
+        }
+        _ => bail!("diff input not found: {}", spec.display()),
+    }
+}
+
+// Revision paths are relative to the current directory, like `--rev`.
+fn read_revision_side(cwd: &Path, rev: &str, path: &Path) -> Result<Side> {
+    let scope = GitScope::discover(cwd)?;
+    let repo_path: PathBuf = scope.repo_path(path);
+    let bytes = scope.read_file_at(rev, &repo_path)?;
+    Ok(Side {
+        name: format!("{rev}:{}", scope.display_path(&repo_path).display()),
+        bytes,
+    })
+}
+
+fn diff_input_format(args: &DiffArgs, name: &str) -> DiffFormatArg {
+    let lower = name.to_ascii_lowercase();
+    let by_name = if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+        DiffFormatArg::Yaml
+    } else {
+        DiffFormatArg::Json
+    };
+    match args.input_format {
+        None | Some(DiffFormatArg::Auto) => by_name,
+        Some(format) => format,
+    }
+}
+
+fn diff_template(
+    format: DiffFormatArg,
+    new_format: DiffFormatArg,
+    style: headson::Style,
+) -> headson::OutputTemplate {
+    match (format, new_format) {
+        (DiffFormatArg::Yaml, _)
+        | (DiffFormatArg::Auto, DiffFormatArg::Yaml) => {
+            headson::OutputTemplate::Yaml
+        }
+        _ => headson::map_json_template_for_style(style),
+    }
+}
+
+fn input_kind(format: DiffFormatArg, bytes: Vec<u8>) -> headson::InputKind {
+    match format {
+        DiffFormatArg::Yaml => headson::InputKind::Yaml(bytes),
+        DiffFormatArg::Auto | DiffFormatArg::Json => {
+            headson::InputKind::Json(bytes)
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::cli::args::Cli;
+use crate::cli::notice::{Notice, NoticeKind, exit_code};
+
+/// Bumped when a field changes meaning or goes away; new fields may be
+/// added without a bump.
+const ENVELOPE_VERSION: u32 = 1;
+
+/// `--output json-envelope`: the preview plus run metadata as one document.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    text: &'a str,
+    truncated: bool,
+    #[serde(flatten)]
+    stats: &'a headson::RenderStats,
+    ignored: Vec<IgnoredFile<'a>>,
+    notices: Vec<NoticeEntry<'a>>,
+    grep: Option<&'a headson::GrepReport>,
+    exit_code: u8,
+}
+
+#[derive(Serialize)]
+struct IgnoredFile<'a> {
+    file: Option<&'a str>,
+    reason: &'a str,
+}
+
+#[derive(Serialize)]
+struct NoticeEntry<'a> {
+    kind: &'static str,
+    message: &'a str,
+}
+
+/// Serialize the envelope for a run; `rendered` is None when no input was
+/// rendered (nothing matched the given paths).
+#[allow(
+    clippy::unwrap_used,
+    reason = "Serializing plain owned strings and counts cannot fail"
+)]
+pub(crate) fn render(
+    cli: &Cli,
+    rendered: Option<&headson::Rendered>,
+    notices: &[Notice],
+) -> String {
+    let empty = headson::RenderStats::default();
+    let truncated = rendered.is_some_and(|r| r.truncated);
+    let envelope = Envelope {
+        version: ENVELOPE_VERSION,
+        text: rendered.map_or("", |r| r.text.as_str()),
+        truncated,
+        stats: rendered.map_or(&empty, |r| &r.stats),
+        ignored: notices
+            .iter()
+            .filter(|n| n.kind == NoticeKind::Ignored)
+            .map(|n| IgnoredFile {
+                file: n.file.as_deref(),
+                reason: &n.message,
+            })
+            .collect(),
+        notices: notices
+            .iter()
+            .filter(|n| n.kind != NoticeKind::Ignored)
+            .map(|n| NoticeEntry {
+                kind: kind_str(n.kind),
+                message: &n.message,
+            })
+            .collect(),
+        grep: rendered.and_then(|r| r.grep_report.as_ref()),
+        exit_code: exit_code(notices, truncated, &cli.fail_on),
+    };
+    serde_json::to_string_pretty(&envelope).unwrap()
+}
+
+fn kind_str(kind: NoticeKind) -> &'static str {
+    match kind {
+        NoticeKind::Info => "info",
+        NoticeKind::ParseFailure => "parse-failure",
+        NoticeKind::Ignored => "ignored",
+        NoticeKind::NoMatch => "no-match",
+    }
+}
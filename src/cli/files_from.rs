@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::cli::args::Cli;
+use crate::cli::run::read_stdin;
+
+/// Paths listed by `--files-from`, empty when the flag is absent.
+pub(crate) fn read_listed(cli: &Cli) -> Result<Vec<PathBuf>> {
+    match &cli.files_from {
+        Some(source) => read_files_from(source, cli.null_separated),
+        None => Ok(Vec::new()),
+    }
+}
+
+// Read a path list from a file or `-` (stdin): one path per line, or
+// NUL-separated with `-0` (as produced by `find -print0`/`git ls-files -z`).
+fn read_files_from(
+    source: &Path,
+    null_separated: bool,
+) -> Result<Vec<PathBuf>> {
+    let bytes = if source == Path::new("-") {
+        read_stdin()?
+    } else {
+        std::fs::read(source).with_context(|| {
+            format!("failed to read --files-from list: {}", source.display())
+        })?
+    };
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    Ok(bytes
+        .split(|&b| b == separator)
+        .map(|entry| entry.strip_suffix(b"\r").unwrap_or(entry))
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect())
+}
+
+// Listed paths are raw bytes: `find -print0` output need not be UTF-8.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
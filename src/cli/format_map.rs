@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use ignore::overrides::{Override, OverrideBuilder};
+
+use crate::cli::args::{Cli, InputFormat, MapFormatArg, OutputFormat};
+
+/// Per-file format overrides from `--map-format GLOB=FORMAT`.
+pub(crate) struct FormatMap {
+    rules: Vec<(Override, MapFormatArg)>,
+}
+
+impl FormatMap {
+    /// Rules from the CLI, for single files and filesets alike. Empty when
+    /// `-i` or a non-auto `--format` picks the format instead.
+    pub(crate) fn from_cli(cli: &Cli) -> Result<Self> {
+        let rules = cli
+            .map_formats
+            .iter()
+            .map(|spec| parse_rule(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let detects = matches!(
+            cli.format,
+            OutputFormat::Auto
+                | OutputFormat::Markdown
+                | OutputFormat::Html
+                | OutputFormat::Gron
+        );
+        if cli.input_format.is_some() || !detects {
+            return Ok(Self { rules: Vec::new() });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Format forced for `name` by the last matching rule, if any.
+    pub(crate) fn format_for(&self, name: &str) -> Option<MapFormatArg> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| {
+                matcher.matched(Path::new(name), false).is_whitelist()
+            })
+            .map(|(_, format)| *format)
+    }
+
+    /// Fileset ingest kind for `name`: a mapped format, else the name-based
+    /// guess.
+    pub(crate) fn fileset_kind(
+        &self,
+        name: &str,
+    ) -> headson::FilesetInputKind {
+        match self.format_for(name) {
+            Some(MapFormatArg::Json) => headson::FilesetInputKind::Json,
+            Some(MapFormatArg::Yaml) => headson::FilesetInputKind::Yaml,
+            Some(MapFormatArg::Text) => headson::FilesetInputKind::Text {
+                atomic_lines: false,
+            },
+            Some(MapFormatArg::Code) => {
+                headson::FilesetInputKind::Text { atomic_lines: true }
+            }
+            None => headson::FilesetInputKind::from_filename(name),
+        }
+    }
+}
+
+impl MapFormatArg {
+    pub(crate) fn input_format(self) -> InputFormat {
+        match self {
+            MapFormatArg::Json => InputFormat::Json,
+            MapFormatArg::Yaml => InputFormat::Yaml,
+            MapFormatArg::Text | MapFormatArg::Code => InputFormat::Text,
+        }
+    }
+
+    pub(crate) fn template(
+        self,
+        style: headson::Style,
+    ) -> headson::OutputTemplate {
+        match self {
+            MapFormatArg::Json => headson::map_json_template_for_style(style),
+            MapFormatArg::Yaml => headson::OutputTemplate::Yaml,
+            MapFormatArg::Text => headson::OutputTemplate::Text,
+            MapFormatArg::Code => headson::OutputTemplate::Code,
+        }
+    }
+}
+
+fn parse_rule(spec: &str) -> Result<(Override, MapFormatArg)> {
+    let invalid = || {
+        format!(
+            "invalid --map-format '{spec}': expected GLOB=FORMAT with FORMAT one of json|yaml|text|code"
+        )
+    };
+    let Some((glob, raw)) = spec.rsplit_once('=') else {
+        bail!(invalid());
+    };
+    let Ok(format) = MapFormatArg::from_str(raw.trim(), true) else {
+        bail!(invalid());
+    };
+    if glob.is_empty() {
+        bail!(invalid());
+    }
+    let mut builder = OverrideBuilder::new(".");
+    builder.add(glob).with_context(invalid)?;
+    Ok((builder.build().with_context(invalid)?, format))
+}
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::{
+    Delta, DiffOptions, ObjectType, Oid, Repository, TreeWalkMode,
+    TreeWalkResult,
+};
+
+/// The repository around the current directory, used to read inputs from a
+/// revision (`--rev`) or to limit them to changed files (`--changed`).
+pub(crate) struct GitScope {
+    repo: Repository,
+    root: PathBuf,
+    // Current directory relative to the repository root.
+    prefix: PathBuf,
+}
+
+impl GitScope {
+    pub(crate) fn discover(cwd: &Path) -> Result<Self> {
+        let repo = Repository::discover(cwd).with_context(|| {
+            format!(
+                "--rev/--changed require a git repository: {}",
+                cwd.display()
+            )
+        })?;
+        let workdir = repo
+            .workdir()
+            .context("--rev/--changed do not support bare repositories")?;
+        let root = workdir
+            .canonicalize()
+            .unwrap_or_else(|_| workdir.to_path_buf());
+        let canonical_cwd =
+            cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+        let prefix = canonical_cwd
+            .strip_prefix(&root)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Ok(Self { repo, root, prefix })
+    }
+
+    pub(crate) fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+
+    /// Repository-relative form of a user-supplied path. The path need not
+    /// exist in the working tree, so `.`/`..` are resolved lexically.
+    pub(crate) fn repo_path(&self, path: &Path) -> PathBuf {
+        let joined = if path.is_absolute() {
+            path.strip_prefix(&self.root)
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|_| path.to_path_buf())
+        } else {
+            self.prefix.join(path)
+        };
+        normalize_lexically(&joined)
+    }
+
+    /// Repository-relative form of an existing working-tree path.
+    pub(crate) fn worktree_path(&self, cwd: &Path, path: &Path) -> PathBuf {
+        cwd.join(path)
+            .canonicalize()
+            .ok()
+            .and_then(|abs| {
+                abs.strip_prefix(&self.root).ok().map(PathBuf::from)
+            })
+            .unwrap_or_else(|| self.repo_path(path))
+    }
+
+    /// Path shown to the user: relative to the current directory when the
+    /// file lives below it, repository-relative otherwise.
+    pub(crate) fn display_path(&self, repo_path: &Path) -> PathBuf {
+        repo_path
+            .strip_prefix(&self.prefix)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| repo_path.to_path_buf())
+    }
+
+    /// Files added or modified since `base`, compared against `rev` when
+    /// given and against the working tree (including the index and
+    /// untracked, non-ignored files) otherwise.
+    pub(crate) fn changed_paths(
+        &self,
+        base: &str,
+        rev: Option<&str>,
+    ) -> Result<HashSet<PathBuf>> {
+        let base_tree = self.tree_at(base)?;
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true).recurse_untracked_dirs(true);
+        let diff = match rev {
+            Some(rev) => {
+                let tree = self.tree_at(rev)?;
+                self.repo.diff_tree_to_tree(
+                    Some(&base_tree),
+                    Some(&tree),
+                    Some(&mut opts),
+                )
+            }
+            None => self.repo.diff_tree_to_workdir_with_index(
+                Some(&base_tree),
+                Some(&mut opts),
+            ),
+        }
+        .with_context(|| format!("failed to diff against {base}"))?;
+        Ok(diff
+            .deltas()
+            .filter(|d| d.status() != Delta::Deleted)
+            .filter_map(|d| d.new_file().path().map(Path::to_path_buf))
+            .collect())
+    }
+
+    /// Every file in the tree of `rev`, in path order.
+    pub(crate) fn files_at(&self, rev: &str) -> Result<Vec<(PathBuf, Oid)>> {
+        let tree = self.tree_at(rev)?;
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    files.push((Path::new(dir).join(name), entry.id()));
+                }
+            }
+            TreeWalkResult::Ok
+        })
+        .with_context(|| format!("failed to walk tree of {rev}"))?;
+        Ok(files)
+    }
+
+    pub(crate) fn read_blob(&self, oid: Oid) -> Result<Vec<u8>> {
+        let blob = self
+            .repo
+            .find_blob(oid)
+            .with_context(|| format!("failed to read blob {oid}"))?;
+        Ok(blob.content().to_vec())
+    }
+
+    /// Contents of the file at repository path `repo_path` in `rev`.
+    pub(crate) fn read_file_at(
+        &self,
+        rev: &str,
+        repo_path: &Path,
+    ) -> Result<Vec<u8>> {
+        let entry =
+            self.tree_at(rev)?.get_path(repo_path).with_context(|| {
+                format!("Not found in {rev}: {}", repo_path.display())
+            })?;
+        self.read_blob(entry.id())
+    }
+
+    fn tree_at(&self, rev: &str) -> Result<git2::Tree<'_>> {
+        self.repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_tree())
+            .with_context(|| format!("unknown git revision: {rev}"))
+    }
+}
+
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_lexically;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn lexical_normalization_resolves_dots() {
+        assert_eq!(
+            normalize_lexically(Path::new("sub/./a/../b.json")),
+            PathBuf::from("sub/b.json")
+        );
+        assert_eq!(normalize_lexically(Path::new("./")), PathBuf::new());
+    }
+}
@@ -1,3 +1,13 @@
+pub mod allocation;
 pub mod args;
 pub mod budget;
+pub mod changed;
+pub mod diff;
+pub mod envelope;
+pub mod files_from;
+pub mod format_map;
+pub mod git;
+pub mod notice;
+pub mod revision;
 pub mod run;
+pub mod walk;
@@ -0,0 +1,113 @@
+use crate::cli::args::FailOnArg;
+
+/// Exit code for `--fail-on parse-error`.
+pub(crate) const EXIT_PARSE_FAILURE: u8 = 3;
+/// Exit code for `--fail-on ignored`.
+pub(crate) const EXIT_IGNORED: u8 = 4;
+/// Exit code for `--fail-on no-match`.
+pub(crate) const EXIT_NO_MATCH: u8 = 5;
+/// Exit code for `--fail-on truncated`.
+pub(crate) const EXIT_TRUNCATED: u8 = 6;
+
+/// What a notice reports; decides the exit status of the run.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum NoticeKind {
+    Info,
+    /// An input did not parse and was recovered or previewed as text.
+    ParseFailure,
+    /// An input was skipped (binary file, path missing from a revision).
+    Ignored,
+    /// Grep found no match.
+    NoMatch,
+}
+
+/// A message for stderr, printed after the preview.
+#[derive(Clone, Debug)]
+pub(crate) struct Notice {
+    pub kind: NoticeKind,
+    pub message: String,
+    // Input the notice is about, when it names a single one.
+    pub file: Option<String>,
+}
+
+impl Notice {
+    pub(crate) fn info(message: impl Into<String>) -> Self {
+        Self::new(NoticeKind::Info, message)
+    }
+
+    pub(crate) fn ignored(
+        file: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: Some(file.into()),
+            ..Self::new(NoticeKind::Ignored, message)
+        }
+    }
+
+    pub(crate) fn new(kind: NoticeKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            file: None,
+        }
+    }
+}
+
+/// Exit code for a run that produced `notices` and whose preview was
+/// `truncated` or not: a condition counts only when named in `--fail-on`.
+/// The lowest code wins when several apply.
+pub(crate) fn exit_code(
+    notices: &[Notice],
+    truncated: bool,
+    fail_on: &[FailOnArg],
+) -> u8 {
+    let has = |kind| notices.iter().any(|n| n.kind == kind);
+    let conditions = [
+        (
+            has(NoticeKind::ParseFailure),
+            FailOnArg::ParseError,
+            EXIT_PARSE_FAILURE,
+        ),
+        (has(NoticeKind::Ignored), FailOnArg::Ignored, EXIT_IGNORED),
+        (has(NoticeKind::NoMatch), FailOnArg::NoMatch, EXIT_NO_MATCH),
+        (truncated, FailOnArg::Truncated, EXIT_TRUNCATED),
+    ];
+    conditions
+        .into_iter()
+        .find(|(hit, flag, _)| *hit && fail_on.contains(flag))
+        .map_or(0, |(_, _, code)| code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_fail_only_when_requested() {
+        let notices = [Notice::ignored("a.bin", "Ignored binary file: a.bin")];
+        assert_eq!(exit_code(&notices, true, &[]), 0);
+        assert_eq!(exit_code(&notices, true, &[FailOnArg::Truncated]), 6);
+        assert_eq!(
+            exit_code(
+                &notices,
+                true,
+                &[FailOnArg::Truncated, FailOnArg::Ignored]
+            ),
+            4
+        );
+        assert_eq!(exit_code(&notices, true, &[FailOnArg::NoMatch]), 0);
+    }
+
+    #[test]
+    fn untruncated_runs_pass_fail_on_truncated() {
+        assert_eq!(exit_code(&[], false, &[FailOnArg::Truncated]), 0);
+    }
+
+    #[test]
+    fn parse_failures_fail_only_when_requested() {
+        let notices = [Notice::new(NoticeKind::ParseFailure, "bad.json")];
+        assert_eq!(exit_code(&notices, false, &[]), 0);
+        assert_eq!(exit_code(&notices, true, &[FailOnArg::ParseError]), 3);
+    }
+}
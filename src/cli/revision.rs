@@ -0,0 +1,155 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use content_inspector::{ContentType, inspect};
+
+use crate::cli::args::{Cli, SortArg};
+use crate::cli::files_from::read_listed;
+use crate::cli::git::GitScope;
+use crate::cli::notice::Notice;
+use crate::cli::run::{
+    IgnoreNotices, InputEntries, RunOutput, render_entries,
+};
+use crate::cli::walk::{
+    WalkOptions, build_exclude_matcher, build_overrides, is_excluded,
+};
+
+// Files selected from the tree of a revision (`--rev`): explicit paths (files
+// or directories), globs, or everything below the current directory.
+struct RevisionSelection<'a> {
+    explicit: Vec<PathBuf>,
+    globs: Option<ignore::overrides::Override>,
+    excludes: ignore::gitignore::Gitignore,
+    walk: WalkOptions<'a>,
+    prefix: PathBuf,
+}
+
+impl<'a> RevisionSelection<'a> {
+    fn new(cli: &'a Cli, scope: &GitScope) -> Result<Self> {
+        let walk = WalkOptions::from_cli(cli);
+        let listed = read_listed(cli)?;
+        let explicit = cli
+            .inputs
+            .iter()
+            .chain(&listed)
+            .map(|p| scope.repo_path(p))
+            .collect();
+        let globs = if cli.globs.is_empty() {
+            None
+        } else {
+            Some(build_overrides(&cli.globs, walk)?)
+        };
+        Ok(Self {
+            explicit,
+            globs,
+            excludes: build_exclude_matcher(walk)?,
+            walk,
+            prefix: scope.prefix().to_path_buf(),
+        })
+    }
+
+    // Explicit files bypass --type/--exclude, like they do on disk.
+    fn accepts(&self, repo_path: &Path, display: &Path) -> bool {
+        if self.explicit.iter().any(|e| e == repo_path) {
+            return true;
+        }
+        self.in_scope(repo_path, display)
+            && self.walk.accepts_type(display)
+            && !is_excluded(&self.excludes, display)
+    }
+
+    fn in_scope(&self, repo_path: &Path, display: &Path) -> bool {
+        if self.explicit.is_empty() && self.globs.is_none() {
+            return repo_path.starts_with(&self.prefix);
+        }
+        self.explicit.iter().any(|e| repo_path.starts_with(e))
+            || self
+                .globs
+                .as_ref()
+                .is_some_and(|o| o.matched(display, false).is_whitelist())
+    }
+
+    // A lone explicit file renders like a single positional file.
+    fn names_single_file(&self, files: &[(PathBuf, git2::Oid)]) -> bool {
+        match self.explicit.as_slice() {
+            [only] if self.globs.is_none() => {
+                files.iter().any(|(path, _)| path == only)
+            }
+            _ => false,
+        }
+    }
+
+    fn missing_notices(
+        &self,
+        files: &[(PathBuf, git2::Oid)],
+        rev: &str,
+    ) -> IgnoreNotices {
+        self.explicit
+            .iter()
+            .filter(|e| !files.iter().any(|(path, _)| path.starts_with(e)))
+            .map(|e| {
+                let path = e.display().to_string();
+                Notice::ignored(&path, format!("Not found in {rev}: {path}"))
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn run_from_revision(
+    cli: &Cli,
+    render_cfg: &headson::RenderConfig,
+    grep_cfg: &headson::GrepConfig,
+    rev: &str,
+) -> Result<RunOutput> {
+    let cwd =
+        env::current_dir().context("failed to read current directory")?;
+    let scope = GitScope::discover(&cwd)?;
+    let selection = RevisionSelection::new(cli, &scope)?;
+    let changed = cli
+        .changed
+        .as_deref()
+        .map(|base| scope.changed_paths(base, Some(rev)))
+        .transpose()?;
+    let files = scope.files_at(rev)?;
+    let mut notices = selection.missing_notices(&files, rev);
+    let mut entries: InputEntries = Vec::new();
+    for (repo_path, oid) in &files {
+        let display = scope.display_path(repo_path);
+        let unchanged =
+            changed.as_ref().is_some_and(|c| !c.contains(repo_path));
+        if unchanged || !selection.accepts(repo_path, &display) {
+            continue;
+        }
+        push_blob(&scope, *oid, &display, &mut entries, &mut notices)?;
+    }
+    // Tree order is path order (paths are unique, so reversing it is a true
+    // inverse); the other orderings describe the working tree and do not
+    // apply to a past revision, except grep matches, which the render sorts.
+    let fileset =
+        cli.tree || entries.len() > 1 || !selection.names_single_file(&files);
+    if fileset && cli.reverse && !matches!(cli.sort, SortArg::GrepMatches) {
+        entries.reverse();
+    }
+    render_entries(entries, notices, fileset, cli, render_cfg, grep_cfg)
+}
+
+fn push_blob(
+    scope: &GitScope,
+    oid: git2::Oid,
+    display: &Path,
+    entries: &mut InputEntries,
+    notices: &mut IgnoreNotices,
+) -> Result<()> {
+    let name = display.display().to_string();
+    let bytes = scope.read_blob(oid)?;
+    if matches!(inspect(&bytes), ContentType::BINARY) {
+        notices.push(Notice::ignored(
+            &name,
+            format!("Ignored binary file: {name}"),
+        ));
+    } else {
+        entries.push((name, bytes));
+    }
+    Ok(())
+}
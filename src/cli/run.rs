@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::env;
 use std::fs::File;
 use std::io::{self, Read};
@@ -6,19 +5,35 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 use content_inspector::{ContentType, inspect};
-use ignore::{WalkBuilder, overrides::OverrideBuilder};
 
+use crate::cli::allocation::fileset_layout;
 use crate::cli::args::{
-    Cli, InputFormat, OutputFormat, get_render_config_from,
+    Cli, GrepReportArg, InputFormat, OutputFormat, OutputModeArg, SortArg,
+    get_render_config_from, map_sort,
 };
 use crate::cli::budget;
+use crate::cli::changed::limit_to_changed;
+use crate::cli::files_from::read_listed;
+use crate::cli::format_map::FormatMap;
+use crate::cli::notice::{Notice, NoticeKind};
+use crate::cli::walk::{
+    InputSet, WalkOptions, collect_glob_matches, collect_paths, load_gitignore,
+};
 use crate::sorting::sort_paths_for_fileset;
 
 type InputEntry = (String, Vec<u8>);
-type InputEntries = Vec<InputEntry>;
-pub(crate) type IgnoreNotices = Vec<String>;
+pub(crate) type InputEntries = Vec<InputEntry>;
+pub(crate) type IgnoreNotices = Vec<Notice>;
+
+/// Outcome of a run: stdout text, notices for stderr, and whether the
+/// preview left part of the input out (for `--fail-on truncated`).
+pub(crate) struct RunOutput {
+    pub text: String,
+    pub notices: IgnoreNotices,
+    pub truncated: bool,
+}
 
-fn build_effective_configs(
+pub(crate) fn build_effective_configs(
     cli: &Cli,
     mut render_cfg: headson::RenderConfig,
     input_count: usize,
@@ -33,102 +48,225 @@ fn build_effective_configs(
     (render_cfg, prio, effective.budgets)
 }
 
-fn needs_fileset(cli: &Cli, inputs_len: usize) -> bool {
-    inputs_len > 1 || cli.tree
+fn needs_fileset(cli: &Cli, resolved: &ResolvedInputs) -> bool {
+    resolved.paths.len() > 1 || resolved.expanded_dir || cli.tree
 }
 
-pub(crate) fn run(cli: &Cli) -> Result<(String, IgnoreNotices)> {
+pub(crate) fn run(cli: &Cli) -> Result<RunOutput> {
     budget::validate(cli)?;
+    if cli.format == OutputFormat::Markdown && (cli.compact || cli.no_newline)
+    {
+        bail!("--format markdown needs newlines; drop --compact/--no-newline");
+    }
+    if cli.format == OutputFormat::Gron && (cli.compact || cli.no_newline) {
+        bail!("--format gron needs newlines; drop --compact/--no-newline");
+    }
+    if cli.format == OutputFormat::Gron && cli.line_numbers {
+        bail!("--format gron does not support --line-numbers");
+    }
+    if cli.format == OutputFormat::Html && cli.tree {
+        bail!("--format html does not support --tree");
+    }
+    if cli.format == OutputFormat::Html && (cli.line_numbers || cli.key_paths)
+    {
+        bail!("--format html does not support --line-numbers or --key-paths");
+    }
     let mut render_cfg = get_render_config_from(cli);
-    let grep_cfg = headson::build_grep_config(
+    let grep_cfg = headson::build_grep_config_with_predicates(
         cli.grep.as_deref(),
         cli.weak_grep.as_deref(),
         crate::cli::args::map_grep_show(cli.grep_show),
+        &cli.predicates,
     )?;
-    render_cfg.grep_highlight = grep_cfg.regex.clone();
-    let resolved_inputs = resolve_inputs(cli)?;
-    if resolved_inputs.is_empty() {
-        if !cli.globs.is_empty() {
-            return Ok((
-                String::new(),
-                vec!["No files matched provided globs".to_string()],
-            ));
-        }
-        if cli.tree {
-            bail!("--tree requires file inputs; stdin mode is not supported");
-        }
-        Ok((run_from_stdin(cli, &render_cfg, &grep_cfg)?, Vec::new()))
+    render_cfg.grep_highlight = grep_cfg.highlight_regex();
+    render_cfg.grep_active = grep_cfg.is_active();
+    if cli.grep_report.is_some() && !grep_cfg.is_active() {
+        bail!("--grep-report requires --grep, --weak-grep or --where");
+    }
+    if matches!(cli.sort, SortArg::GrepMatches) && !grep_cfg.is_active() {
+        bail!("--sort grep-matches requires --grep, --weak-grep or --where");
+    }
+    if let Some(rev) = &cli.rev {
+        return crate::cli::revision::run_from_revision(
+            cli,
+            &render_cfg,
+            &grep_cfg,
+            rev,
+        );
+    }
+    let resolved = resolve_inputs(cli)?;
+    if resolved.paths.is_empty() {
+        run_without_paths(cli, &render_cfg, &grep_cfg, resolved.notices)
     } else {
-        run_from_paths(cli, &render_cfg, &grep_cfg, &resolved_inputs)
+        run_from_paths(cli, &render_cfg, &grep_cfg, resolved)
     }
 }
 
-fn detect_fileset_input_kind(name: &str) -> headson::FilesetInputKind {
-    let lower = name.to_ascii_lowercase();
-    if lower.ends_with(".yaml") || lower.ends_with(".yml") {
-        headson::FilesetInputKind::Yaml
-    } else if lower.ends_with(".json") {
-        headson::FilesetInputKind::Json
-    } else {
-        let atomic = headson::extensions::is_code_like_name(&lower);
-        headson::FilesetInputKind::Text {
-            atomic_lines: atomic,
-        }
+// Nothing resolved to a file: read stdin as the document, unless the user
+// asked for paths (which then just produced nothing).
+fn run_without_paths(
+    cli: &Cli,
+    render_cfg: &headson::RenderConfig,
+    grep_cfg: &headson::GrepConfig,
+    mut notices: IgnoreNotices,
+) -> Result<RunOutput> {
+    if !cli.globs.is_empty() {
+        notices.push(Notice::info("No files matched provided globs"));
+        return Ok(nothing_rendered(cli, notices));
+    }
+    if cli.files_from.is_some() {
+        notices.push(Notice::info("No paths listed by --files-from"));
+        return Ok(nothing_rendered(cli, notices));
+    }
+    if cli.changed.is_some() {
+        notices.push(Notice::info("No changed files found"));
+        return Ok(nothing_rendered(cli, notices));
+    }
+    if !cli.inputs.is_empty() {
+        // Only empty directories were given; never fall back to stdin.
+        return Ok(nothing_rendered(cli, notices));
+    }
+    if cli.tree {
+        bail!("--tree requires file inputs; stdin mode is not supported");
     }
+    run_from_stdin(cli, render_cfg, grep_cfg, notices)
 }
 
 fn run_from_stdin(
     cli: &Cli,
     render_cfg: &headson::RenderConfig,
     grep_cfg: &headson::GrepConfig,
-) -> Result<String> {
+    mut notices: IgnoreNotices,
+) -> Result<RunOutput> {
     let input_bytes = read_stdin()?;
     let input_count = 1usize;
     let mut cfg = render_cfg.clone();
     cfg.template = resolve_effective_template_for_stdin(cli.format, cfg.style);
     let (cfg, prio, budgets) = build_effective_configs(cli, cfg, input_count);
     let chosen_input = cli.input_format.unwrap_or(InputFormat::Json);
-    render_single_input(
-        chosen_input,
-        input_bytes,
+    let rendered = headson::headson_with_report(
+        single_input_kind(
+            chosen_input,
+            text_mode_for(cfg.template),
+            input_bytes,
+        ),
         &cfg,
         &prio,
         grep_cfg,
         budgets,
-    )
+    )?;
+    push_render_notices(&rendered, grep_cfg, Some("stdin"), &mut notices);
+    Ok(finish_render(cli, rendered, notices))
+}
+
+// Stdout for a rendered run: the preview with any grep report, or the JSON
+// envelope.
+fn finish_render(
+    cli: &Cli,
+    rendered: headson::Rendered,
+    notices: IgnoreNotices,
+) -> RunOutput {
+    let truncated = rendered.truncated;
+    let text = match cli.output {
+        OutputModeArg::Text => apply_grep_report(cli, rendered),
+        OutputModeArg::JsonEnvelope => {
+            crate::cli::envelope::render(cli, Some(&rendered), &notices)
+        }
+    };
+    RunOutput {
+        text,
+        notices,
+        truncated,
+    }
+}
+
+// Result for a run where no input was rendered.
+fn nothing_rendered(cli: &Cli, notices: IgnoreNotices) -> RunOutput {
+    let text = match cli.output {
+        OutputModeArg::Text => String::new(),
+        OutputModeArg::JsonEnvelope => {
+            crate::cli::envelope::render(cli, None, &notices)
+        }
+    };
+    RunOutput {
+        text,
+        notices,
+        truncated: false,
+    }
+}
+
+// Attach the requested grep report: a footer after the preview, or the JSON
+// report in place of the preview.
+fn apply_grep_report(cli: &Cli, rendered: headson::Rendered) -> String {
+    let headson::Rendered {
+        text, grep_report, ..
+    } = rendered;
+    let Some(report) = grep_report else {
+        return text;
+    };
+    match cli.grep_report {
+        None => text,
+        Some(GrepReportArg::Json) => report.to_json(),
+        Some(GrepReportArg::Summary) => {
+            let footer = report.summary_lines().join("\n");
+            let body = text.trim_end_matches(['\n', '\r']);
+            if body.is_empty() {
+                footer
+            } else {
+                format!("{body}\n\n{footer}")
+            }
+        }
+    }
 }
 
 fn run_from_paths(
     cli: &Cli,
     render_cfg: &headson::RenderConfig,
     grep_cfg: &headson::GrepConfig,
-    inputs: &[PathBuf],
-) -> Result<(String, IgnoreNotices)> {
-    let sorted_inputs = if needs_fileset(cli, inputs.len()) && !cli.no_sort {
-        sort_paths_for_fileset(inputs)
+    resolved: ResolvedInputs,
+) -> Result<RunOutput> {
+    let fileset = needs_fileset(cli, &resolved);
+    let sorted_inputs = if fileset && !cli.no_sort {
+        sort_paths_for_fileset(
+            &resolved.paths,
+            map_sort(cli.sort),
+            cli.reverse,
+        )
     } else {
-        inputs.to_vec()
+        resolved.paths
     };
     if std::env::var_os("HEADSON_FRECEN_TRACE").is_some() {
         eprintln!("run_from_paths sorted_inputs={sorted_inputs:?}");
     }
-    let (entries, ignored) = ingest_paths(&sorted_inputs)?;
+    let (entries, mut ignored) = ingest_paths(&sorted_inputs)?;
+    ignored.extend(resolved.notices);
     if std::env::var_os("HEADSON_FRECEN_TRACE").is_some() {
         eprintln!(
             "run_from_paths ingested={:?}",
             entries.iter().map(|(n, _)| n).collect::<Vec<_>>()
         );
     }
-    if needs_fileset(cli, inputs.len()) {
-        return render_fileset(entries, ignored, cli, render_cfg, grep_cfg);
+    render_entries(entries, ignored, fileset, cli, render_cfg, grep_cfg)
+}
+
+pub(crate) fn render_entries(
+    entries: InputEntries,
+    notices: IgnoreNotices,
+    fileset: bool,
+    cli: &Cli,
+    render_cfg: &headson::RenderConfig,
+    grep_cfg: &headson::GrepConfig,
+) -> Result<RunOutput> {
+    if fileset {
+        return render_fileset(entries, notices, cli, render_cfg, grep_cfg);
     }
     if entries.is_empty() {
-        return Ok((String::new(), ignored));
+        return Ok(nothing_rendered(cli, notices));
     }
-    render_single_entry(entries, ignored, cli, render_cfg, grep_cfg)
+    render_single_entry(entries, notices, cli, render_cfg, grep_cfg)
 }
 
-fn read_stdin() -> Result<Vec<u8>> {
+pub(crate) fn read_stdin() -> Result<Vec<u8>> {
     let mut buf = Vec::new();
     io::stdin()
         .read_to_end(&mut buf)
@@ -176,205 +314,89 @@ fn ingest_paths(paths: &[PathBuf]) -> Result<(InputEntries, IgnoreNotices)> {
     let mut ignored: IgnoreNotices = Vec::new();
     for path in paths.iter() {
         let display = path.display().to_string();
-        if let Ok(meta) = std::fs::metadata(path) {
-            if meta.is_dir() {
-                ignored.push(format!("Ignored directory: {display}"));
-                continue;
-            }
-        }
         if let Some(bytes) = sniff_then_read_text(path)? {
             out.push((display, bytes))
         } else {
-            ignored.push(format!("Ignored binary file: {display}"));
+            ignored.push(Notice::ignored(
+                &display,
+                format!("Ignored binary file: {display}"),
+            ));
             continue;
         }
     }
     Ok((out, ignored))
 }
 
-fn resolve_inputs(cli: &Cli) -> Result<Vec<PathBuf>> {
+// Paths resolved from positional inputs (directories expanded) and globs.
+struct ResolvedInputs {
+    paths: Vec<PathBuf>,
+    notices: IgnoreNotices,
+    // A directory was walked; output is always a fileset so every file keeps
+    // its header, even when the walk found a single file.
+    expanded_dir: bool,
+}
+
+fn resolve_inputs(cli: &Cli) -> Result<ResolvedInputs> {
     let cwd =
         env::current_dir().context("failed to read current directory")?;
-    let mut seen_abs: HashSet<PathBuf> = HashSet::new();
-    let mut inputs: Vec<PathBuf> = Vec::new();
-
-    for path in &cli.inputs {
-        push_unique(&cwd, &mut seen_abs, &mut inputs, path);
-    }
+    let walk = WalkOptions::from_cli(cli);
+    let mut set = InputSet::new(cwd);
+    let mut notices: IgnoreNotices = Vec::new();
+    let listed = read_listed(cli)?;
+    let expanded_dir = collect_paths(
+        &mut set,
+        cli.inputs.iter().chain(&listed),
+        walk,
+        &mut notices,
+    )?;
 
     if !cli.globs.is_empty() {
-        let gitignore = load_gitignore(&cwd);
+        let gitignore = (!walk.no_ignore)
+            .then(|| load_gitignore(&set.cwd))
+            .flatten();
         collect_glob_matches(
             &cli.globs,
-            &cwd,
-            &mut seen_abs,
-            &mut inputs,
+            &mut set,
             gitignore.as_ref(),
             cli.no_sort,
+            walk,
         )?;
     }
 
-    Ok(inputs)
-}
-
-fn push_unique(
-    cwd: &Path,
-    seen_abs: &mut HashSet<PathBuf>,
-    inputs: &mut Vec<PathBuf>,
-    path: &Path,
-) {
-    let abs = if path.is_absolute() {
-        path.to_path_buf()
-    } else {
-        cwd.join(path)
+    let seeded =
+        !(cli.inputs.is_empty() && listed.is_empty() && cli.globs.is_empty());
+    let from_changed = match &cli.changed {
+        Some(base) => limit_to_changed(&mut set, base, walk, seeded)?,
+        None => false,
     };
-    if seen_abs.insert(abs) {
-        inputs.push(path.to_path_buf());
-    }
-}
-
-fn relativize<'a>(path: &'a Path, cwd: &Path) -> &'a Path {
-    path.strip_prefix(cwd)
-        .or_else(|_| path.strip_prefix("."))
-        .unwrap_or(path)
-}
-
-fn load_gitignore(cwd: &Path) -> Option<ignore::gitignore::Gitignore> {
-    let gi_path = cwd.join(".gitignore");
-    let (gi, err) = ignore::gitignore::Gitignore::new(gi_path);
-    if err.is_none() { Some(gi) } else { None }
-}
-
-fn collect_glob_matches(
-    patterns: &[String],
-    cwd: &Path,
-    seen_abs: &mut HashSet<PathBuf>,
-    inputs: &mut Vec<PathBuf>,
-    gitignore: Option<&ignore::gitignore::Gitignore>,
-    no_sort: bool,
-) -> Result<()> {
-    if no_sort {
-        // Expand each glob in the order provided so --no-sort preserves user intent.
-        for pattern in patterns {
-            let mut overrides = OverrideBuilder::new(".");
-            overrides
-                .add(pattern)
-                .with_context(|| format!("invalid glob pattern: {pattern}"))?;
-            let overrides = overrides
-                .build()
-                .context("failed to compile glob overrides")?;
-            let mut walker = WalkBuilder::new(".");
-            // Still sort within each glob for deterministic traversal.
-            configure_walker(&mut walker, overrides, true);
-            collect_from_walker(&walker, cwd, seen_abs, inputs, gitignore)?;
-        }
-        return Ok(());
-    }
-
-    let mut overrides = OverrideBuilder::new(".");
-    for pattern in patterns {
-        overrides
-            .add(pattern)
-            .with_context(|| format!("invalid glob pattern: {pattern}"))?;
-    }
-    let overrides = overrides
-        .build()
-        .context("failed to compile glob overrides")?;
 
-    let mut walker = WalkBuilder::new(".");
-    configure_walker(&mut walker, overrides, true);
-    collect_from_walker(&walker, cwd, seen_abs, inputs, gitignore)?;
-    Ok(())
+    Ok(ResolvedInputs {
+        paths: set.paths,
+        notices,
+        expanded_dir: expanded_dir || from_changed,
+    })
 }
 
-fn configure_walker(
-    walker: &mut WalkBuilder,
-    overrides: ignore::overrides::Override,
-    should_sort: bool,
-) {
-    walker.overrides(overrides);
-    walker.git_ignore(true);
-    walker.git_global(true);
-    walker.git_exclude(true);
-    walker.require_git(false);
-    walker.add_custom_ignore_filename(".gitignore");
-    if should_sort {
-        // Deterministic expansion keeps traversal stable; fileset ordering is still
-        // resolved later (mtime/frecency or --no-sort) on the collected list.
-        walker.sort_by_file_name(std::cmp::Ord::cmp);
+fn text_mode_for(template: headson::OutputTemplate) -> headson::TextMode {
+    if matches!(template, headson::OutputTemplate::Code) {
+        headson::TextMode::CodeLike
     } else {
-        // Keep discovery order stable for --no-sort: single-threaded walk and no sorting.
-        walker.threads(1);
-        walker.sort_by_file_name(|_, _| std::cmp::Ordering::Equal);
-    }
-}
-
-fn collect_from_walker(
-    walker: &WalkBuilder,
-    cwd: &Path,
-    seen_abs: &mut HashSet<PathBuf>,
-    inputs: &mut Vec<PathBuf>,
-    gitignore: Option<&ignore::gitignore::Gitignore>,
-) -> Result<()> {
-    for dent in walker.build() {
-        let dir_entry = dent?;
-        if !dir_entry
-            .file_type()
-            .map(|ft| ft.is_file())
-            .unwrap_or(false)
-        {
-            continue;
-        }
-        let path = dir_entry.into_path();
-        let rel = relativize(&path, cwd).to_path_buf();
-        if gitignore.is_some_and(|gi| {
-            gi.matched_path_or_any_parents(&rel, false).is_ignore()
-        }) {
-            continue;
-        }
-        push_unique(cwd, seen_abs, inputs, &rel);
+        headson::TextMode::Plain
     }
-    Ok(())
 }
 
-fn render_single_input(
+fn single_input_kind(
     input_format: InputFormat,
+    text_mode: headson::TextMode,
     bytes: Vec<u8>,
-    cfg: &headson::RenderConfig,
-    prio: &headson::PriorityConfig,
-    grep_cfg: &headson::GrepConfig,
-    budgets: headson::Budgets,
-) -> Result<String> {
-    let text_mode = if matches!(cfg.template, headson::OutputTemplate::Code) {
-        headson::TextMode::CodeLike
-    } else {
-        headson::TextMode::Plain
-    };
+) -> headson::InputKind {
     match input_format {
-        InputFormat::Json => headson::headson(
-            headson::InputKind::Json(bytes),
-            cfg,
-            prio,
-            grep_cfg,
-            budgets,
-        ),
-        InputFormat::Yaml => headson::headson(
-            headson::InputKind::Yaml(bytes),
-            cfg,
-            prio,
-            grep_cfg,
-            budgets,
-        ),
-        InputFormat::Text => headson::headson(
-            headson::InputKind::Text {
-                bytes,
-                mode: text_mode,
-            },
-            cfg,
-            prio,
-            grep_cfg,
-            budgets,
-        ),
+        InputFormat::Json => headson::InputKind::Json(bytes),
+        InputFormat::Yaml => headson::InputKind::Yaml(bytes),
+        InputFormat::Text => headson::InputKind::Text {
+            bytes,
+            mode: text_mode,
+        },
     }
 }
 
@@ -388,6 +410,20 @@ fn resolve_effective_template_for_stdin(
         }
         OutputFormat::Yaml => headson::OutputTemplate::Yaml,
         OutputFormat::Text => headson::OutputTemplate::Text,
+        OutputFormat::Markdown => headson::OutputTemplate::Markdown,
+        OutputFormat::Html => headson::OutputTemplate::Html,
+        OutputFormat::Gron => headson::OutputTemplate::Gron,
+    }
+}
+
+// Markdown fences whatever `auto` renders, HTML lays it out as a page and
+// gron flattens it, so inputs resolve as for `auto`.
+fn detection_format(fmt: OutputFormat) -> OutputFormat {
+    match fmt {
+        OutputFormat::Markdown | OutputFormat::Html | OutputFormat::Gron => {
+            OutputFormat::Auto
+        }
+        other => other,
     }
 }
 
@@ -400,14 +436,17 @@ fn resolve_effective_template_for_single(
         OutputFormat::Json => headson::map_json_template_for_style(style),
         OutputFormat::Yaml => headson::OutputTemplate::Yaml,
         OutputFormat::Text => headson::OutputTemplate::Text,
-        OutputFormat::Auto => {
-            if lower_name.ends_with(".yaml") || lower_name.ends_with(".yml") {
-                headson::OutputTemplate::Yaml
-            } else if lower_name.ends_with(".json") {
-                headson::map_json_template_for_style(style)
-            } else {
+        OutputFormat::Auto
+        | OutputFormat::Markdown
+        | OutputFormat::Html
+        | OutputFormat::Gron => {
+            match headson::Format::from_filename(lower_name) {
+                headson::Format::Yaml => headson::OutputTemplate::Yaml,
+                headson::Format::Json => {
+                    headson::map_json_template_for_style(style)
+                }
                 // Unknown extension: prefer text template.
-                headson::OutputTemplate::Text
+                headson::Format::Unknown => headson::OutputTemplate::Text,
             }
         }
     }
@@ -419,99 +458,172 @@ fn render_fileset(
     cli: &Cli,
     render_cfg: &headson::RenderConfig,
     grep_cfg: &headson::GrepConfig,
-) -> Result<(String, IgnoreNotices)> {
-    if !matches!(cli.format, OutputFormat::Auto) {
-        bail!(
-            "--format cannot be customized for filesets; remove it or set to auto"
-        );
-    }
+) -> Result<RunOutput> {
+    let template = match cli.format {
+        OutputFormat::Auto => headson::OutputTemplate::Auto,
+        OutputFormat::Markdown => headson::OutputTemplate::Markdown,
+        OutputFormat::Html => headson::OutputTemplate::Html,
+        _ => bail!(
+            "--format cannot be customized for filesets; remove it or set to auto, markdown or html"
+        ),
+    };
     let mut cfg = render_cfg.clone();
-    cfg.template = headson::OutputTemplate::Auto;
+    cfg.template = template;
     let input_count = entries.len().max(1);
     let (cfg, prio, budgets) = build_effective_configs(cli, cfg, input_count);
+    let layout = fileset_layout(cli, &entries, grep_cfg)?;
+    let format_map = FormatMap::from_cli(cli)?;
     let files: Vec<headson::FilesetInput> = entries
         .into_iter()
-        .map(|(name, bytes)| {
-            let kind = detect_fileset_input_kind(&name);
-            headson::FilesetInput { name, bytes, kind }
+        .map(|(name, bytes)| headson::FilesetInput {
+            kind: format_map.fileset_kind(&name),
+            name,
+            bytes,
         })
         .collect();
-    let out = headson::headson(
-        headson::InputKind::Fileset(files),
+    let rendered = headson::headson_with_report(
+        headson::InputKind::FilesetWithLayout(files, layout),
         &cfg,
         &prio,
         grep_cfg,
         budgets,
     )?;
-    if grep_cfg.regex.is_some()
-        && matches!(grep_cfg.show, headson::GrepShow::Matching)
+    push_render_notices(&rendered, grep_cfg, None, &mut notices);
+    Ok(finish_render(cli, rendered, notices))
+}
+
+// Notices about the rendered result: inputs that failed to parse and grep
+// misses. `source` names a single input.
+fn push_render_notices(
+    rendered: &headson::Rendered,
+    grep_cfg: &headson::GrepConfig,
+    source: Option<&str>,
+    notices: &mut IgnoreNotices,
+) {
+    push_parse_notices(rendered, source, notices);
+    let no_match = grep_cfg.is_active()
         && !grep_cfg.weak
-        && out.trim().is_empty()
-    {
-        notices.push("No grep matches found".to_string());
+        && rendered.grep_report.as_ref().is_some_and(|r| r.total == 0);
+    if no_match {
+        notices.push(Notice::new(
+            NoticeKind::NoMatch,
+            no_match_message(grep_cfg),
+        ));
     }
-    Ok((out, notices))
+}
+
+// Names the filters that found nothing: `--grep`, `--where`, or both.
+fn no_match_message(grep_cfg: &headson::GrepConfig) -> &'static str {
+    match (grep_cfg.regex.is_some(), grep_cfg.predicates.is_empty()) {
+        (true, true) => "No grep matches found",
+        (false, _) => "No --where matches found",
+        (true, false) => "No grep or --where matches found",
+    }
+}
+
+fn push_parse_notices(
+    rendered: &headson::Rendered,
+    source: Option<&str>,
+    notices: &mut IgnoreNotices,
+) {
+    let shown_as_text = rendered.parse_failures.iter().map(|failure| {
+        format!(
+            "Could not parse {}; showing it as text\n{}",
+            failure.name, failure.error
+        )
+    });
+    let recovered = rendered.recovered.iter().map(|note| match source {
+        Some(name) => format!("Recovered {name}: {note}"),
+        None => format!("Recovered {note}"),
+    });
+    notices.extend(
+        shown_as_text
+            .chain(recovered)
+            .map(|message| Notice::new(NoticeKind::ParseFailure, message)),
+    );
 }
 
 fn render_single_entry(
     mut entries: InputEntries,
-    notices: IgnoreNotices,
+    mut notices: IgnoreNotices,
     cli: &Cli,
     render_cfg: &headson::RenderConfig,
     grep_cfg: &headson::GrepConfig,
-) -> Result<(String, IgnoreNotices)> {
+) -> Result<RunOutput> {
     let (name, bytes) = entries
         .pop()
         .expect("single-entry render expects one ingested input");
-    let lower = name.to_ascii_lowercase();
-    let chosen_input = select_input_format(cli, &lower);
     let mut cfg = render_cfg.clone();
-    cfg.template =
-        resolve_effective_template_for_single(cli.format, cfg.style, &lower);
-    cfg.primary_source_name = Some(name);
-    let (cfg, prio, budgets) = build_effective_configs(cli, cfg, 1usize);
-    let is_auto = matches!(cli.format, OutputFormat::Auto);
-    let mut cfg_for_render = cfg;
-    if let InputFormat::Text = chosen_input {
-        let is_code = headson::extensions::is_code_like_name(
-            cfg_for_render
-                .primary_source_name
-                .as_deref()
-                .unwrap_or_default(),
-        );
-        if is_auto
-            && is_code
-            && matches!(cfg_for_render.template, headson::OutputTemplate::Text)
-        {
-            cfg_for_render.template = headson::OutputTemplate::Code;
-        }
-    }
-    let out = render_single_input(
-        chosen_input,
-        bytes,
+    let (chosen_input, template) = single_entry_format(cli, &name, cfg.style)?;
+    // Gron flattens code to its lines rather than nested blocks.
+    let text_mode = match cli.format {
+        OutputFormat::Gron => headson::TextMode::Plain,
+        _ => text_mode_for(template),
+    };
+    cfg.template = match cli.format {
+        OutputFormat::Markdown => headson::OutputTemplate::Markdown,
+        OutputFormat::Html => headson::OutputTemplate::Html,
+        OutputFormat::Gron => headson::OutputTemplate::Gron,
+        _ => template,
+    };
+    cfg.primary_source_name = Some(name.clone());
+    let (cfg_for_render, prio, budgets) =
+        build_effective_configs(cli, cfg, 1usize);
+    let rendered = headson::headson_with_report(
+        single_input_kind(chosen_input, text_mode, bytes),
         &cfg_for_render,
         &prio,
         grep_cfg,
         budgets,
-    )?;
-    Ok((out, notices))
+    )
+    .map_err(|err| headson::attribute_parse_error(err, &name))?;
+    push_render_notices(&rendered, grep_cfg, Some(&name), &mut notices);
+    Ok(finish_render(cli, rendered, notices))
 }
 
-fn select_input_format(cli: &Cli, lower_name: &str) -> InputFormat {
-    let is_yaml_ext =
-        lower_name.ends_with(".yaml") || lower_name.ends_with(".yml");
-    match cli.format {
-        OutputFormat::Auto => {
-            if let Some(fmt) = cli.input_format {
-                fmt
-            } else if is_yaml_ext {
-                InputFormat::Yaml
-            } else if lower_name.ends_with(".json") {
-                InputFormat::Json
-            } else {
-                InputFormat::Text
-            }
-        }
+// Ingest format and template for a single file: a `--map-format` rule when
+// formats are left on auto, else detection from the file name.
+fn single_entry_format(
+    cli: &Cli,
+    name: &str,
+    style: headson::Style,
+) -> Result<(InputFormat, headson::OutputTemplate)> {
+    let detect = detection_format(cli.format);
+    let is_auto = matches!(detect, OutputFormat::Auto);
+    if let Some(format) = FormatMap::from_cli(cli)?.format_for(name) {
+        return Ok((format.input_format(), format.template(style)));
+    }
+    let lower = name.to_ascii_lowercase();
+    let input = select_input_format(cli, detect, &lower);
+    let template =
+        resolve_effective_template_for_single(detect, style, &lower);
+    let is_code = headson::extensions::is_code_like_name(name);
+    if is_auto
+        && is_code
+        && matches!(input, InputFormat::Text)
+        && matches!(template, headson::OutputTemplate::Text)
+    {
+        return Ok((input, headson::OutputTemplate::Code));
+    }
+    Ok((input, template))
+}
+
+fn select_input_format(
+    cli: &Cli,
+    format: OutputFormat,
+    lower_name: &str,
+) -> InputFormat {
+    match format {
+        OutputFormat::Auto
+        | OutputFormat::Markdown
+        | OutputFormat::Html
+        | OutputFormat::Gron => cli.input_format.unwrap_or(
+            match headson::Format::from_filename(lower_name) {
+                headson::Format::Yaml => InputFormat::Yaml,
+                headson::Format::Json => InputFormat::Json,
+                headson::Format::Unknown => InputFormat::Text,
+            },
+        ),
         OutputFormat::Json => cli.input_format.unwrap_or(InputFormat::Json),
         OutputFormat::Yaml => cli.input_format.unwrap_or(InputFormat::Yaml),
         OutputFormat::Text => cli.input_format.unwrap_or(InputFormat::Text),
@@ -535,10 +647,10 @@ mod tests {
         let cli =
             Cli::parse_from(["hson", "-i", "text", path.to_str().unwrap()]);
 
-        let (out, notices) = run(&cli).expect("run succeeds with text ingest");
-        assert!(notices.is_empty());
+        let out = run(&cli).expect("run succeeds with text ingest");
+        assert!(out.notices.is_empty());
         assert!(
-            out.contains("not json"),
+            out.text.contains("not json"),
             "should treat .json as text when -i text is passed"
         );
     }
@@ -551,11 +663,10 @@ mod tests {
 
         let cli = Cli::parse_from(["hson", path.to_str().unwrap()]);
 
-        let (out, notices) =
-            run(&cli).expect("run succeeds with default ingest");
-        assert!(notices.is_empty());
+        let out = run(&cli).expect("run succeeds with default ingest");
+        assert!(out.notices.is_empty());
         assert!(
-            out.contains("\"a\"") || out.contains("a"),
+            out.text.contains("\"a\"") || out.text.contains("a"),
             "auto mode should still treat .json as json when -i is absent"
         );
     }
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::{WalkBuilder, overrides::OverrideBuilder};
+
+use crate::cli::args::{Cli, FileTypeArg};
+use crate::cli::notice::Notice;
+use crate::cli::run::IgnoreNotices;
+
+// Traversal controls shared by directory walks and glob expansion, mirroring
+// ripgrep's --max-depth/--hidden/--no-ignore/--glob '!..'/--type.
+#[derive(Copy, Clone)]
+pub(crate) struct WalkOptions<'a> {
+    max_depth: Option<usize>,
+    hidden: bool,
+    pub no_ignore: bool,
+    excludes: &'a [String],
+    types: &'a [FileTypeArg],
+}
+
+impl<'a> WalkOptions<'a> {
+    pub(crate) fn from_cli(cli: &'a Cli) -> Self {
+        Self {
+            max_depth: cli.max_depth,
+            hidden: cli.hidden,
+            no_ignore: cli.no_ignore,
+            excludes: &cli.excludes,
+            types: &cli.types,
+        }
+    }
+
+    // Files pass when no --type is given or any requested type matches.
+    pub(crate) fn accepts_type(&self, path: &Path) -> bool {
+        if self.types.is_empty() {
+            return true;
+        }
+        let name = path.to_string_lossy();
+        self.types.iter().any(|t| matches_file_type(&name, *t))
+    }
+}
+
+fn matches_file_type(name: &str, file_type: FileTypeArg) -> bool {
+    match file_type {
+        FileTypeArg::Json => {
+            headson::Format::from_filename(name) == headson::Format::Json
+        }
+        FileTypeArg::Yaml => {
+            headson::Format::from_filename(name) == headson::Format::Yaml
+        }
+        FileTypeArg::Code => headson::extensions::is_code_like_name(name),
+    }
+}
+
+// Compile include globs plus `--exclude` globs (as negated overrides).
+pub(crate) fn build_overrides(
+    includes: &[String],
+    walk: WalkOptions,
+) -> Result<ignore::overrides::Override> {
+    let mut overrides = OverrideBuilder::new(".");
+    for pattern in includes {
+        overrides
+            .add(pattern)
+            .with_context(|| format!("invalid glob pattern: {pattern}"))?;
+    }
+    for pattern in walk.excludes {
+        overrides
+            .add(&format!("!{pattern}"))
+            .with_context(|| format!("invalid exclude pattern: {pattern}"))?;
+    }
+    overrides
+        .build()
+        .context("failed to compile glob overrides")
+}
+
+// Accumulates inputs in discovery order, skipping paths seen before.
+pub(crate) struct InputSet {
+    pub cwd: PathBuf,
+    seen_abs: HashSet<PathBuf>,
+    pub paths: Vec<PathBuf>,
+}
+
+impl InputSet {
+    pub(crate) fn new(cwd: PathBuf) -> Self {
+        Self {
+            cwd,
+            seen_abs: HashSet::new(),
+            paths: Vec::new(),
+        }
+    }
+}
+
+// `--exclude` globs as a gitignore matcher, for paths that are not walked.
+pub(crate) fn build_exclude_matcher(
+    walk: WalkOptions,
+) -> Result<ignore::gitignore::Gitignore> {
+    let mut builder = ignore::gitignore::GitignoreBuilder::new(".");
+    for pattern in walk.excludes {
+        builder
+            .add_line(None, pattern)
+            .with_context(|| format!("invalid exclude pattern: {pattern}"))?;
+    }
+    builder
+        .build()
+        .context("failed to compile exclude patterns")
+}
+
+pub(crate) fn is_excluded(
+    excludes: &ignore::gitignore::Gitignore,
+    path: &Path,
+) -> bool {
+    !path.has_root()
+        && excludes
+            .matched_path_or_any_parents(path, false)
+            .is_ignore()
+}
+
+// Push explicit paths, walking directories. Returns whether any directory
+// was expanded.
+pub(crate) fn collect_paths<'p>(
+    set: &mut InputSet,
+    paths: impl Iterator<Item = &'p PathBuf>,
+    walk: WalkOptions,
+    notices: &mut IgnoreNotices,
+) -> Result<bool> {
+    let mut expanded_dir = false;
+    for path in paths {
+        if !path.is_dir() {
+            push_unique(set, path);
+            continue;
+        }
+        expanded_dir = true;
+        if collect_directory(set, path, walk)? == 0 {
+            notices.push(Notice::info(format!(
+                "No files found in directory: {}",
+                path.display()
+            )));
+        }
+    }
+    Ok(expanded_dir)
+}
+
+pub(crate) fn push_unique(set: &mut InputSet, path: &Path) {
+    let abs = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        set.cwd.join(path)
+    };
+    if set.seen_abs.insert(abs) {
+        set.paths.push(path.to_path_buf());
+    }
+}
+
+fn relativize<'a>(path: &'a Path, cwd: &Path) -> &'a Path {
+    path.strip_prefix(cwd)
+        .or_else(|_| path.strip_prefix("."))
+        .unwrap_or(path)
+}
+
+pub(crate) fn load_gitignore(
+    cwd: &Path,
+) -> Option<ignore::gitignore::Gitignore> {
+    let gi_path = cwd.join(".gitignore");
+    let (gi, err) = ignore::gitignore::Gitignore::new(gi_path);
+    if err.is_none() { Some(gi) } else { None }
+}
+
+// Walk a directory input; ignore files are picked up from the directory and
+// its parents by the walker itself. Returns the number of files found.
+fn collect_directory(
+    set: &mut InputSet,
+    dir: &Path,
+    walk: WalkOptions,
+) -> Result<usize> {
+    let mut walker = WalkBuilder::new(dir);
+    configure_walker(&mut walker, build_overrides(&[], walk)?, true, walk);
+    collect_from_walker(&walker, set, None, walk)
+}
+
+pub(crate) fn collect_glob_matches(
+    patterns: &[String],
+    set: &mut InputSet,
+    gitignore: Option<&ignore::gitignore::Gitignore>,
+    no_sort: bool,
+    walk: WalkOptions,
+) -> Result<()> {
+    if no_sort {
+        // Expand each glob in the order provided so --no-sort preserves user intent.
+        for pattern in patterns {
+            let overrides =
+                build_overrides(std::slice::from_ref(pattern), walk)?;
+            let mut walker = WalkBuilder::new(".");
+            // Still sort within each glob for deterministic traversal.
+            configure_walker(&mut walker, overrides, true, walk);
+            collect_from_walker(&walker, set, gitignore, walk)?;
+        }
+        return Ok(());
+    }
+
+    let overrides = build_overrides(patterns, walk)?;
+    let mut walker = WalkBuilder::new(".");
+    configure_walker(&mut walker, overrides, true, walk);
+    collect_from_walker(&walker, set, gitignore, walk)?;
+    Ok(())
+}
+
+fn configure_walker(
+    walker: &mut WalkBuilder,
+    overrides: ignore::overrides::Override,
+    should_sort: bool,
+    walk: WalkOptions,
+) {
+    walker.overrides(overrides);
+    walker.max_depth(walk.max_depth);
+    walker.hidden(!walk.hidden);
+    let respect_ignores = !walk.no_ignore;
+    walker.ignore(respect_ignores);
+    walker.parents(respect_ignores);
+    walker.git_ignore(respect_ignores);
+    walker.git_global(respect_ignores);
+    walker.git_exclude(respect_ignores);
+    walker.require_git(false);
+    if respect_ignores {
+        walker.add_custom_ignore_filename(".gitignore");
+    }
+    if should_sort {
+        // Deterministic expansion keeps traversal stable; fileset ordering is still
+        // resolved later (mtime/frecency or --no-sort) on the collected list.
+        walker.sort_by_file_name(std::cmp::Ord::cmp);
+    } else {
+        // Keep discovery order stable for --no-sort: single-threaded walk and no sorting.
+        walker.threads(1);
+        walker.sort_by_file_name(|_, _| std::cmp::Ordering::Equal);
+    }
+}
+
+fn collect_from_walker(
+    walker: &WalkBuilder,
+    set: &mut InputSet,
+    gitignore: Option<&ignore::gitignore::Gitignore>,
+    walk: WalkOptions,
+) -> Result<usize> {
+    let mut found = 0usize;
+    for dent in walker.build() {
+        let dir_entry = dent?;
+        if !dir_entry
+            .file_type()
+            .map(|ft| ft.is_file())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        let path = dir_entry.into_path();
+        if !walk.accepts_type(&path) {
+            continue;
+        }
+        let rel = relativize(&path, &set.cwd).to_path_buf();
+        if gitignore.is_some_and(|gi| {
+            gi.matched_path_or_any_parents(&rel, false).is_ignore()
+        }) {
+            continue;
+        }
+        found += 1;
+        push_unique(set, &rel);
+    }
+    Ok(found)
+}
@@ -170,12 +170,14 @@ struct FilesetItemDbg {
     rendered_empty: bool,
 }
 
-fn template_str_for_root(
+pub(crate) fn template_str_for_root(
     order: &PriorityOrder,
     cfg: &crate::RenderConfig,
 ) -> &'static str {
     use crate::serialization::types::OutputTemplate as T;
-    if order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset) {
+    if !matches!(cfg.template, T::Markdown | T::Html)
+        && order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset)
+    {
         // In filesets root, per-file templates may vary under Auto; report "auto".
         return "auto";
     }
@@ -186,8 +188,12 @@ fn template_str_for_root(
         T::Yaml => "yaml",
         T::Text => "text",
         T::Code => "code",
+        T::Markdown => "markdown",
+        T::Html => "html",
+        T::Gron => "gron",
         T::Auto => match cfg.style {
-            crate::serialization::types::Style::Strict => "json",
+            crate::serialization::types::Style::Strict
+            | crate::serialization::types::Style::Annotated => "json",
             crate::serialization::types::Style::Default => "pseudo",
             crate::serialization::types::Style::Detailed => "js",
         },
@@ -199,6 +205,7 @@ fn style_str(s: crate::serialization::types::Style) -> &'static str {
         crate::serialization::types::Style::Strict => "strict",
         crate::serialization::types::Style::Default => "default",
         crate::serialization::types::Style::Detailed => "detailed",
+        crate::serialization::types::Style::Annotated => "annotated",
     }
 }
 
@@ -666,13 +673,16 @@ pub(crate) fn build_render_debug_json(args: RenderDebugArgs) -> String {
     let mut omitted_children_sum: usize = 0;
     let root_is_fileset =
         order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset);
-    let treat_atomic_as_string =
-        matches!(
-            cfg.template,
-            crate::serialization::types::OutputTemplate::Text
-                | crate::serialization::types::OutputTemplate::Code
-        ) || (matches!(cfg.template, crate::OutputTemplate::Auto)
-            && root_is_fileset);
+    let treat_atomic_as_string = matches!(
+        cfg.template,
+        crate::serialization::types::OutputTemplate::Text
+            | crate::serialization::types::OutputTemplate::Code
+    ) || (matches!(
+        cfg.template,
+        crate::OutputTemplate::Auto
+            | crate::OutputTemplate::Markdown
+            | crate::OutputTemplate::Html
+    ) && root_is_fileset);
     let mut ctx = BuildCtx {
         order,
         inclusion_flags,
@@ -0,0 +1,504 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::order::NodeKind;
+use crate::utils::tree_arena::{JsonTreeArena, JsonTreeNode};
+
+/// Side of a structural diff a node belongs to. Unmarked nodes are shared
+/// ancestors kept for context.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DiffMark {
+    Added,
+    Removed,
+}
+
+// Array elements that are objects carrying one of these scalar fields (unique
+// on both sides) are aligned by that field instead of by position, so an
+// insertion does not mark every following element as changed.
+const IDENTITY_FIELDS: &[&str] = &["name", "id", "key", "uid"];
+
+/// Merged tree holding only what differs between two documents.
+pub(crate) struct ArenaDiff {
+    pub arena: JsonTreeArena,
+    // Number of added, removed or replaced values.
+    pub changes: usize,
+}
+
+enum Change {
+    Same,
+    // Containers on both sides with differences inside: merged node id.
+    Nested(usize),
+    // Leaves that differ, or values whose kind changed.
+    Replaced,
+}
+
+/// Align `old` and `new` by key path (array elements by index or identity
+/// field) and build one arena with changed, added and removed nodes plus
+/// their ancestors. Unchanged siblings are dropped but still counted in the
+/// container lengths, so they surface as omission markers.
+pub(crate) fn diff_arenas(
+    old: &JsonTreeArena,
+    new: &JsonTreeArena,
+) -> ArenaDiff {
+    let mut merger = Merger {
+        old,
+        new,
+        out: JsonTreeArena::default(),
+        changes: 0,
+    };
+    let root = merger.merge_roots(old.root_id, new.root_id);
+    merger.out.root_id = root;
+    ArenaDiff {
+        arena: merger.out,
+        changes: merger.changes,
+    }
+}
+
+struct Merger<'a> {
+    old: &'a JsonTreeArena,
+    new: &'a JsonTreeArena,
+    out: JsonTreeArena,
+    changes: usize,
+}
+
+// Children of a merged container, with how many were dropped as unchanged.
+#[derive(Default)]
+struct MergedChildren {
+    ids: Vec<usize>,
+    keys: Vec<String>,
+    indices: Vec<usize>,
+    unchanged: usize,
+}
+
+impl Merger<'_> {
+    fn merge_roots(&mut self, old_id: usize, new_id: usize) -> usize {
+        match self.diff(old_id, new_id) {
+            Change::Nested(id) => id,
+            Change::Same => self.unchanged_root(new_id),
+            Change::Replaced => {
+                self.changes += 1;
+                let removed = self.copy(Side::Old, old_id, DiffMark::Removed);
+                let added = self.copy(Side::New, new_id, DiffMark::Added);
+                let children = MergedChildren {
+                    ids: vec![removed, added],
+                    indices: vec![0, 0],
+                    ..MergedChildren::default()
+                };
+                self.push_container(NodeKind::Array, children)
+            }
+        }
+    }
+
+    // Identical documents: an empty container whose length reports everything
+    // as omitted, or the leaf itself.
+    fn unchanged_root(&mut self, new_id: usize) -> usize {
+        let node = &self.new.nodes[new_id];
+        if matches!(node.kind, NodeKind::Array | NodeKind::Object) {
+            let children = MergedChildren {
+                unchanged: node.children_len,
+                ..MergedChildren::default()
+            };
+            return self.push_container(node.kind, children);
+        }
+        self.copy(Side::New, new_id, None)
+    }
+
+    fn diff(&mut self, old_id: usize, new_id: usize) -> Change {
+        let (o, n) = (&self.old.nodes[old_id], &self.new.nodes[new_id]);
+        if o.kind != n.kind {
+            return Change::Replaced;
+        }
+        match o.kind {
+            NodeKind::Object => self.merge_objects(old_id, new_id),
+            NodeKind::Array => self.merge_arrays(old_id, new_id),
+            _ if o.atomic_token == n.atomic_token
+                && o.string_value == n.string_value =>
+            {
+                Change::Same
+            }
+            _ => Change::Replaced,
+        }
+    }
+
+    fn merge_objects(&mut self, old_id: usize, new_id: usize) -> Change {
+        let old_entries = entries(self.old, old_id);
+        let new_entries = entries(self.new, new_id);
+        let new_by_key: HashMap<&str, usize> = new_entries
+            .iter()
+            .map(|(k, id)| (k.as_str(), *id))
+            .collect();
+        let mut merged = MergedChildren::default();
+        for (key, old_child) in &old_entries {
+            match new_by_key.get(key.as_str()) {
+                Some(&new_child) => {
+                    self.merge_pair(&mut merged, key, 0, *old_child, new_child)
+                }
+                None => {
+                    self.push_removed(&mut merged, key, 0, *old_child);
+                }
+            }
+        }
+        let old_keys: HashSet<&str> =
+            old_entries.iter().map(|(k, _)| k.as_str()).collect();
+        for (key, new_child) in &new_entries {
+            if !old_keys.contains(key.as_str()) {
+                self.push_added(&mut merged, key, 0, *new_child);
+            }
+        }
+        self.finish(NodeKind::Object, merged)
+    }
+
+    fn merge_arrays(&mut self, old_id: usize, new_id: usize) -> Change {
+        let old_items = items(self.old, old_id);
+        let new_items = items(self.new, new_id);
+        let mut merged = MergedChildren::default();
+        match identity_field(self.old, &old_items, self.new, &new_items) {
+            Some(field) => self.align_by_identity(
+                &mut merged,
+                field,
+                &old_items,
+                &new_items,
+            ),
+            None => self.align_by_index(&mut merged, &old_items, &new_items),
+        }
+        sort_by_index(&mut merged);
+        self.finish(NodeKind::Array, merged)
+    }
+
+    fn align_by_index(
+        &mut self,
+        merged: &mut MergedChildren,
+        old_items: &[usize],
+        new_items: &[usize],
+    ) {
+        for idx in 0..old_items.len().max(new_items.len()) {
+            match (old_items.get(idx), new_items.get(idx)) {
+                (Some(o), Some(n)) => self.merge_pair(merged, "", idx, *o, *n),
+                (Some(o), None) => self.push_removed(merged, "", idx, *o),
+                (None, Some(n)) => self.push_added(merged, "", idx, *n),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn align_by_identity(
+        &mut self,
+        merged: &mut MergedChildren,
+        field: &str,
+        old_items: &[usize],
+        new_items: &[usize],
+    ) {
+        let new_by_id: HashMap<String, (usize, usize)> = new_items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, id)| {
+                identity_of(self.new, *id, field).map(|v| (v, (idx, *id)))
+            })
+            .collect();
+        for (idx, old_child) in old_items.iter().enumerate() {
+            let matched = identity_of(self.old, *old_child, field)
+                .and_then(|v| new_by_id.get(&v).copied());
+            match matched {
+                Some((new_idx, new_child)) => {
+                    self.merge_pair(merged, "", new_idx, *old_child, new_child)
+                }
+                None => self.push_removed(merged, "", idx, *old_child),
+            }
+        }
+        let old_ids: HashSet<String> = old_items
+            .iter()
+            .filter_map(|id| identity_of(self.old, *id, field))
+            .collect();
+        for (idx, new_child) in new_items.iter().enumerate() {
+            let known = identity_of(self.new, *new_child, field)
+                .is_some_and(|v| old_ids.contains(&v));
+            if !known {
+                self.push_added(merged, "", idx, *new_child);
+            }
+        }
+    }
+
+    fn merge_pair(
+        &mut self,
+        merged: &mut MergedChildren,
+        key: &str,
+        index: usize,
+        old_child: usize,
+        new_child: usize,
+    ) {
+        match self.diff(old_child, new_child) {
+            Change::Same => merged.unchanged += 1,
+            Change::Nested(id) => push_child(merged, key, index, id),
+            Change::Replaced => {
+                self.changes += 1;
+                let removed =
+                    self.copy(Side::Old, old_child, DiffMark::Removed);
+                push_child(merged, key, index, removed);
+                let added = self.copy(Side::New, new_child, DiffMark::Added);
+                push_child(merged, key, index, added);
+            }
+        }
+    }
+
+    fn push_removed(
+        &mut self,
+        merged: &mut MergedChildren,
+        key: &str,
+        index: usize,
+        old_child: usize,
+    ) {
+        self.changes += 1;
+        let id = self.copy(Side::Old, old_child, DiffMark::Removed);
+        push_child(merged, key, index, id);
+    }
+
+    fn push_added(
+        &mut self,
+        merged: &mut MergedChildren,
+        key: &str,
+        index: usize,
+        new_child: usize,
+    ) {
+        self.changes += 1;
+        let id = self.copy(Side::New, new_child, DiffMark::Added);
+        push_child(merged, key, index, id);
+    }
+
+    fn finish(&mut self, kind: NodeKind, merged: MergedChildren) -> Change {
+        if merged.ids.is_empty() {
+            return Change::Same;
+        }
+        Change::Nested(self.push_container(kind, merged))
+    }
+
+    fn push_container(
+        &mut self,
+        kind: NodeKind,
+        merged: MergedChildren,
+    ) -> usize {
+        // Length covers kept entries plus the unchanged ones that were
+        // dropped, so omission markers count exactly the unchanged siblings.
+        let len = merged.ids.len() + merged.unchanged;
+        let node = JsonTreeNode {
+            kind,
+            array_len: (kind == NodeKind::Array).then_some(len),
+            object_len: (kind == NodeKind::Object).then_some(len),
+            ..JsonTreeNode::default()
+        };
+        let keys = if kind == NodeKind::Object {
+            merged.keys
+        } else {
+            Vec::new()
+        };
+        let indices = if kind == NodeKind::Array {
+            merged.indices
+        } else {
+            Vec::new()
+        };
+        self.push_node(node, merged.ids, keys, indices)
+    }
+
+    fn copy(
+        &mut self,
+        side: Side,
+        id: usize,
+        mark: impl Into<Option<DiffMark>>,
+    ) -> usize {
+        let mark = mark.into();
+        let src = match side {
+            Side::Old => self.old,
+            Side::New => self.new,
+        };
+        let node = &src.nodes[id];
+        let kids: Vec<usize> = (0..node.children_len)
+            .map(|i| src.children[node.children_start + i])
+            .collect();
+        let ids = kids.into_iter().map(|k| self.copy(side, k, mark)).collect();
+        let keys = src.obj_keys
+            [node.obj_keys_start..node.obj_keys_start + node.obj_keys_len]
+            .to_vec();
+        let indices = src.arr_indices[node.arr_indices_start
+            ..node.arr_indices_start + node.arr_indices_len]
+            .to_vec();
+        let copied = JsonTreeNode {
+            diff: mark,
+            ..node.clone()
+        };
+        self.push_node(copied, ids, keys, indices)
+    }
+
+    fn push_node(
+        &mut self,
+        mut node: JsonTreeNode,
+        ids: Vec<usize>,
+        keys: Vec<String>,
+        indices: Vec<usize>,
+    ) -> usize {
+        node.children_start = self.out.children.len();
+        node.children_len = ids.len();
+        node.obj_keys_start = self.out.obj_keys.len();
+        node.obj_keys_len = keys.len();
+        node.arr_indices_start = self.out.arr_indices.len();
+        node.arr_indices_len = indices.len();
+        self.out.children.extend(ids);
+        self.out.obj_keys.extend(keys);
+        self.out.arr_indices.extend(indices);
+        self.out.nodes.push(node);
+        self.out.nodes.len() - 1
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Side {
+    Old,
+    New,
+}
+
+fn push_child(
+    merged: &mut MergedChildren,
+    key: &str,
+    index: usize,
+    id: usize,
+) {
+    merged.ids.push(id);
+    merged.keys.push(key.to_string());
+    merged.indices.push(index);
+}
+
+// Stable, so a removed element stays ahead of the element replacing it.
+fn sort_by_index(merged: &mut MergedChildren) {
+    let mut order: Vec<usize> = (0..merged.ids.len()).collect();
+    order.sort_by_key(|&i| merged.indices[i]);
+    merged.ids = order.iter().map(|&i| merged.ids[i]).collect();
+    merged.keys = order.iter().map(|&i| merged.keys[i].clone()).collect();
+    merged.indices = order.iter().map(|&i| merged.indices[i]).collect();
+}
+
+fn entries(arena: &JsonTreeArena, id: usize) -> Vec<(String, usize)> {
+    let node = &arena.nodes[id];
+    (0..node.children_len)
+        .map(|i| {
+            (
+                arena.obj_keys[node.obj_keys_start + i].clone(),
+                arena.children[node.children_start + i],
+            )
+        })
+        .collect()
+}
+
+fn items(arena: &JsonTreeArena, id: usize) -> Vec<usize> {
+    let node = &arena.nodes[id];
+    arena.children
+        [node.children_start..node.children_start + node.children_len]
+        .to_vec()
+}
+
+fn identity_of(
+    arena: &JsonTreeArena,
+    id: usize,
+    field: &str,
+) -> Option<String> {
+    let node = &arena.nodes[id];
+    if node.kind != NodeKind::Object {
+        return None;
+    }
+    let child = entries(arena, id)
+        .into_iter()
+        .find(|(k, _)| k == field)
+        .map(|(_, child)| &arena.nodes[child])?;
+    child
+        .string_value
+        .clone()
+        .or_else(|| child.atomic_token.clone())
+}
+
+fn identity_field(
+    old: &JsonTreeArena,
+    old_items: &[usize],
+    new: &JsonTreeArena,
+    new_items: &[usize],
+) -> Option<&'static str> {
+    if old_items.is_empty() || new_items.is_empty() {
+        return None;
+    }
+    IDENTITY_FIELDS.iter().copied().find(|field| {
+        unique_identities(old, old_items, field)
+            && unique_identities(new, new_items, field)
+    })
+}
+
+fn unique_identities(
+    arena: &JsonTreeArena,
+    items: &[usize],
+    field: &str,
+) -> bool {
+    let mut seen = HashSet::new();
+    items.iter().all(|id| {
+        identity_of(arena, *id, field).is_some_and(|v| seen.insert(v))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PriorityConfig;
+
+    fn arena(json: &str) -> JsonTreeArena {
+        let cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        crate::ingest::ingest_into_arena(
+            crate::InputKind::Json(json.as_bytes().to_vec()),
+            &cfg,
+        )
+        .unwrap()
+    }
+
+    fn root_entries(diff: &ArenaDiff) -> Vec<(String, Option<DiffMark>)> {
+        entries(&diff.arena, diff.arena.root_id)
+            .into_iter()
+            .map(|(k, id)| (k, diff.arena.nodes[id].diff))
+            .collect()
+    }
+
+    #[test]
+    fn object_changes_keep_only_differing_keys() {
+        let diff = diff_arenas(
+            &arena(r#"{"same":1,"gone":2,"edit":"a"}"#),
+            &arena(r#"{"same":1,"edit":"b","new":true}"#),
+        );
+        assert_eq!(diff.changes, 3);
+        assert_eq!(
+            root_entries(&diff),
+            vec![
+                ("gone".to_string(), Some(DiffMark::Removed)),
+                ("edit".to_string(), Some(DiffMark::Removed)),
+                ("edit".to_string(), Some(DiffMark::Added)),
+                ("new".to_string(), Some(DiffMark::Added)),
+            ]
+        );
+        let root = &diff.arena.nodes[diff.arena.root_id];
+        assert_eq!(root.object_len, Some(5), "4 kept + 1 unchanged");
+    }
+
+    #[test]
+    fn arrays_align_by_identity_field() {
+        let diff = diff_arenas(
+            &arena(r#"[{"name":"a","v":1},{"name":"b","v":1}]"#),
+            &arena(
+                r#"[{"name":"new","v":0},{"name":"a","v":1},{"name":"b","v":2}]"#,
+            ),
+        );
+        // One added element and one changed field; "a" is unchanged.
+        assert_eq!(diff.changes, 2);
+        let root = &diff.arena.nodes[diff.arena.root_id];
+        assert_eq!(root.array_len, Some(3));
+        assert_eq!(diff.arena.arr_indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn identical_documents_have_no_changes() {
+        let diff =
+            diff_arenas(&arena(r#"{"a":[1,2]}"#), &arena(r#"{"a":[1,2]}"#));
+        assert_eq!(diff.changes, 0);
+        let root = &diff.arena.nodes[diff.arena.root_id];
+        assert_eq!((root.children_len, root.object_len), (0, Some(1)));
+    }
+}
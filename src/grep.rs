@@ -5,25 +5,86 @@ use crate::order::{
     NodeId, ObjectType, PriorityOrder, ROOT_PQ_ID, RankedNode,
 };
 
+mod predicate;
+mod report;
+pub use predicate::Predicate;
+pub(crate) use report::build_grep_report;
+pub use report::{GrepFileSummary, GrepMatch, GrepReport};
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum GrepShow {
     #[default]
     Matching,
     All,
+    // List matching files with their match counts; nothing is rendered.
+    Names,
+    // Render only the must-keep skeleton (matches plus ancestors) with no
+    // extra budget spent on context.
+    Summary,
+}
+
+impl GrepShow {
+    /// Whether files without matches are dropped from filesets.
+    pub fn drops_unmatched_files(self) -> bool {
+        !matches!(self, GrepShow::All)
+    }
 }
 
 /// Grep configuration threaded through the pipeline.
 #[derive(Default)]
 pub struct GrepConfig {
     pub regex: Option<Regex>,
+    // Structured value filters; a node matches when the regex or any
+    // predicate matches. Predicates always act like strong grep.
+    pub predicates: Vec<Predicate>,
     pub weak: bool,
     pub show: GrepShow,
 }
 
+impl GrepConfig {
+    /// Whether any matcher (regex or predicate) is configured.
+    pub fn is_active(&self) -> bool {
+        self.regex.is_some() || !self.predicates.is_empty()
+    }
+
+    /// Regex whose matches are highlighted in rendered values. Predicate
+    /// hits have none; they are highlighted per node.
+    pub fn highlight_regex(&self) -> Option<Regex> {
+        self.regex.clone()
+    }
+}
+
 pub fn build_grep_config(
     grep: Option<&str>,
     weak_grep: Option<&str>,
     grep_show: GrepShow,
+) -> Result<GrepConfig> {
+    build_grep_config_with_predicates(grep, weak_grep, grep_show, &[])
+}
+
+/// Like [`build_grep_config`], additionally parsing structured predicates
+/// (see [`Predicate::parse`]).
+pub fn build_grep_config_with_predicates(
+    grep: Option<&str>,
+    weak_grep: Option<&str>,
+    grep_show: GrepShow,
+    predicates: &[String],
+) -> Result<GrepConfig> {
+    if weak_grep.is_some() && !predicates.is_empty() {
+        anyhow::bail!("--where cannot be combined with --weak-grep");
+    }
+    let mut cfg = build_regex_config(grep, weak_grep, grep_show)?;
+    cfg.predicates = predicates
+        .iter()
+        .map(|p| Predicate::parse(p))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(cfg)
+}
+
+fn build_regex_config(
+    grep: Option<&str>,
+    weak_grep: Option<&str>,
+    grep_show: GrepShow,
 ) -> Result<GrepConfig> {
     match (grep, weak_grep) {
         (Some(_), Some(_)) => {
@@ -31,18 +92,22 @@ pub fn build_grep_config(
         }
         (Some(pat), None) => Ok(GrepConfig {
             regex: Some(RegexBuilder::new(pat).unicode(true).build()?),
+            predicates: Vec::new(),
             weak: false,
             show: grep_show,
         }),
         (None, Some(pat)) => Ok(GrepConfig {
             regex: Some(RegexBuilder::new(pat).unicode(true).build()?),
+            predicates: Vec::new(),
             weak: true,
             show: GrepShow::Matching,
         }),
+        // `show` only matters once predicates are added on top.
         (None, None) => Ok(GrepConfig {
             regex: None,
+            predicates: Vec::new(),
             weak: false,
-            show: GrepShow::Matching,
+            show: grep_show,
         }),
     }
 }
@@ -50,6 +115,11 @@ pub fn build_grep_config(
 pub(crate) struct GrepState {
     pub must_keep: Vec<bool>,
     pub must_keep_count: usize,
+    // Nodes that matched directly (not just kept as ancestors), in node order.
+    pub matches: Vec<NodeId>,
+    // Leaves matched by a structured predicate; highlighted as a whole since
+    // there is no regex span to mark. Empty when no predicates are set.
+    pub predicate_hits: Vec<bool>,
 }
 
 impl GrepState {
@@ -88,40 +158,110 @@ fn matches_ranked(
     !is_fileset_child
 }
 
+fn matches_predicates(
+    order: &PriorityOrder,
+    idx: usize,
+    grep: &GrepConfig,
+    predicate_hits: &mut [bool],
+) -> bool {
+    let hit = grep.predicates.iter().any(|p| p.matches(order, idx));
+    if let (true, Some(flag)) = (hit, predicate_hits.get_mut(idx)) {
+        *flag = true;
+    }
+    hit
+}
+
+/// Number of grep matches in each file of a fileset order, parallel to
+/// `fileset_children`; the same matches the render keeps.
+pub(crate) fn matches_per_file(
+    order: &PriorityOrder,
+    grep: &GrepConfig,
+) -> Vec<usize> {
+    let Some(files) = &order.fileset_children else {
+        return Vec::new();
+    };
+    let mut counts = vec![0usize; files.len()];
+    let Some(state) = compute_grep_state(order, grep) else {
+        return counts;
+    };
+    let mut slot_of: Vec<Option<usize>> = vec![None; order.total_nodes];
+    for (slot, file) in files.iter().enumerate() {
+        slot_of[file.0] = Some(slot);
+    }
+    for id in state.matches {
+        let mut cursor = Some(id);
+        while let Some(node) = cursor {
+            if let Some(slot) = slot_of[node.0] {
+                counts[slot] += 1;
+                break;
+            }
+            cursor = order.parent.get(node.0).and_then(|p| *p);
+        }
+    }
+    counts
+}
+
+fn mark_ancestors(order: &PriorityOrder, idx: usize, must_keep: &mut [bool]) {
+    let mut cursor = Some(NodeId(idx));
+    while let Some(node_id) = cursor {
+        let raw = node_id.0;
+        if must_keep[raw] {
+            break;
+        }
+        must_keep[raw] = true;
+        cursor = order.parent.get(raw).and_then(|p| *p);
+    }
+}
+
 fn mark_matches_and_ancestors(
     order: &PriorityOrder,
-    re: &Regex,
+    grep: &GrepConfig,
     must_keep: &mut [bool],
-) {
+    predicate_hits: &mut [bool],
+) -> Vec<NodeId> {
+    let mut matches: Vec<NodeId> = Vec::new();
     for (idx, node) in order.nodes.iter().enumerate() {
-        if !matches_ranked(order, idx, node, re) {
-            continue;
-        }
-        let mut cursor = Some(NodeId(idx));
-        while let Some(node_id) = cursor {
-            let raw = node_id.0;
-            if must_keep[raw] {
-                break;
-            }
-            must_keep[raw] = true;
-            cursor = order.parent.get(raw).and_then(|p| *p);
+        let by_predicate =
+            matches_predicates(order, idx, grep, predicate_hits);
+        let by_regex = grep
+            .regex
+            .as_ref()
+            .is_some_and(|re| matches_ranked(order, idx, node, re));
+        if by_predicate || by_regex {
+            matches.push(NodeId(idx));
+            mark_ancestors(order, idx, must_keep);
         }
     }
+    matches
 }
 
-/// Find all nodes that match the regex (or whose keys match) and mark their
-/// ancestor chain for guaranteed inclusion.
+/// Find all nodes that match the regex (or whose keys match) or satisfy a
+/// predicate, and mark their ancestor chain for guaranteed inclusion.
 pub(crate) fn compute_grep_state(
     order: &PriorityOrder,
     grep: &GrepConfig,
 ) -> Option<GrepState> {
-    let re = grep.regex.as_ref()?;
+    if !grep.is_active() {
+        return None;
+    }
     let mut must_keep = vec![false; order.total_nodes];
-    mark_matches_and_ancestors(order, re, &mut must_keep);
+    let mut predicate_hits = if grep.predicates.is_empty() {
+        Vec::new()
+    } else {
+        vec![false; order.total_nodes]
+    };
+    let matches = mark_matches_and_ancestors(
+        order,
+        grep,
+        &mut must_keep,
+        &mut predicate_hits,
+    );
     let must_keep_count = must_keep.iter().filter(|b| **b).count();
     (must_keep_count > 0).then_some(GrepState {
         must_keep,
         must_keep_count,
+        matches,
+        predicate_hits,
     })
 }
 
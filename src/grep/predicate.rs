@@ -0,0 +1,310 @@
+use anyhow::{Result, bail};
+use std::cmp::Ordering;
+
+use crate::order::{PriorityOrder, RankedNode};
+use crate::utils::path::{Segment, node_segments};
+
+/// A structured value filter such as `latency_ms > 500` or
+/// `$.items[*].ok == false`, evaluated against leaf values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Predicate {
+    // `$`-prefixed paths must match from the document root; bare paths
+    // match any suffix of a leaf's key path.
+    anchored: bool,
+    path: Vec<PathPattern>,
+    op: CmpOp,
+    literal: Literal,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PathPattern {
+    Key(String),
+    Index(usize),
+    Any,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Literal {
+    Number(f64),
+    Bool(bool),
+    Null,
+    String(String),
+}
+
+// Longer operators first so `<=` is not read as `<`.
+const OPERATORS: &[(&str, CmpOp)] = &[
+    ("==", CmpOp::Eq),
+    ("!=", CmpOp::Ne),
+    ("<=", CmpOp::Le),
+    (">=", CmpOp::Ge),
+    ("<", CmpOp::Lt),
+    (">", CmpOp::Gt),
+];
+
+impl Predicate {
+    /// Parse `<path> <op> <literal>`. The path may be empty to test every
+    /// leaf; literals are numbers, `true`/`false`, `null`, JSON-quoted
+    /// strings, or bare words (taken as strings).
+    pub fn parse(src: &str) -> Result<Self> {
+        let Some((at, token, op)) = find_operator(src) else {
+            bail!(
+                "invalid predicate '{src}': expected one of == != < <= > >="
+            );
+        };
+        let path_src = src[..at].trim();
+        let literal_src = src[at + token.len()..].trim();
+        let (anchored, path) = parse_path(path_src)
+            .map_err(|e| anyhow::anyhow!("invalid predicate '{src}': {e}"))?;
+        let literal = parse_literal(literal_src)
+            .map_err(|e| anyhow::anyhow!("invalid predicate '{src}': {e}"))?;
+        Ok(Self {
+            anchored,
+            path,
+            op,
+            literal,
+        })
+    }
+
+    /// Whether node `id` is a leaf whose path and value satisfy this predicate.
+    pub(crate) fn matches(&self, order: &PriorityOrder, id: usize) -> bool {
+        let ordering = match &order.nodes[id] {
+            RankedNode::AtomicLeaf { token, .. } => {
+                compare_token(token, &self.literal)
+            }
+            RankedNode::SplittableLeaf { value, .. } => match &self.literal {
+                Literal::String(s) => Some(value.as_str().cmp(s.as_str())),
+                _ => None,
+            },
+            _ => return false,
+        };
+        // The value check is cheap; only candidates pay for their path.
+        if !self.op.accepts(ordering) {
+            return false;
+        }
+        let (_, segments) = node_segments(order, id);
+        self.path_matches(&segments)
+    }
+
+    fn path_matches(&self, segments: &[Segment<'_>]) -> bool {
+        if self.anchored && segments.len() != self.path.len() {
+            return false;
+        }
+        if segments.len() < self.path.len() {
+            return false;
+        }
+        let tail = &segments[segments.len() - self.path.len()..];
+        tail.iter()
+            .zip(&self.path)
+            .all(|(seg, pat)| pat.accepts(seg))
+    }
+}
+
+impl PathPattern {
+    fn accepts(&self, segment: &Segment<'_>) -> bool {
+        match (self, segment) {
+            (PathPattern::Any, _) => true,
+            (PathPattern::Key(k), Segment::Key(s)) => k == s,
+            (PathPattern::Index(i), Segment::Index(s)) => i == s,
+            _ => false,
+        }
+    }
+}
+
+impl CmpOp {
+    // Values of a different type than the literal are unequal and unordered.
+    fn accepts(self, ordering: Option<Ordering>) -> bool {
+        match (self, ordering) {
+            (CmpOp::Ne, None) => true,
+            (_, None) => false,
+            (CmpOp::Eq, Some(o)) => o == Ordering::Equal,
+            (CmpOp::Ne, Some(o)) => o != Ordering::Equal,
+            (CmpOp::Lt, Some(o)) => o == Ordering::Less,
+            (CmpOp::Le, Some(o)) => o != Ordering::Greater,
+            (CmpOp::Gt, Some(o)) => o == Ordering::Greater,
+            (CmpOp::Ge, Some(o)) => o != Ordering::Less,
+        }
+    }
+}
+
+fn compare_token(token: &str, literal: &Literal) -> Option<Ordering> {
+    match (token, literal) {
+        ("null", Literal::Null) => Some(Ordering::Equal),
+        ("true" | "false", Literal::Bool(b)) => Some((token == "true").cmp(b)),
+        ("null" | "true" | "false", _) => None,
+        (_, Literal::Number(n)) => {
+            token.parse::<f64>().ok().and_then(|v| v.partial_cmp(n))
+        }
+        _ => None,
+    }
+}
+
+fn find_operator(src: &str) -> Option<(usize, &'static str, CmpOp)> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (at, c) in src.char_indices() {
+        if in_quotes {
+            let was_escaped = escaped;
+            escaped = !was_escaped && c == '\\';
+            in_quotes = was_escaped || c != '"';
+            continue;
+        }
+        if c == '"' {
+            in_quotes = true;
+            continue;
+        }
+        let rest = &src[at..];
+        if let Some((token, op)) =
+            OPERATORS.iter().find(|(token, _)| rest.starts_with(token))
+        {
+            return Some((at, token, *op));
+        }
+    }
+    None
+}
+
+fn parse_path(src: &str) -> Result<(bool, Vec<PathPattern>)> {
+    let (anchored, mut rest) = match src.strip_prefix('$') {
+        Some(r) => (true, r),
+        None => (false, src),
+    };
+    let mut path = Vec::new();
+    while !rest.is_empty() {
+        let (pattern, next) = parse_segment(rest, path.is_empty())?;
+        path.push(pattern);
+        rest = next;
+    }
+    Ok((anchored, path))
+}
+
+fn parse_segment(src: &str, first: bool) -> Result<(PathPattern, &str)> {
+    if let Some(inner) = src.strip_prefix('[') {
+        return parse_bracket(inner);
+    }
+    let body = match src.strip_prefix('.') {
+        Some(b) => b,
+        None if first => src,
+        None => bail!("expected '.' or '[' in path at '{src}'"),
+    };
+    let end = body.find(['.', '[']).unwrap_or(body.len());
+    let key = &body[..end];
+    if key.is_empty() {
+        bail!("empty key in path");
+    }
+    let pattern = if key == "*" {
+        PathPattern::Any
+    } else {
+        PathPattern::Key(key.to_string())
+    };
+    Ok((pattern, &body[end..]))
+}
+
+fn parse_bracket(src: &str) -> Result<(PathPattern, &str)> {
+    if let Some(rest) = src.strip_prefix("*]") {
+        return Ok((PathPattern::Any, rest));
+    }
+    if src.starts_with('"') {
+        let mut stream =
+            serde_json::Deserializer::from_str(src).into_iter::<String>();
+        let Some(Ok(key)) = stream.next() else {
+            bail!("unterminated quoted key in path");
+        };
+        let rest = &src[stream.byte_offset()..];
+        let Some(rest) = rest.strip_prefix(']') else {
+            bail!("expected ']' after quoted key");
+        };
+        return Ok((PathPattern::Key(key), rest));
+    }
+    let Some((idx, rest)) = src.split_once(']') else {
+        bail!("expected ']' in path");
+    };
+    let index = idx
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| anyhow::anyhow!("invalid array index '{idx}'"))?;
+    Ok((PathPattern::Index(index), rest))
+}
+
+fn parse_literal(src: &str) -> Result<Literal> {
+    match src {
+        "" => bail!("missing value after operator"),
+        "null" => Ok(Literal::Null),
+        "true" => Ok(Literal::Bool(true)),
+        "false" => Ok(Literal::Bool(false)),
+        quoted if quoted.starts_with('"') => serde_json::from_str(quoted)
+            .map(Literal::String)
+            .map_err(|e| anyhow::anyhow!("invalid string literal: {e}")),
+        other => Ok(other
+            .parse::<f64>()
+            .map(Literal::Number)
+            .unwrap_or_else(|_| Literal::String(other.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scoped_numeric_comparison() {
+        let p = Predicate::parse("$.items[*].latency_ms >= 500").unwrap();
+        assert_eq!(
+            p,
+            Predicate {
+                anchored: true,
+                path: vec![
+                    PathPattern::Key("items".to_string()),
+                    PathPattern::Any,
+                    PathPattern::Key("latency_ms".to_string()),
+                ],
+                op: CmpOp::Ge,
+                literal: Literal::Number(500.0),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_quoted_keys_and_strings() {
+        let p = Predicate::parse(r#"["a>b"][2] != "x==y""#).unwrap();
+        assert_eq!(
+            (p.anchored, p.path, p.op, p.literal),
+            (
+                false,
+                vec![
+                    PathPattern::Key("a>b".to_string()),
+                    PathPattern::Index(2)
+                ],
+                CmpOp::Ne,
+                Literal::String("x==y".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_missing_operator() {
+        assert!(Predicate::parse("latency_ms").is_err());
+        assert!(Predicate::parse("a.b ==").is_err());
+    }
+
+    #[test]
+    fn compares_tokens_by_type() {
+        assert_eq!(
+            compare_token("1e3", &Literal::Number(500.0)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_token("true", &Literal::Number(1.0)), None);
+        assert_eq!(
+            compare_token("null", &Literal::Null),
+            Some(Ordering::Equal)
+        );
+    }
+}
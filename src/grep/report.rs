@@ -0,0 +1,166 @@
+use serde::Serialize;
+
+use super::GrepState;
+use crate::order::{NodeId, PriorityOrder, ROOT_PQ_ID, RankedNode};
+use crate::utils::path::node_path;
+
+/// One grep hit: where it lives and whether the final render shows it.
+#[derive(Clone, Debug, Serialize)]
+pub struct GrepMatch {
+    pub file: Option<String>,
+    pub path: String,
+    // Leaf value for value matches; None when only the key matched.
+    pub value: Option<String>,
+    pub shown: bool,
+}
+
+/// Per-file match tally. `hidden` counts matches pruned by budgets, which
+/// can only happen under weak grep.
+#[derive(Clone, Debug, Serialize)]
+pub struct GrepFileSummary {
+    pub file: Option<String>,
+    pub matches: usize,
+    pub hidden: usize,
+}
+
+/// Match report for a grep run, built from the must-keep matches and the
+/// final render set.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct GrepReport {
+    pub total: usize,
+    pub hidden: usize,
+    pub files: Vec<GrepFileSummary>,
+    pub matches: Vec<GrepMatch>,
+}
+
+impl GrepFileSummary {
+    fn line(&self, unnamed: &str) -> String {
+        let mut line = format!(
+            "{}: {}",
+            self.file.as_deref().unwrap_or(unnamed),
+            plural(self.matches, "match", "matches")
+        );
+        if self.hidden > 0 {
+            line.push_str(&format!(", {} hidden", self.hidden));
+        }
+        line
+    }
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    if n == 1 {
+        format!("{n} {one}")
+    } else {
+        format!("{n} {many}")
+    }
+}
+
+impl GrepReport {
+    /// Human-readable footer: a total line followed by one line per file.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut head = plural(self.total, "match", "matches");
+        let named: Vec<&GrepFileSummary> =
+            self.files.iter().filter(|f| f.file.is_some()).collect();
+        if !named.is_empty() {
+            head.push_str(" in ");
+            head.push_str(&plural(named.len(), "file", "files"));
+        }
+        if self.hidden > 0 {
+            head.push_str(&format!(" ({} hidden by budget)", self.hidden));
+        }
+        let mut lines = vec![format!("==> {head} <==")];
+        lines.extend(named.into_iter().map(|f| f.line("")));
+        lines
+    }
+
+    /// One `name: N matches` line per file, for `--grep-show names`.
+    /// Unnamed (stdin) input is listed as `<stdin>`.
+    pub fn file_lines(&self) -> Vec<String> {
+        self.files.iter().map(|f| f.line("<stdin>")).collect()
+    }
+
+    #[allow(
+        clippy::unwrap_used,
+        reason = "Serializing plain owned strings and counts cannot fail"
+    )]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    fn tally(&mut self, m: GrepMatch) {
+        self.total += 1;
+        let hidden = usize::from(!m.shown);
+        self.hidden += hidden;
+        match self.files.iter_mut().find(|f| f.file == m.file) {
+            Some(entry) => {
+                entry.matches += 1;
+                entry.hidden += hidden;
+            }
+            None => self.files.push(GrepFileSummary {
+                file: m.file.clone(),
+                matches: 1,
+                hidden,
+            }),
+        }
+        self.matches.push(m);
+    }
+}
+
+fn match_value(node: &RankedNode) -> Option<String> {
+    match node {
+        RankedNode::SplittableLeaf { value, .. } => Some(value.clone()),
+        RankedNode::AtomicLeaf { token, .. } => Some(token.clone()),
+        _ => None,
+    }
+}
+
+// Pre-order position of every node so matches are reported in document
+// order (and, for filesets, in the order files are rendered).
+fn document_rank(order: &PriorityOrder) -> Vec<usize> {
+    let mut rank = vec![usize::MAX; order.total_nodes];
+    let roots: Vec<NodeId> = order
+        .fileset_children
+        .clone()
+        .unwrap_or_else(|| vec![NodeId(ROOT_PQ_ID)]);
+    let mut stack: Vec<NodeId> = roots.into_iter().rev().collect();
+    let mut next = 0usize;
+    while let Some(id) = stack.pop() {
+        if let Some(slot) = rank.get_mut(id.0) {
+            *slot = next;
+            next += 1;
+        }
+        if let Some(kids) = order.children.get(id.0) {
+            stack.extend(kids.iter().rev().copied());
+        }
+    }
+    rank
+}
+
+/// Collect matches from `state` and mark each as shown when it made it into
+/// the render set identified by `render_id`. `source_name` names the input
+/// for single-file runs, where node paths carry no file.
+pub(crate) fn build_grep_report(
+    order: &PriorityOrder,
+    state: Option<&GrepState>,
+    inclusion_flags: &[u32],
+    render_id: u32,
+    source_name: Option<&str>,
+) -> GrepReport {
+    let mut report = GrepReport::default();
+    let Some(state) = state else {
+        return report;
+    };
+    let rank = document_rank(order);
+    let mut ids = state.matches.clone();
+    ids.sort_by_key(|id| rank.get(id.0).copied().unwrap_or(usize::MAX));
+    for id in &ids {
+        let located = node_path(order, id.0);
+        report.tally(GrepMatch {
+            file: located.file.or_else(|| source_name.map(str::to_string)),
+            path: located.path,
+            value: match_value(&order.nodes[id.0]),
+            shown: inclusion_flags.get(id.0) == Some(&render_id),
+        });
+    }
+    report
+}
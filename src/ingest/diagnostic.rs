@@ -0,0 +1,189 @@
+use std::fmt;
+
+// Longest excerpt line shown around the error column, in chars.
+const EXCERPT_WIDTH: usize = 100;
+
+/// A JSON/YAML parse failure located in its source: where it happened and a
+/// caret-annotated excerpt of the surrounding lines.
+#[derive(Debug, Clone)]
+pub struct ParseDiagnostic {
+    /// Input the failure belongs to; None for stdin and library inputs.
+    pub file: Option<String>,
+    /// Format the input was parsed as ("JSON" or "YAML").
+    pub format: &'static str,
+    pub message: String,
+    /// Byte offset of the failure in the input.
+    pub offset: usize,
+    /// 1-based line of `offset`.
+    pub line: usize,
+    /// 1-based column of `offset`, in chars.
+    pub column: usize,
+    // Up to two source lines ending with the failing one, as (line number,
+    // text) clipped around `column`.
+    excerpt: Vec<(usize, String)>,
+}
+
+impl ParseDiagnostic {
+    pub(crate) fn new(
+        format: &'static str,
+        message: impl Into<String>,
+        source: &[u8],
+        offset: usize,
+    ) -> Self {
+        let offset = offset.min(source.len());
+        // An error at EOF after a final newline belongs to the last line.
+        let at = if offset > 0
+            && offset == source.len()
+            && source[offset - 1] == b'\n'
+        {
+            offset - 1
+        } else {
+            offset
+        };
+        let line_start = source[..at]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let line = source[..at].iter().filter(|&&b| b == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&source[line_start..at])
+            .chars()
+            .count()
+            + 1;
+        let mut excerpt = Vec::with_capacity(2);
+        if line > 1 {
+            let prev_start = source[..line_start - 1]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            excerpt.push((line - 1, source_line(source, prev_start, column)));
+        }
+        excerpt.push((line, source_line(source, line_start, column)));
+        Self {
+            file: None,
+            format,
+            message: message.into(),
+            offset,
+            line,
+            column,
+            excerpt,
+        }
+    }
+
+    /// Attach the input name the failure belongs to.
+    #[must_use]
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// One-line summary, e.g. `data.json:3:8: invalid JSON: ...`.
+    pub fn headline(&self) -> String {
+        let location = match &self.file {
+            Some(file) => format!("{file}:{}:{}", self.line, self.column),
+            None => format!("line {}, column {}", self.line, self.column),
+        };
+        format!("{location}: invalid {}: {}", self.format, self.message)
+    }
+}
+
+/// Name the file a parse error belongs to, when the error is a diagnostic.
+pub fn attribute_parse_error(
+    mut err: anyhow::Error,
+    file: &str,
+) -> anyhow::Error {
+    if let Some(diagnostic) = err.downcast_mut::<ParseDiagnostic>() {
+        diagnostic.file = Some(file.to_string());
+    }
+    err
+}
+
+// First char shown for a line whose error sits at `column`: long lines are
+// clipped to a window that keeps the caret in view.
+fn clip_start(column: usize) -> usize {
+    column.saturating_sub(EXCERPT_WIDTH / 2 + 1)
+}
+
+fn source_line(source: &[u8], start: usize, column: usize) -> String {
+    let end = source[start..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(source.len(), |i| start + i);
+    let text = String::from_utf8_lossy(&source[start..end]);
+    let skip = clip_start(column);
+    let mut shown: String = text
+        .chars()
+        .skip(skip)
+        .take(EXCERPT_WIDTH)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if skip > 0 {
+        shown.insert(0, '…');
+    }
+    if text.chars().count() > skip + EXCERPT_WIDTH {
+        shown.push('…');
+    }
+    shown.trim_end().to_string()
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.headline())?;
+        let width = self.line.to_string().len();
+        for (number, text) in &self.excerpt {
+            write!(f, "\n {number:>width$} | {text}")?;
+        }
+        // The ellipsis that marks a clipped start shifts the caret by one.
+        let skip = clip_start(self.column);
+        let pad = self.column - 1 - skip + usize::from(skip > 0);
+        write!(f, "\n {:width$} | {:pad$}^", "", "")
+    }
+}
+
+impl std::error::Error for ParseDiagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_offsets_and_renders_an_excerpt() {
+        let source = b"{\n  \"a\": 1 \"b\": 2\n}\n";
+        let diag = ParseDiagnostic::new("JSON", "unexpected '\"'", source, 11)
+            .with_file("data.json");
+        assert_eq!((diag.line, diag.column), (2, 10));
+        assert_eq!(
+            diag.to_string(),
+            "data.json:2:10: invalid JSON: unexpected '\"'\n \
+             1 | {\n \
+             2 |   \"a\": 1 \"b\": 2\n   \
+             |          ^"
+        );
+    }
+
+    #[test]
+    fn eof_after_trailing_newline_points_past_the_last_line() {
+        let source = b"a: [1,\n";
+        let diag = ParseDiagnostic::new("YAML", "unexpected EOF", source, 7);
+        assert_eq!((diag.line, diag.column), (1, 7));
+        assert!(
+            diag.to_string().ends_with(" 1 | a: [1,\n   |       ^"),
+            "{diag}"
+        );
+    }
+
+    #[test]
+    fn long_lines_are_clipped_around_the_caret() {
+        let source = format!("[{}x]", "1,".repeat(200));
+        let diag = ParseDiagnostic::new(
+            "JSON",
+            "unexpected 'x'",
+            source.as_bytes(),
+            401,
+        );
+        let rendered = diag.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        let caret = lines[2].chars().position(|c| c == '^').unwrap();
+        assert_eq!(lines[1].chars().nth(caret), Some('x'), "{rendered}");
+        assert!(lines[1].contains('…'), "{rendered}");
+    }
+}
@@ -0,0 +1,151 @@
+use crate::PriorityConfig;
+use crate::order::NodeKind;
+use crate::utils::tree_arena::{JsonTreeArena, JsonTreeNode};
+
+use super::fileset::append_subtree;
+use super::formats::{
+    json::build_json_tree_arena_from_bytes,
+    yaml::build_yaml_tree_arena_from_bytes,
+};
+
+/// Replace string leaves that hold a serialized JSON object/array (or a
+/// multi-line YAML mapping/sequence) with the parsed subtree, flagged as
+/// `embedded`. Grafted nodes are visited too, so doubly encoded payloads
+/// unfold fully. Strings that fail to parse are left untouched.
+pub(crate) fn expand_embedded_strings(
+    arena: &mut JsonTreeArena,
+    cfg: &PriorityConfig,
+) {
+    // Lines inside the string are not lines of the input; the subtree root
+    // takes the string's line instead.
+    let cfg = PriorityConfig {
+        source_lines: false,
+        ..*cfg
+    };
+    let mut id = 0;
+    while id < arena.nodes.len() {
+        if let Some(sub) = parse_embedded(&arena.nodes[id], &cfg) {
+            graft(arena, id, sub);
+        }
+        id += 1;
+    }
+}
+
+// Append `sub` and move its root into the string's slot, so parent links
+// stay valid and no copy of the root is left behind.
+fn graft(arena: &mut JsonTreeArena, id: usize, sub: JsonTreeArena) {
+    let children_start = arena.children.len();
+    let sub_root = append_subtree(arena, sub);
+    let mut root = arena.nodes.swap_remove(sub_root);
+    root.embedded = true;
+    root.source_line = arena.nodes[id].source_line;
+    arena.nodes[id] = root;
+    move_truncation(arena, sub_root, id);
+    // The appended tail node now sits where the root was.
+    let moved = arena.nodes.len();
+    if moved == sub_root {
+        return;
+    }
+    for child in &mut arena.children[children_start..] {
+        if *child == moved {
+            *child = sub_root;
+        }
+    }
+    move_truncation(arena, moved, sub_root);
+}
+
+fn move_truncation(arena: &mut JsonTreeArena, from: usize, to: usize) {
+    if let Some(note) = arena.truncations.remove(&from) {
+        arena.truncations.insert(to, note);
+    }
+}
+
+fn parse_embedded(
+    node: &JsonTreeNode,
+    cfg: &PriorityConfig,
+) -> Option<JsonTreeArena> {
+    if node.kind != NodeKind::String {
+        return None;
+    }
+    let sub = parse_structured(node.string_value.as_deref()?.trim(), cfg)?;
+    let root_kind = sub.nodes.get(sub.root_id)?.kind;
+    matches!(root_kind, NodeKind::Object | NodeKind::Array).then_some(sub)
+}
+
+// JSON is sniffed by its opener; YAML only for multi-line text, since any
+// single line parses as a YAML scalar.
+fn parse_structured(
+    text: &str,
+    cfg: &PriorityConfig,
+) -> Option<JsonTreeArena> {
+    let bytes = text.as_bytes().to_vec();
+    if text.starts_with(['{', '[']) {
+        build_json_tree_arena_from_bytes(bytes, cfg).ok()
+    } else if text.contains('\n') {
+        build_yaml_tree_arena_from_bytes(bytes, cfg).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::parse_json_one;
+
+    fn expanded(json: &str) -> JsonTreeArena {
+        let cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        let mut arena =
+            parse_json_one(json.as_bytes().to_vec(), &cfg).unwrap();
+        expand_embedded_strings(&mut arena, &cfg);
+        arena
+    }
+
+    fn child(arena: &JsonTreeArena, id: usize, idx: usize) -> &JsonTreeNode {
+        &arena.nodes[arena.children[arena.nodes[id].children_start + idx]]
+    }
+
+    #[test]
+    fn expands_nested_json_strings() {
+        let arena = expanded(
+            r#"{"message":"{\"user\":\"{\\\"id\\\":7}\",\"n\":[1,2]}"}"#,
+        );
+        let message = child(&arena, arena.root_id, 0);
+        assert!(message.embedded && message.kind == NodeKind::Object);
+        let user = &arena.nodes[arena.children[message.children_start]];
+        assert!(user.embedded && user.object_len == Some(1));
+    }
+
+    #[test]
+    fn grafting_leaves_no_orphaned_nodes() {
+        // Root, the expanded object, its array and the array item.
+        let arena = expanded(r#"{"a":"{\"b\":[1]}"}"#);
+        assert_eq!(arena.nodes.len(), 4);
+        let a = child(&arena, arena.root_id, 0);
+        let b = &arena.nodes[arena.children[a.children_start]];
+        assert!(b.kind == NodeKind::Array && b.children_len == 1);
+    }
+
+    #[test]
+    fn grafted_root_keeps_the_line_of_its_string() {
+        let mut cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        cfg.source_lines = true;
+        let json = "{\n  \"a\": 1,\n  \"b\": \"x:\\n  - 1\\n\"\n}";
+        let mut arena =
+            parse_json_one(json.as_bytes().to_vec(), &cfg).unwrap();
+        expand_embedded_strings(&mut arena, &cfg);
+        let b = child(&arena, arena.root_id, 1);
+        assert!(b.embedded && b.source_line == Some(3));
+        let x = &arena.nodes[arena.children[b.children_start]];
+        assert_eq!(x.source_line, None);
+    }
+
+    #[test]
+    fn leaves_plain_and_scalar_strings_alone() {
+        let arena = expanded(r#"{"a":"{not json","b":"42","c":"x: 1"}"#);
+        let root = arena.root_id;
+        let kinds: Vec<NodeKind> =
+            (0..3).map(|i| child(&arena, root, i).kind).collect();
+        assert_eq!(kinds, vec![NodeKind::String; 3]);
+    }
+}
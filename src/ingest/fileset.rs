@@ -1,16 +1,20 @@
 use anyhow::Result;
 
+use crate::ingest::diagnostic::{ParseDiagnostic, attribute_parse_error};
+use crate::ingest::format::Format;
 use crate::order::NodeKind;
+use crate::utils::extensions::is_code_like_name;
 use crate::utils::tree_arena::{JsonTreeArena, JsonTreeNode};
 
 use super::formats::{
-    json::build_json_tree_arena_from_bytes,
+    json::parse_json_slice,
     text::{
         build_text_tree_arena_from_bytes,
         build_text_tree_arena_from_bytes_with_mode,
     },
-    yaml::build_yaml_tree_arena_from_bytes,
+    yaml::parse_yaml_slice,
 };
+use super::with_embedded;
 use crate::PriorityConfig;
 
 /// Input descriptor for a single file in a multi-format fileset ingest.
@@ -21,6 +25,103 @@ pub struct FilesetInput {
     pub kind: FilesetInputKind,
 }
 
+/// How the files of a fileset are arranged beyond their input order: their
+/// shares of a global budget and an optional order by grep matches.
+#[derive(Clone, Debug, Default)]
+pub struct FilesetLayout {
+    /// Relative share of a global budget for each file, parallel to the
+    /// inputs. `None` (or a missing entry) is the default share: 1.0, or
+    /// the file's grep matches + 1 with `weigh_by_matches`.
+    pub weights: Vec<Option<f64>>,
+    /// Default shares follow the number of grep matches in each file.
+    pub weigh_by_matches: bool,
+    /// Order files by their number of grep matches; ties keep input order.
+    pub sort_by_matches: Option<MatchSort>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MatchSort {
+    MostFirst,
+    FewestFirst,
+}
+
+impl FilesetLayout {
+    /// Whether arranging files needs their grep match counts.
+    pub(crate) fn uses_matches(&self) -> bool {
+        self.weigh_by_matches || self.sort_by_matches.is_some()
+    }
+
+    /// Set the budget share of every file in `arena`. `matches` holds the
+    /// grep match count of each file (parallel to the root's children) once
+    /// known.
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Weights are relative shares; precision beyond 2^52 is irrelevant"
+    )]
+    pub(crate) fn apply_weights(
+        &self,
+        arena: &mut JsonTreeArena,
+        matches: Option<&[usize]>,
+    ) {
+        let files = arena.nodes[arena.root_id].children_len;
+        arena.fileset_weights = (0..files)
+            .map(|idx| {
+                let explicit = self.weights.get(idx).copied().flatten();
+                let by_matches = matches
+                    .filter(|_| self.weigh_by_matches)
+                    .and_then(|m| m.get(idx))
+                    .map(|&count| count as f64 + 1.0);
+                explicit.or(by_matches).unwrap_or(1.0)
+            })
+            .collect();
+    }
+
+    /// Reorder the files of `arena` by `matches` when sorting is requested.
+    pub(crate) fn apply_order(
+        &self,
+        arena: &mut JsonTreeArena,
+        matches: &[usize],
+    ) {
+        let Some(sort) = self.sort_by_matches else {
+            return;
+        };
+        let root = &arena.nodes[arena.root_id];
+        if matches.len() != root.children_len {
+            return;
+        }
+        let mut order: Vec<usize> = (0..matches.len()).collect();
+        // A stable sort on the (reversed) key keeps ties in input order.
+        match sort {
+            MatchSort::MostFirst => {
+                order.sort_by_key(|&idx| std::cmp::Reverse(matches[idx]));
+            }
+            MatchSort::FewestFirst => order.sort_by_key(|&idx| matches[idx]),
+        }
+        permute_fileset(arena, &order);
+    }
+}
+
+// Put the files of a fileset arena in `order` (old positions, new order),
+// along with their per-file metadata.
+fn permute_fileset(arena: &mut JsonTreeArena, order: &[usize]) {
+    fn permuted<T: Clone>(items: &[T], order: &[usize]) -> Vec<T> {
+        if items.len() != order.len() {
+            return items.to_vec();
+        }
+        order.iter().map(|&idx| items[idx].clone()).collect()
+    }
+    let root = &arena.nodes[arena.root_id];
+    let children = root.children_start..root.children_start + order.len();
+    let keys = root.obj_keys_start..root.obj_keys_start + order.len();
+    let moved_children = permuted(&arena.children[children.clone()], order);
+    arena.children.splice(children, moved_children);
+    let moved_keys = permuted(&arena.obj_keys[keys.clone()], order);
+    arena.obj_keys.splice(keys, moved_keys);
+    arena.fileset_weights = permuted(&arena.fileset_weights, order);
+    arena.fileset_sizes = permuted(&arena.fileset_sizes, order);
+    arena.fileset_kinds = permuted(&arena.fileset_kinds, order);
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum FilesetInputKind {
     Json,
@@ -28,33 +129,97 @@ pub enum FilesetInputKind {
     Text { atomic_lines: bool },
 }
 
+impl FilesetInputKind {
+    /// Kind implied by a file name: JSON/YAML by extension or well-known
+    /// name, otherwise text (line-atomic for code-like files).
+    pub fn from_filename(name: &str) -> Self {
+        match Format::from_filename(name) {
+            Format::Json => FilesetInputKind::Json,
+            Format::Yaml => FilesetInputKind::Yaml,
+            Format::Unknown => FilesetInputKind::Text {
+                atomic_lines: is_code_like_name(name),
+            },
+        }
+    }
+}
+
+/// A fileset entry that did not parse as its format and was ingested as
+/// plain text instead.
+#[derive(Debug, Clone)]
+pub struct FilesetParseFailure {
+    pub name: String,
+    pub error: String,
+    /// Where the parse failed, attributed to `name`.
+    pub diagnostic: Option<ParseDiagnostic>,
+}
+
 pub fn parse_fileset_multi(
     inputs: Vec<FilesetInput>,
     cfg: &PriorityConfig,
 ) -> Result<JsonTreeArena> {
     let mut arenas: Vec<(String, JsonTreeArena)> =
         Vec::with_capacity(inputs.len());
+    let sizes: Vec<usize> = inputs.iter().map(|i| i.bytes.len()).collect();
+    let mut kinds: Vec<FilesetInputKind> = Vec::with_capacity(inputs.len());
+    let mut failures: Vec<FilesetParseFailure> = Vec::new();
+    let mut recovered: Vec<String> = Vec::new();
     for FilesetInput { name, bytes, kind } in inputs {
-        let arena = match kind {
-            FilesetInputKind::Json => {
-                build_json_tree_arena_from_bytes(bytes, cfg)?
+        let arena = match parse_fileset_entry(&bytes, kind, cfg) {
+            Ok(arena) => {
+                kinds.push(kind);
+                recovered.extend(
+                    arena.truncations.values().map(|n| format!("{name}: {n}")),
+                );
+                arena
             }
-            FilesetInputKind::Yaml => {
-                build_yaml_tree_arena_from_bytes(bytes, cfg)?
+            Err(err) if !cfg.strict_parse => {
+                // Keep the rest of the fileset: preview this file as text.
+                let err = attribute_parse_error(err, &name);
+                failures.push(FilesetParseFailure {
+                    name: name.clone(),
+                    error: format!("{err:#}"),
+                    diagnostic: err.downcast_ref::<ParseDiagnostic>().cloned(),
+                });
+                kinds.push(FilesetInputKind::Text {
+                    atomic_lines: false,
+                });
+                build_text_tree_arena_from_bytes(&bytes, cfg)
             }
-            FilesetInputKind::Text { atomic_lines } => {
-                if atomic_lines {
-                    build_text_tree_arena_from_bytes_with_mode(
-                        &bytes, cfg, true,
-                    )
-                } else {
-                    build_text_tree_arena_from_bytes(&bytes, cfg)
-                }
+            Err(err) => {
+                return Err(attribute_parse_error(err, &name)
+                    .context(format!("failed to parse {name}")));
             }
         };
         arenas.push((name, arena));
     }
-    Ok(build_fileset_root(arenas))
+    let mut root = build_fileset_root(arenas);
+    root.fileset_sizes = sizes;
+    root.fileset_kinds = kinds;
+    root.fileset_parse_failures = failures;
+    root.fileset_recovered = recovered;
+    Ok(root)
+}
+
+fn parse_fileset_entry(
+    bytes: &[u8],
+    kind: FilesetInputKind,
+    cfg: &PriorityConfig,
+) -> Result<JsonTreeArena> {
+    Ok(match kind {
+        FilesetInputKind::Json => {
+            with_embedded(parse_json_slice(bytes, cfg)?, cfg)
+        }
+        FilesetInputKind::Yaml => {
+            with_embedded(parse_yaml_slice(bytes, cfg)?, cfg)
+        }
+        FilesetInputKind::Text { atomic_lines } => {
+            if atomic_lines {
+                build_text_tree_arena_from_bytes_with_mode(bytes, cfg, true)
+            } else {
+                build_text_tree_arena_from_bytes(bytes, cfg)
+            }
+        }
+    })
 }
 
 pub(crate) fn build_fileset_root(
@@ -100,7 +265,10 @@ pub(crate) fn build_fileset_root(
     clippy::cognitive_complexity,
     reason = "Tree merge touches multiple parallel arrays and offsets; easier to follow inline"
 )]
-fn append_subtree(dest: &mut JsonTreeArena, src: JsonTreeArena) -> usize {
+pub(crate) fn append_subtree(
+    dest: &mut JsonTreeArena,
+    src: JsonTreeArena,
+) -> usize {
     let node_offset = dest.nodes.len();
     let child_offset = dest.children.len();
     let obj_key_offset = dest.obj_keys.len();
@@ -112,6 +280,7 @@ fn append_subtree(dest: &mut JsonTreeArena, src: JsonTreeArena) -> usize {
         obj_keys,
         arr_indices,
         code_lines,
+        truncations,
         ..
     } = src;
 
@@ -135,6 +304,9 @@ fn append_subtree(dest: &mut JsonTreeArena, src: JsonTreeArena) -> usize {
     for (arena_idx, lines) in code_lines {
         dest.code_lines.insert(arena_idx + node_offset, lines);
     }
+    for (arena_idx, note) in truncations {
+        dest.truncations.insert(arena_idx + node_offset, note);
+    }
 
     node_offset + root_id
 }
@@ -8,30 +8,55 @@ pub enum Format {
 }
 
 impl Format {
-    /// Map a filename or path string to a `Format` by inspecting its extension.
-    /// Uses `Path::extension` and ASCII case-insensitive comparison to avoid
+    /// Map a filename or path string to a `Format` by inspecting its extension,
+    /// or its whole name for well-known config files without one. Uses
+    /// `Path::extension` and ASCII case-insensitive comparison to avoid
     /// allocations. Known mappings:
-    /// - .json -> Json
-    /// - .yaml, .yml -> Yaml
+    /// - .json, .babelrc, .swcrc, Pipfile.lock, ... -> Json
+    /// - .yaml, .yml, .clang-format, .yamllint, ... -> Yaml
     pub fn from_filename(name: &str) -> Self {
-        use std::path::Path;
-        const EXT_FORMATS: &[(&str, Format)] = &[
-            ("json", Format::Json),
-            ("yaml", Format::Yaml),
-            ("yml", Format::Yaml),
-        ];
-        if let Some(ext) = Path::new(name).extension().and_then(|e| e.to_str())
-        {
-            for (pat, fmt) in EXT_FORMATS {
-                if ext.eq_ignore_ascii_case(pat) {
-                    return *fmt;
-                }
-            }
-        }
-        Format::Unknown
+        let path = std::path::Path::new(name);
+        let by_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|file_name| lookup(NAME_FORMATS, file_name));
+        by_name
+            .or_else(|| {
+                path.extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(|ext| lookup(EXT_FORMATS, ext))
+            })
+            .unwrap_or(Format::Unknown)
     }
 }
 
+const EXT_FORMATS: &[(&str, Format)] = &[
+    ("json", Format::Json),
+    ("yaml", Format::Yaml),
+    ("yml", Format::Yaml),
+];
+
+// Well-known config files whose names carry no (or a misleading) extension.
+const NAME_FORMATS: &[(&str, Format)] = &[
+    (".babelrc", Format::Json),
+    (".jshintrc", Format::Json),
+    (".swcrc", Format::Json),
+    (".watchmanconfig", Format::Json),
+    ("composer.lock", Format::Json),
+    ("flake.lock", Format::Json),
+    ("Pipfile.lock", Format::Json),
+    (".clang-format", Format::Yaml),
+    (".clang-tidy", Format::Yaml),
+    (".yamllint", Format::Yaml),
+];
+
+fn lookup(table: &[(&str, Format)], key: &str) -> Option<Format> {
+    table
+        .iter()
+        .find(|(pat, _)| key.eq_ignore_ascii_case(pat))
+        .map(|(_, fmt)| *fmt)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Format;
@@ -50,4 +75,12 @@ mod tests {
         assert_eq!(Format::from_filename("noext"), Format::Unknown);
         assert_eq!(Format::from_filename("weird.tar.gz"), Format::Unknown);
     }
+
+    #[test]
+    fn maps_well_known_filenames() {
+        assert_eq!(Format::from_filename("web/.babelrc"), Format::Json);
+        assert_eq!(Format::from_filename("Pipfile.lock"), Format::Json);
+        assert_eq!(Format::from_filename(".clang-format"), Format::Yaml);
+        assert_eq!(Format::from_filename("notes.babelrc"), Format::Unknown);
+    }
 }
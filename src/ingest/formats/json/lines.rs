@@ -0,0 +1,198 @@
+//! Source lines for an ingested JSON tree.
+//!
+//! simd-json keeps no positions, so a second pass walks the raw input next
+//! to the finished arena. Object members map one to one onto the node's
+//! children; arrays descend only into the items sampling kept and skip the
+//! rest. The input already parsed, so the walk only has to stay in step with
+//! it, not validate it.
+
+use crate::order::NodeKind;
+use crate::utils::tree_arena::JsonTreeArena;
+
+pub(super) fn record_source_lines(input: &[u8], arena: &mut JsonTreeArena) {
+    let mut cursor = Cursor {
+        input,
+        pos: 0,
+        line: 1,
+    };
+    cursor.node(arena, arena.root_id);
+}
+
+struct Cursor<'a> {
+    input: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) {
+        if self.peek() == Some(b'\n') {
+            self.line += 1;
+        }
+        self.pos += 1;
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn node(&mut self, arena: &mut JsonTreeArena, id: usize) {
+        self.skip_whitespace();
+        arena.nodes[id].source_line = Some(self.line);
+        match arena.nodes[id].kind {
+            NodeKind::Object => self.object(arena, id),
+            NodeKind::Array => self.array(arena, id),
+            _ => self.skip_value(),
+        }
+    }
+
+    fn object(&mut self, arena: &mut JsonTreeArena, id: usize) {
+        let node = &arena.nodes[id];
+        let children =
+            node.children_start..node.children_start + node.children_len;
+        let mut member = 0;
+        self.bump();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return,
+                Some(b'}') => return self.bump(),
+                Some(b'"') => {
+                    self.skip_string();
+                    self.skip_whitespace();
+                    self.bump(); // ':'
+                    let child = children
+                        .clone()
+                        .nth(member)
+                        .map(|slot| arena.children[slot]);
+                    match child {
+                        Some(child) => self.node(arena, child),
+                        None => self.skip_value(),
+                    }
+                    member += 1;
+                }
+                Some(_) => self.bump(),
+            }
+        }
+    }
+
+    fn array(&mut self, arena: &mut JsonTreeArena, id: usize) {
+        let node = &arena.nodes[id];
+        let (start, kept) = (node.children_start, node.children_len);
+        let indices = node.arr_indices_start
+            ..node.arr_indices_start + node.arr_indices_len;
+        // Original index of the k-th kept child; contiguous when sampling
+        // stored no indices.
+        let original = |a: &JsonTreeArena, k: usize| {
+            if indices.is_empty() {
+                k
+            } else {
+                a.arr_indices[indices.start + k]
+            }
+        };
+        let (mut index, mut next) = (0, 0);
+        self.bump();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return,
+                Some(b']') => return self.bump(),
+                Some(b',') => self.bump(),
+                Some(_) => {
+                    if next < kept && original(arena, next) == index {
+                        let child = arena.children[start + next];
+                        self.node(arena, child);
+                        next += 1;
+                    } else {
+                        self.skip_value();
+                    }
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    fn skip_value(&mut self) {
+        match self.peek() {
+            Some(b'"') => self.skip_string(),
+            Some(b'{' | b'[') => self.skip_container(),
+            _ => {
+                while self.peek().is_some_and(|b| {
+                    !b.is_ascii_whitespace() && !b",]}".contains(&b)
+                }) {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn skip_container(&mut self) {
+        let mut depth = 0usize;
+        while let Some(b) = self.peek() {
+            match b {
+                b'"' => {
+                    self.skip_string();
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.bump();
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    fn skip_string(&mut self) {
+        self.bump();
+        while let Some(b) = self.peek() {
+            self.bump();
+            match b {
+                b'\\' => self.bump(),
+                b'"' => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_json_one;
+    use crate::PriorityConfig;
+
+    fn lines_of(input: &str, array_max_items: usize) -> Vec<Option<usize>> {
+        let mut cfg = PriorityConfig::new(usize::MAX, array_max_items);
+        cfg.source_lines = true;
+        let arena = parse_json_one(input.as_bytes().to_vec(), &cfg).unwrap();
+        arena.nodes.iter().map(|n| n.source_line).collect()
+    }
+
+    #[test]
+    fn members_and_items_get_their_lines() {
+        let input = "{\n  \"a\": 1,\n  \"b\": [\n    \"x,]\",\n    {\"c\": null}\n  ]\n}";
+        // Arena order: root, a, b, "x,]", {c}, null.
+        assert_eq!(
+            lines_of(input, usize::MAX),
+            vec![Some(1), Some(2), Some(3), Some(4), Some(5), Some(5)]
+        );
+    }
+
+    #[test]
+    fn sampled_away_items_are_skipped() {
+        let input = "[\n0,\n[1,\n2],\n3,\n4,\n5\n]";
+        // Kept: root, 0 and [1, 2] with its items; 3..5 are sampled away.
+        assert_eq!(
+            lines_of(input, 2),
+            vec![Some(1), Some(2), Some(3), Some(3), Some(4)]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 mod builder;
+mod lines;
+mod recover;
 mod samplers;
 
 use anyhow::Result;
@@ -6,6 +8,8 @@ use builder::JsonTreeBuilder;
 use serde::de::DeserializeSeed;
 
 use crate::PriorityConfig;
+use crate::ingest::diagnostic::ParseDiagnostic;
+use crate::order::NodeKind;
 use crate::utils::tree_arena::JsonTreeArena as TreeArena;
 
 #[cfg(test)]
@@ -60,11 +64,108 @@ pub fn build_json_tree_arena_from_many(
 }
 
 /// Convenience functions for the JSON ingest path.
+///
+/// With `recover_json`, input that fails to parse is cut at the first error
+/// and its open containers are closed; the innermost one carries a truncation
+/// note for rendering. Otherwise failures are reported as a
+/// `ParseDiagnostic`. With `source_lines`, each node records the line it
+/// starts on.
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Signature stays aligned with other ingest helpers and public API"
+)]
 pub fn parse_json_one(
     bytes: Vec<u8>,
     cfg: &PriorityConfig,
 ) -> Result<TreeArena> {
-    build_json_tree_arena_from_bytes(bytes, cfg)
+    parse_json_slice(&bytes, cfg)
+}
+
+// simd-json parses in place, so it gets a copy and `bytes` stays intact for
+// recovery, diagnostics and source lines.
+pub(crate) fn parse_json_slice(
+    bytes: &[u8],
+    cfg: &PriorityConfig,
+) -> Result<TreeArena> {
+    let mut arena = match build_json_tree_arena_from_bytes(bytes.to_vec(), cfg)
+    {
+        Ok(arena) => arena,
+        Err(err) => {
+            let recovered = if cfg.recover_json {
+                recover_json(bytes, cfg)
+            } else {
+                None
+            };
+            let Some(arena) = recovered else {
+                return Err(json_diagnostic(bytes, &err).into());
+            };
+            arena
+        }
+    };
+    if cfg.source_lines {
+        // A recovered tree covers a prefix of the original input.
+        lines::record_source_lines(bytes, &mut arena);
+    }
+    Ok(arena)
+}
+
+// Locate the failure with the recovery scanner, which names the offending
+// token; fall back to simd-json's own position.
+fn json_diagnostic(source: &[u8], err: &anyhow::Error) -> ParseDiagnostic {
+    if let Some((offset, reason)) = recover::first_error(source) {
+        return ParseDiagnostic::new("JSON", reason, source, offset);
+    }
+    let offset = err
+        .downcast_ref::<simd_json::Error>()
+        .map_or(0, simd_json::Error::index);
+    ParseDiagnostic::new("JSON", err.to_string(), source, offset)
+}
+
+fn recover_json(bytes: &[u8], cfg: &PriorityConfig) -> Option<TreeArena> {
+    let repaired = recover::repair(bytes)?;
+    let note = repaired.note();
+    let mut arena =
+        build_json_tree_arena_from_bytes(repaired.bytes, cfg).ok()?;
+    // Scalars have nowhere to carry the note; keep the parse error instead.
+    let root_kind = arena.nodes[arena.root_id].kind;
+    if !matches!(root_kind, NodeKind::Array | NodeKind::Object) {
+        return None;
+    }
+    let target = innermost_open(&arena, repaired.open);
+    arena.truncations.insert(target, note);
+    Some(arena)
+}
+
+// Follow the last child down `open - 1` levels to the container that was
+// still open at the cut.
+fn innermost_open(arena: &TreeArena, open: usize) -> usize {
+    let mut id = arena.root_id;
+    for _ in 1..open {
+        match open_last_child(arena, id) {
+            Some(child) => id = child,
+            None => break,
+        }
+    }
+    id
+}
+
+// The last child of `id` when it is a non-empty container that really ends
+// its parent (not an array tail that was sampled away).
+fn open_last_child(arena: &TreeArena, id: usize) -> Option<usize> {
+    let node = &arena.nodes[id];
+    let last = node.children_len.checked_sub(1)?;
+    if node.arr_indices_len > 0 {
+        let kept = arena.arr_indices
+            [node.arr_indices_start + node.arr_indices_len - 1];
+        if Some(kept + 1) != node.array_len {
+            return None;
+        }
+    }
+    let child = arena.children[node.children_start + last];
+    let child_node = &arena.nodes[child];
+    let container =
+        matches!(child_node.kind, NodeKind::Array | NodeKind::Object);
+    (container && child_node.children_len > 0).then_some(child)
 }
 
 #[cfg(test)]
@@ -89,4 +190,22 @@ mod tests {
             build_json_tree_arena_from_bytes(b"{}".to_vec(), &cfg).unwrap();
         assert!(!arena.is_fileset, "expected fileset marker false");
     }
+
+    #[test]
+    fn recovery_marks_innermost_open_container() {
+        let mut cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        cfg.recover_json = true;
+        let arena =
+            parse_json_one(br#"{"a": {"b": [1, 2"#.to_vec(), &cfg).unwrap();
+        let (&id, note) = arena.truncations.iter().next().unwrap();
+        assert_eq!(arena.nodes[id].kind, NodeKind::Array);
+        assert_eq!(arena.nodes[id].array_len, Some(1));
+        assert_eq!(note, "input truncated at byte 17: unexpected EOF");
+    }
+
+    #[test]
+    fn recovery_is_opt_in() {
+        let cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        assert!(parse_json_one(b"[1, 2".to_vec(), &cfg).is_err());
+    }
 }
@@ -0,0 +1,372 @@
+//! Best-effort repair of truncated or malformed JSON.
+//!
+//! A light scanner walks the input up to the first syntax error, tracking
+//! the open containers and the end of the last complete value. Everything
+//! after that point is dropped and the open containers are closed, which
+//! yields a document simd-json accepts.
+
+pub(crate) struct Repaired {
+    pub(crate) bytes: Vec<u8>,
+    // Byte offset of the first problem in the original input.
+    pub(crate) offset: usize,
+    pub(crate) reason: String,
+    // Number of containers that were still open at the cut.
+    pub(crate) open: usize,
+}
+
+impl Repaired {
+    pub(crate) fn note(&self) -> String {
+        format!("input truncated at byte {}: {}", self.offset, self.reason)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Expect {
+    Value,
+    ValueOrClose,
+    Key,
+    KeyOrClose,
+    Colon,
+    CommaOrClose,
+    Done,
+}
+
+struct Failure {
+    offset: usize,
+    reason: String,
+}
+
+struct Scanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+    stack: Vec<u8>,
+    // End of the last complete value or opening bracket; cutting here and
+    // closing `stack` always leaves valid JSON.
+    last_good: usize,
+    expect: Expect,
+}
+
+/// Repair `input` after a failed strict parse. Returns None when the input
+/// scans as valid JSON or when no root value can be salvaged.
+pub(crate) fn repair(input: &[u8]) -> Option<Repaired> {
+    let mut scanner = Scanner::new(input);
+    let failure = scanner.run().err()?;
+    if scanner.last_good == 0 {
+        return None;
+    }
+    let mut bytes = input[..scanner.last_good].to_vec();
+    bytes.extend(scanner.stack.iter().rev().map(|open| match open {
+        b'{' => b'}',
+        _ => b']',
+    }));
+    Some(Repaired {
+        bytes,
+        offset: failure.offset,
+        reason: failure.reason,
+        open: scanner.stack.len(),
+    })
+}
+
+/// Offset and reason of the first syntax error or invalid UTF-8 in
+/// `input`, when there is one the scanner can see.
+pub(crate) fn first_error(input: &[u8]) -> Option<(usize, String)> {
+    let syntax = Scanner::new(input)
+        .run()
+        .err()
+        .map(|failure| (failure.offset, failure.reason));
+    let utf8 = std::str::from_utf8(input)
+        .err()
+        .map(|err| (err.valid_up_to(), "invalid UTF-8".to_string()));
+    match (syntax, utf8) {
+        (Some(a), Some(b)) => Some(if b.0 < a.0 { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            stack: Vec::new(),
+            last_good: 0,
+            expect: Expect::Value,
+        }
+    }
+
+    fn run(&mut self) -> Result<(), Failure> {
+        loop {
+            self.skip_whitespace();
+            let Some(&b) = self.input.get(self.pos) else {
+                return match self.expect {
+                    Expect::Done => Ok(()),
+                    _ => Err(self.eof()),
+                };
+            };
+            self.step(b)?;
+        }
+    }
+
+    fn step(&mut self, b: u8) -> Result<(), Failure> {
+        match (self.expect, b) {
+            (Expect::ValueOrClose, b']') | (Expect::KeyOrClose, b'}') => {
+                self.close(b)
+            }
+            (Expect::CommaOrClose, b']' | b'}') => self.close(b),
+            (Expect::CommaOrClose, b',') => {
+                self.pos += 1;
+                self.expect = if self.stack.last() == Some(&b'{') {
+                    Expect::Key
+                } else {
+                    Expect::Value
+                };
+                Ok(())
+            }
+            (Expect::Key | Expect::KeyOrClose, b'"') => {
+                self.pos = self.scan_string()?;
+                self.expect = Expect::Colon;
+                Ok(())
+            }
+            (Expect::Colon, b':') => {
+                self.pos += 1;
+                self.expect = Expect::Value;
+                Ok(())
+            }
+            (Expect::Value | Expect::ValueOrClose, _) => self.value(b),
+            _ => Err(self.unexpected(b)),
+        }
+    }
+
+    fn value(&mut self, b: u8) -> Result<(), Failure> {
+        match b {
+            b'{' | b'[' => {
+                self.stack.push(b);
+                self.pos += 1;
+                self.last_good = self.pos;
+                self.expect = if b == b'{' {
+                    Expect::KeyOrClose
+                } else {
+                    Expect::ValueOrClose
+                };
+                Ok(())
+            }
+            b'"' => {
+                let end = self.scan_string()?;
+                self.complete(end);
+                Ok(())
+            }
+            b'-' | b'0'..=b'9' => self.scan_number(),
+            b't' => self.scan_literal(b"true"),
+            b'f' => self.scan_literal(b"false"),
+            b'n' => self.scan_literal(b"null"),
+            _ => Err(self.unexpected(b)),
+        }
+    }
+
+    fn close(&mut self, b: u8) -> Result<(), Failure> {
+        let expected = if b == b'}' { b'{' } else { b'[' };
+        if self.stack.last() != Some(&expected) {
+            return Err(self.unexpected(b));
+        }
+        self.stack.pop();
+        self.complete(self.pos + 1);
+        Ok(())
+    }
+
+    fn complete(&mut self, end: usize) {
+        self.pos = end;
+        self.last_good = end;
+        self.expect = if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::CommaOrClose
+        };
+    }
+
+    // Returns the offset just past the closing quote.
+    fn scan_string(&self) -> Result<usize, Failure> {
+        let mut i = self.pos + 1;
+        while let Some(&b) = self.input.get(i) {
+            match b {
+                b'"' => return Ok(i + 1),
+                b'\\' => i = self.scan_escape(i)?,
+                0..0x20 => {
+                    return Err(Failure {
+                        offset: i,
+                        reason: "control character in string".to_string(),
+                    });
+                }
+                _ => i += 1,
+            }
+        }
+        Err(self.eof())
+    }
+
+    // Validates the escape at `i` (the backslash); returns the offset after.
+    fn scan_escape(&self, i: usize) -> Result<usize, Failure> {
+        let invalid = || Failure {
+            offset: i,
+            reason: "invalid escape".to_string(),
+        };
+        match self.input.get(i + 1) {
+            None => Err(self.eof()),
+            Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                Ok(i + 2)
+            }
+            Some(b'u') => {
+                let hex = self.input.get(i + 2..i + 6);
+                match hex {
+                    Some(h) if h.iter().all(u8::is_ascii_hexdigit) => {
+                        Ok(i + 6)
+                    }
+                    None if self.input[i + 2..]
+                        .iter()
+                        .all(u8::is_ascii_hexdigit) =>
+                    {
+                        Err(self.eof())
+                    }
+                    _ => Err(invalid()),
+                }
+            }
+            Some(_) => Err(invalid()),
+        }
+    }
+
+    fn scan_number(&mut self) -> Result<(), Failure> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .iter()
+            .position(|b| {
+                !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+            })
+            .unwrap_or(rest.len());
+        // A number running into EOF inside a container may have lost digits.
+        if self.pos + len == self.input.len() && !self.stack.is_empty() {
+            return Err(self.eof());
+        }
+        if !rest[..len].iter().any(u8::is_ascii_digit) {
+            return Err(self.unexpected(rest[0]));
+        }
+        self.complete(self.pos + len);
+        Ok(())
+    }
+
+    fn scan_literal(&mut self, word: &[u8]) -> Result<(), Failure> {
+        let rest = &self.input[self.pos..];
+        if rest.starts_with(word) {
+            self.complete(self.pos + word.len());
+            return Ok(());
+        }
+        if word.starts_with(rest) {
+            return Err(self.eof());
+        }
+        Err(self.unexpected(rest[0]))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn eof(&self) -> Failure {
+        Failure {
+            offset: self.input.len(),
+            reason: "unexpected EOF".to_string(),
+        }
+    }
+
+    fn unexpected(&self, b: u8) -> Failure {
+        let reason = if matches!(self.expect, Expect::Done) {
+            "trailing characters".to_string()
+        } else if b.is_ascii_graphic() {
+            format!("unexpected character '{}'", char::from(b))
+        } else {
+            format!("unexpected byte 0x{b:02x}")
+        };
+        Failure {
+            offset: self.pos,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repaired(input: &str) -> (String, usize, String, usize) {
+        let r = repair(input.as_bytes()).expect("repairable");
+        (
+            String::from_utf8(r.bytes).unwrap(),
+            r.offset,
+            r.reason,
+            r.open,
+        )
+    }
+
+    #[test]
+    fn truncated_inputs_are_cut_and_closed() {
+        let cases = [
+            (r#"{"a": [1, 2"#, r#"{"a": [1]}"#, 11, 2),
+            (r#"{"a": 1, "b"#, r#"{"a": 1}"#, 11, 1),
+            (r#"[{"a": "xy"#, "[{}]", 10, 2),
+            ("[true, fa", "[true]", 9, 1),
+            ("[1, 2, ", "[1, 2]", 7, 1),
+        ];
+        for (input, fixed, offset, open) in cases {
+            let (bytes, at, reason, depth) = repaired(input);
+            assert_eq!(
+                (bytes.as_str(), at, reason.as_str(), depth),
+                (fixed, offset, "unexpected EOF", open),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn syntax_errors_report_the_offending_character() {
+        let cases = [
+            (
+                r#"{"a": 1 "b": 2}"#,
+                r#"{"a": 1}"#,
+                8,
+                "unexpected character '\"'",
+            ),
+            ("[1, 2] x", "[1, 2]", 7, "trailing characters"),
+            ("[1, }", "[1]", 4, "unexpected character '}'"),
+        ];
+        for (input, fixed, offset, why) in cases {
+            let (bytes, at, reason, _) = repaired(input);
+            assert_eq!(
+                (bytes.as_str(), at, reason.as_str()),
+                (fixed, offset, why),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn first_error_covers_escapes_and_utf8() {
+        let cases: [(&[u8], usize, &str); 3] = [
+            (br#"["a\x"]"#, 3, "invalid escape"),
+            (b"[\"\xff\"]", 2, "invalid UTF-8"),
+            (br#"["\u12"#, 6, "unexpected EOF"),
+        ];
+        for (input, offset, reason) in cases {
+            assert_eq!(first_error(input), Some((offset, reason.to_string())));
+        }
+        assert_eq!(first_error(br#"["\u00e9"]"#), None);
+    }
+
+    #[test]
+    fn unrecoverable_or_valid_inputs_yield_none() {
+        for input in ["", "   ", "x", "\"abc", r#"{"a": 1}"#, "[1, 2]"] {
+            assert!(repair(input.as_bytes()).is_none(), "{input}");
+        }
+    }
+}
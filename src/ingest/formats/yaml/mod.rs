@@ -1,11 +1,14 @@
 use anyhow::{Result, anyhow};
 
 use crate::PriorityConfig;
+use crate::ingest::diagnostic::ParseDiagnostic;
 use crate::order::NodeKind;
 use crate::utils::tree_arena::{JsonTreeArena, JsonTreeNode};
 
 use crate::ingest::sampling::{ArraySamplerKind, choose_indices};
 use yaml_rust2::Yaml;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
 
 pub fn build_yaml_tree_arena_from_bytes(
     bytes: Vec<u8>,
@@ -13,21 +16,33 @@ pub fn build_yaml_tree_arena_from_bytes(
 ) -> Result<JsonTreeArena> {
     let s = String::from_utf8(bytes)
         .map_err(|_| anyhow!("input is not valid UTF-8 text"))?;
-    let docs = yaml_rust2::YamlLoader::load_from_str(&s)?;
+    build_yaml_tree_arena_from_str(&s, config)
+}
+
+fn build_yaml_tree_arena_from_str(
+    s: &str,
+    config: &PriorityConfig,
+) -> Result<JsonTreeArena> {
+    let docs = yaml_rust2::YamlLoader::load_from_str(s)?;
+    let lines = if config.source_lines {
+        source_lines(s)
+    } else {
+        Vec::new()
+    };
     let mut b = YamlArenaBuilder::new(
         config.array_max_items,
         config.array_sampler.into(),
     );
     let root_id = if docs.len() <= 1 {
         match docs.first() {
-            Some(doc) => b.build(doc),
-            None => b.build(&Yaml::Array(vec![])),
+            Some(doc) => b.build(doc, lines.first()),
+            None => b.build(&Yaml::Array(vec![]), None),
         }
     } else {
         // Multi-doc YAML in a single input -> wrap into an array root.
         let mut children: Vec<usize> = Vec::with_capacity(docs.len());
-        for d in &docs {
-            children.push(b.build(d));
+        for (i, d) in docs.iter().enumerate() {
+            children.push(b.build(d, lines.get(i)));
         }
         b.push_array(&children, docs.len(), (0..docs.len()).collect())
     };
@@ -36,6 +51,87 @@ pub fn build_yaml_tree_arena_from_bytes(
     Ok(arena)
 }
 
+// Source line of a value and of its children (sequence items and mapping
+// values, in order), mirroring one loaded document.
+struct LineTree {
+    line: usize,
+    children: Vec<LineTree>,
+}
+
+struct OpenNode {
+    tree: LineTree,
+    mapping: bool,
+    // Mappings alternate key and value events. Keys are not recorded; a
+    // value takes its key's line, where the entry starts (block values are
+    // marked further down).
+    key_line: Option<usize>,
+}
+
+// Builds one `LineTree` per document from the parser's marked events.
+#[derive(Default)]
+struct LineCollector {
+    docs: Vec<LineTree>,
+    open: Vec<OpenNode>,
+}
+
+impl LineCollector {
+    fn open(&mut self, mark: Marker, mapping: bool) {
+        self.open.push(OpenNode {
+            tree: LineTree {
+                line: mark.line(),
+                children: Vec::new(),
+            },
+            mapping,
+            key_line: None,
+        });
+    }
+
+    fn close(&mut self, tree: LineTree) {
+        match self.open.last_mut() {
+            None => self.docs.push(tree),
+            Some(parent) if parent.mapping => match parent.key_line.take() {
+                None => parent.key_line = Some(tree.line),
+                Some(line) => {
+                    parent.tree.children.push(LineTree { line, ..tree });
+                }
+            },
+            Some(parent) => parent.tree.children.push(tree),
+        }
+    }
+}
+
+impl MarkedEventReceiver for LineCollector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::SequenceStart(..) => self.open(mark, false),
+            Event::MappingStart(..) => self.open(mark, true),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some(node) = self.open.pop() {
+                    self.close(node.tree);
+                }
+            }
+            Event::Scalar(..) | Event::Alias(_) => self.close(LineTree {
+                line: mark.line(),
+                children: Vec::new(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+// The loader drops positions, so a second pass over the parser's events
+// records them; documents line up with `YamlLoader`'s output.
+fn source_lines(text: &str) -> Vec<LineTree> {
+    let mut collector = LineCollector::default();
+    if Parser::new_from_str(text)
+        .load(&mut collector, true)
+        .is_err()
+    {
+        return Vec::new();
+    }
+    collector.docs
+}
+
 struct YamlArenaBuilder {
     arena: JsonTreeArena,
     array_cap: usize,
@@ -151,11 +247,19 @@ impl YamlArenaBuilder {
         n.object_len = Some(count);
     }
 
+    fn build(&mut self, y: &Yaml, lines: Option<&LineTree>) -> usize {
+        let id = self.build_value(y, lines);
+        self.arena.nodes[id].source_line = lines.map(|tree| tree.line);
+        id
+    }
+
     #[allow(
         clippy::cognitive_complexity,
         reason = "YAML node conversion keeps all cases local for clarity"
     )]
-    fn build(&mut self, y: &Yaml) -> usize {
+    fn build_value(&mut self, y: &Yaml, lines: Option<&LineTree>) -> usize {
+        let child_lines =
+            |i: usize| lines.and_then(|tree| tree.children.get(i));
         match y {
             Yaml::Array(v) => {
                 let total = v.len();
@@ -163,7 +267,7 @@ impl YamlArenaBuilder {
                 let mut child_ids = Vec::with_capacity(idxs.len());
                 for i in &idxs {
                     if let Some(item) = v.get(*i) {
-                        child_ids.push(self.build(item));
+                        child_ids.push(self.build(item, child_lines(*i)));
                     }
                 }
                 self.push_array(&child_ids, total, idxs)
@@ -171,9 +275,9 @@ impl YamlArenaBuilder {
             Yaml::Hash(hm) => {
                 let mut keys: Vec<String> = Vec::with_capacity(hm.len());
                 let mut children: Vec<usize> = Vec::with_capacity(hm.len());
-                for (k, v) in hm.iter() {
+                for (i, (k, v)) in hm.iter().enumerate() {
                     let key = stringify_yaml_key(k);
-                    let cid = self.build(v);
+                    let cid = self.build(v, child_lines(i));
                     keys.push(key);
                     children.push(cid);
                 }
@@ -240,12 +344,50 @@ fn stringify_yaml_key(k: &Yaml) -> String {
     }
 }
 
-/// Convenience functions for the YAML ingest path.
+/// Convenience functions for the YAML ingest path. Failures are reported as
+/// a `ParseDiagnostic`.
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Signature stays aligned with other ingest helpers and public API"
+)]
 pub fn parse_yaml_one(
     bytes: Vec<u8>,
     cfg: &PriorityConfig,
 ) -> Result<JsonTreeArena> {
-    build_yaml_tree_arena_from_bytes(bytes, cfg)
+    parse_yaml_slice(&bytes, cfg)
+}
+
+// Borrows the input: the loader reads a `&str`, and diagnostics point into
+// the original bytes.
+pub(crate) fn parse_yaml_slice(
+    bytes: &[u8],
+    cfg: &PriorityConfig,
+) -> Result<JsonTreeArena> {
+    std::str::from_utf8(bytes)
+        .map_err(|_| anyhow!("input is not valid UTF-8 text"))
+        .and_then(|s| build_yaml_tree_arena_from_str(s, cfg))
+        .map_err(|err| yaml_diagnostic(bytes, &err).into())
+}
+
+fn yaml_diagnostic(source: &[u8], err: &anyhow::Error) -> ParseDiagnostic {
+    if let Err(utf8) = std::str::from_utf8(source) {
+        return ParseDiagnostic::new(
+            "YAML",
+            "invalid UTF-8",
+            source,
+            utf8.valid_up_to(),
+        );
+    }
+    let Some(scan) = err.downcast_ref::<yaml_rust2::ScanError>() else {
+        return ParseDiagnostic::new("YAML", err.to_string(), source, 0);
+    };
+    // Markers count chars; the diagnostic wants a byte offset.
+    let text = String::from_utf8_lossy(source);
+    let offset = text
+        .char_indices()
+        .nth(scan.marker().index())
+        .map_or(source.len(), |(at, _)| at);
+    ParseDiagnostic::new("YAML", scan.info(), source, offset)
 }
 
 #[cfg(test)]
@@ -273,4 +415,19 @@ mod tests {
         }
         assert_eq!(orig_indices, vec![5, 6, 7, 8, 9]);
     }
+
+    #[test]
+    fn values_record_their_source_lines() {
+        let input =
+            b"a: 1\nlist:\n  - x\n  - {k: v}\nblock: |\n  text\n".to_vec();
+        let mut cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        cfg.source_lines = true;
+        let arena =
+            build_yaml_tree_arena_from_bytes(input, &cfg).expect("arena");
+        let lines: Vec<Option<usize>> =
+            arena.nodes.iter().map(|n| n.source_line).collect();
+        // Arena order: 1, x, v, {k: v}, list, text, root. Mapping values sit
+        // on their key's line.
+        assert_eq!(lines, [1, 3, 4, 4, 2, 5, 1].map(Some));
+    }
 }
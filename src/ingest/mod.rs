@@ -4,7 +4,10 @@ use crate::order::PriorityConfig;
 use crate::utils::tree_arena::JsonTreeArena as TreeArena;
 
 use crate::InputKind;
+use fileset::FilesetInputKind;
 
+pub mod diagnostic;
+mod embedded;
 pub mod fileset;
 pub mod format;
 pub mod formats;
@@ -22,17 +25,47 @@ pub fn ingest_into_arena(
     input: InputKind,
     priority_cfg: &PriorityConfig,
 ) -> Result<TreeArena> {
-    match input {
-        InputKind::Json(bytes) => parse_json_one(bytes, priority_cfg),
-        InputKind::Yaml(bytes) => parse_yaml_one(bytes, priority_cfg),
+    let (mut arena, kind) = match input {
+        InputKind::Json(bytes) => (
+            with_embedded(parse_json_one(bytes, priority_cfg)?, priority_cfg),
+            FilesetInputKind::Json,
+        ),
+        InputKind::Yaml(bytes) => (
+            with_embedded(parse_yaml_one(bytes, priority_cfg)?, priority_cfg),
+            FilesetInputKind::Yaml,
+        ),
         InputKind::Text { bytes, mode } => {
             let atomic = matches!(mode, crate::TextMode::CodeLike);
-            parse_text_one_with_mode(bytes, priority_cfg, atomic)
+            (
+                parse_text_one_with_mode(bytes, priority_cfg, atomic)?,
+                FilesetInputKind::Text {
+                    atomic_lines: atomic,
+                },
+            )
         }
         InputKind::Fileset(inputs) => {
-            fileset::parse_fileset_multi(inputs, priority_cfg)
+            return fileset::parse_fileset_multi(inputs, priority_cfg);
         }
+        InputKind::FilesetWithLayout(inputs, layout) => {
+            let mut arena =
+                fileset::parse_fileset_multi(inputs, priority_cfg)?;
+            layout.apply_weights(&mut arena, None);
+            return Ok(arena);
+        }
+    };
+    arena.input_kind = Some(kind);
+    Ok(arena)
+}
+
+/// Apply the opt-in embedded-string expansion to a structured arena.
+pub(crate) fn with_embedded(
+    mut arena: TreeArena,
+    priority_cfg: &PriorityConfig,
+) -> TreeArena {
+    if priority_cfg.expand_embedded {
+        embedded::expand_embedded_strings(&mut arena, priority_cfg);
     }
+    arena
 }
 
 #[cfg(test)]
@@ -22,15 +22,25 @@ use anyhow::Result;
 
 pub mod budget;
 mod debug;
+mod diff;
 mod grep;
 mod ingest;
 mod order;
 mod pruner;
 mod serialization;
+mod stats;
 mod utils;
-pub use grep::build_grep_config;
-pub use grep::{GrepConfig, GrepShow};
-pub use ingest::fileset::{FilesetInput, FilesetInputKind};
+pub use diff::DiffMark;
+pub use grep::{GrepConfig, GrepFileSummary, GrepMatch, GrepReport, GrepShow};
+pub use grep::{
+    Predicate, build_grep_config, build_grep_config_with_predicates,
+};
+pub use ingest::diagnostic::{ParseDiagnostic, attribute_parse_error};
+pub use ingest::fileset::{
+    FilesetInput, FilesetInputKind, FilesetLayout, FilesetParseFailure,
+    MatchSort,
+};
+pub use ingest::format::Format;
 pub use order::types::{ArrayBias, ArraySamplerStrategy};
 pub use order::{
     NodeId, NodeKind, PriorityConfig, PriorityOrder, RankedNode, build_order,
@@ -43,8 +53,9 @@ pub use pruner::budget::{
 };
 pub use serialization::color::resolve_color_enabled;
 pub use serialization::types::{
-    ColorMode, ColorStrategy, OutputTemplate, RenderConfig, Style,
+    ColorMode, ColorStrategy, GrepColor, OutputTemplate, RenderConfig, Style,
 };
+pub use stats::{BudgetStats, FileStats, RenderStats};
 
 #[derive(Copy, Clone, Debug)]
 pub enum TextMode {
@@ -55,8 +66,29 @@ pub enum TextMode {
 pub enum InputKind {
     Json(Vec<u8>),
     Yaml(Vec<u8>),
-    Text { bytes: Vec<u8>, mode: TextMode },
+    Text {
+        bytes: Vec<u8>,
+        mode: TextMode,
+    },
     Fileset(Vec<FilesetInput>),
+    /// A fileset with budget shares and an order for its files.
+    FilesetWithLayout(Vec<FilesetInput>, FilesetLayout),
+}
+
+/// Rendered preview together with run metadata.
+#[derive(Debug)]
+pub struct Rendered {
+    pub text: String,
+    // Present whenever a grep (strong or weak) regex was configured.
+    pub grep_report: Option<GrepReport>,
+    // Fileset entries previewed as text because they failed to parse.
+    pub parse_failures: Vec<FilesetParseFailure>,
+    // Malformed JSON inputs recovered up to their first error, as the
+    // truncation note (prefixed with the file name in filesets).
+    pub recovered: Vec<String>,
+    // The preview leaves part of the input out (budget or ingest caps).
+    pub truncated: bool,
+    pub stats: RenderStats,
 }
 
 pub fn headson(
@@ -66,18 +98,124 @@ pub fn headson(
     grep: &GrepConfig,
     budgets: Budgets,
 ) -> Result<String> {
+    headson_with_report(input, config, priority_cfg, grep, budgets)
+        .map(|rendered| rendered.text)
+}
+
+/// Like [`headson`], but also returns metadata such as the grep match report.
+pub fn headson_with_report(
+    input: InputKind,
+    config: &RenderConfig,
+    priority_cfg: &PriorityConfig,
+    grep: &GrepConfig,
+    budgets: Budgets,
+) -> Result<Rendered> {
     let mut prio = *priority_cfg;
-    if grep.regex.is_some() && !grep.weak {
+    if grep.is_active() && !grep.weak {
         // Avoid sampling away potential matches in strong grep mode.
         prio.array_max_items = usize::MAX;
     }
-    let arena = crate::ingest::ingest_into_arena(input, &prio)?;
-    let mut order_build = order::build_order(&arena, &prio)?;
+    let (arena, mut order_build) = ingest_and_order(input, grep, &prio)?;
 
-    Ok(find_largest_render_under_budgets(
+    let out = pruner::budget::render_under_budgets(
         &mut order_build,
         config,
         grep,
         budgets,
-    ))
+    );
+    let recovered = if arena.is_fileset {
+        arena.fileset_recovered
+    } else {
+        arena.truncations.into_values().collect()
+    };
+    Ok(Rendered {
+        text: out.text,
+        grep_report: out.grep_report,
+        parse_failures: arena.fileset_parse_failures,
+        recovered,
+        truncated: out.truncated,
+        stats: out.stats,
+    })
+}
+
+// Budget shares and file order of a fileset laid out by grep matches follow
+// the matches the render keeps, so they are counted on the built order, which
+// is built again once arranged.
+fn ingest_and_order(
+    input: InputKind,
+    grep: &GrepConfig,
+    prio: &PriorityConfig,
+) -> Result<(utils::tree_arena::JsonTreeArena, PriorityOrder)> {
+    let match_layout = match &input {
+        InputKind::FilesetWithLayout(_, layout) if layout.uses_matches() => {
+            Some(layout.clone())
+        }
+        _ => None,
+    };
+    let mut arena = crate::ingest::ingest_into_arena(input, prio)?;
+    let order_build = order::build_order(&arena, prio)?;
+    let Some(layout) = match_layout else {
+        return Ok((arena, order_build));
+    };
+    let matches = grep::matches_per_file(&order_build, grep);
+    layout.apply_weights(&mut arena, Some(&matches));
+    layout.apply_order(&mut arena, &matches);
+    let arranged = order::build_order(&arena, prio)?;
+    Ok((arena, arranged))
+}
+
+/// Structural diff preview of two documents.
+#[derive(Debug)]
+pub struct DiffOutput {
+    pub text: String,
+    // Number of added, removed or replaced values.
+    pub changes: usize,
+    // Whether the budget left part of the diff out.
+    pub truncated: bool,
+}
+
+/// Render only what differs between `old` and `new` (JSON or YAML), with
+/// removed and added nodes marked in a `-`/`+` gutter. Unchanged siblings
+/// are folded into omission markers.
+pub fn headson_diff(
+    old: InputKind,
+    new: InputKind,
+    config: &RenderConfig,
+    priority_cfg: &PriorityConfig,
+    budgets: Budgets,
+) -> Result<DiffOutput> {
+    let mut prio = *priority_cfg;
+    // Sampling would hide array elements from the alignment.
+    prio.array_max_items = usize::MAX;
+    let old_arena = diff_side_arena(old, &prio)?;
+    let new_arena = diff_side_arena(new, &prio)?;
+    let diff = diff::diff_arenas(&old_arena, &new_arena);
+    let mut order_build = order::build_order(&diff.arena, &prio)?;
+    let out = pruner::budget::render_under_budgets(
+        &mut order_build,
+        config,
+        &GrepConfig::default(),
+        budgets,
+    );
+    Ok(DiffOutput {
+        text: out.text,
+        changes: diff.changes,
+        truncated: out.truncated,
+    })
+}
+
+fn diff_side_arena(
+    input: InputKind,
+    prio: &PriorityConfig,
+) -> Result<utils::tree_arena::JsonTreeArena> {
+    match input {
+        InputKind::Json(_) | InputKind::Yaml(_) => {
+            crate::ingest::ingest_into_arena(input, prio)
+        }
+        InputKind::Text { .. }
+        | InputKind::Fileset(_)
+        | InputKind::FilesetWithLayout(..) => {
+            anyhow::bail!("diff supports only JSON and YAML inputs")
+        }
+    }
 }
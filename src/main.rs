@@ -5,20 +5,31 @@
 mod cli;
 mod sorting;
 
+use std::process::ExitCode;
+
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::args::Cli;
+use crate::cli::args::{Cli, Command};
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
-    let (output, ignore_notices) = crate::cli::run::run(&cli)?;
-    println!("{output}");
+    let (output, fail_on) = match &cli.command {
+        Some(Command::Diff(args)) => {
+            (crate::cli::diff::run_diff(args)?, &args.fail_on)
+        }
+        None => (crate::cli::run::run(&cli)?, &cli.fail_on),
+    };
+    println!("{}", output.text);
 
-    for notice in ignore_notices {
-        eprintln!("{notice}");
+    for notice in &output.notices {
+        eprintln!("{}", notice.message);
     }
 
-    Ok(())
+    Ok(ExitCode::from(crate::cli::notice::exit_code(
+        &output.notices,
+        output.truncated,
+        fail_on,
+    )))
 }
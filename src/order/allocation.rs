@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use super::types::NodeId;
+
+/// Whether `weights` differ from an equal share, i.e. whether the weighted
+/// drain would change the plain round-robin order.
+pub(crate) fn is_weighted(weights: &[f64]) -> bool {
+    weights.first().is_some_and(|first| {
+        weights.iter().any(|w| w.total_cmp(first).is_ne())
+    })
+}
+
+/// Drain `buckets` so each slot's share of the output follows its weight.
+/// Every non-empty slot first contributes one node (its file root, so no file
+/// disappears entirely); after that, slots are picked by smooth weighted
+/// round-robin, which spreads a heavy slot's turns evenly instead of in
+/// bursts. Non-positive or non-finite weights count as 1.
+pub(crate) fn drain_weighted(
+    buckets: &mut [VecDeque<NodeId>],
+    weights: &[f64],
+) -> Vec<NodeId> {
+    let total: usize = buckets.iter().map(VecDeque::len).sum();
+    let mut out: Vec<NodeId> = Vec::with_capacity(total);
+    out.extend(buckets.iter_mut().filter_map(VecDeque::pop_front));
+    let weights: Vec<f64> = (0..buckets.len())
+        .map(|slot| sanitize(weights.get(slot).copied()))
+        .collect();
+    let mut credit = vec![0.0f64; buckets.len()];
+    while let Some(slot) = next_slot(buckets, &weights, &mut credit) {
+        if let Some(node) = buckets[slot].pop_front() {
+            out.push(node);
+        }
+    }
+    out
+}
+
+fn sanitize(weight: Option<f64>) -> f64 {
+    match weight {
+        Some(w) if w.is_finite() && w > 0.0 => w,
+        _ => 1.0,
+    }
+}
+
+// One step of smooth weighted round-robin over the non-empty slots: every
+// slot earns its weight, the richest one is picked and pays the total.
+fn next_slot(
+    buckets: &[VecDeque<NodeId>],
+    weights: &[f64],
+    credit: &mut [f64],
+) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    let mut active_total = 0.0;
+    for (slot, bucket) in buckets.iter().enumerate() {
+        if bucket.is_empty() {
+            continue;
+        }
+        credit[slot] += weights[slot];
+        active_total += weights[slot];
+        if best.is_none_or(|b| credit[slot] > credit[b]) {
+            best = Some(slot);
+        }
+    }
+    let slot = best?;
+    credit[slot] -= active_total;
+    Some(slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(sizes: &[usize]) -> Vec<VecDeque<NodeId>> {
+        let mut next = 0;
+        sizes
+            .iter()
+            .map(|&n| {
+                let bucket = (next..next + n).map(NodeId).collect();
+                next += n;
+                bucket
+            })
+            .collect()
+    }
+
+    #[test]
+    fn heavier_slot_gets_proportionally_more_turns() {
+        let mut b = buckets(&[10, 10]);
+        let out = drain_weighted(&mut b, &[3.0, 1.0]);
+        // Roots first, then three turns of slot 0 for each of slot 1.
+        let first: Vec<usize> = out.iter().take(10).map(|n| n.0).collect();
+        assert_eq!(first, vec![0, 10, 1, 2, 11, 3, 4, 5, 12, 6]);
+        assert_eq!(out.len(), 20);
+    }
+
+    #[test]
+    fn equal_weights_are_not_weighted() {
+        assert!(!is_weighted(&[2.0, 2.0]));
+        assert!(is_weighted(&[2.0, 1.0]));
+        assert!(!is_weighted(&[]));
+    }
+}
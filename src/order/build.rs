@@ -4,6 +4,7 @@ use std::collections::{BinaryHeap, HashMap, VecDeque};
 use std::sync::Arc;
 use unicode_segmentation::UnicodeSegmentation;
 
+use super::allocation::{drain_weighted, is_weighted};
 use super::scoring::*;
 use super::types::*;
 use crate::utils::tree_arena::{JsonTreeArena, JsonTreeNode};
@@ -105,19 +106,26 @@ fn place_roots_front(
 
 /// Reorder `by_priority` so each fileset contributes one node before any file
 /// gets a second turn. This keeps tight budgets from starving later files.
+/// With `weights`, files take turns in proportion to their weight instead.
 fn interleave_fileset_priority(
     by_priority: &mut Vec<NodeId>,
     node_slots: &[Option<usize>],
     file_roots: &[NodeId],
+    weights: Option<&[f64]>,
 ) {
     if file_roots.is_empty() {
         return;
     }
-    let (prefix, buckets) =
+    let (mut prefix, buckets) =
         split_priority_by_slot(by_priority, node_slots, file_roots.len());
-    let buckets = place_roots_front(buckets, file_roots);
-    let new_order = collect_round_robin(prefix, buckets, by_priority.len());
-    *by_priority = new_order;
+    let mut buckets = place_roots_front(buckets, file_roots);
+    *by_priority = match weights {
+        Some(weights) => {
+            prefix.extend(drain_weighted(&mut buckets, weights));
+            prefix
+        }
+        None => collect_round_robin(prefix, buckets, by_priority.len()),
+    };
 }
 
 #[derive(Default)]
@@ -337,6 +345,9 @@ impl<'a> Scope<'a> {
         kind: NodeKind,
         arena_id: usize,
     ) {
+        self.metrics[id].embedded = self.arena.nodes[arena_id].embedded;
+        self.metrics[id].diff = self.arena.nodes[arena_id].diff;
+        self.metrics[id].source_line = self.arena.nodes[arena_id].source_line;
         match kind {
             NodeKind::Array => self.record_array_metrics(id, arena_id),
             NodeKind::Object => self.record_object_metrics(id, arena_id),
@@ -814,16 +825,31 @@ pub fn build_order(
         }
     }
 
+    let fileset_weights = is_weighted(&arena.fileset_weights)
+        .then(|| arena.fileset_weights.clone());
+    let mut fileset_sizes = None;
+    let mut fileset_kinds = None;
     let fileset_children = if arena.is_fileset {
         let root = &arena.nodes[arena.root_id];
         let mut ids: Vec<NodeId> = Vec::with_capacity(root.children_len);
+        let mut sizes: Vec<usize> = Vec::with_capacity(root.children_len);
+        let mut kinds = Vec::with_capacity(root.children_len);
         for idx in 0..root.children_len {
             let child_arena_id = arena.children[root.children_start + idx];
             if let Some(Some(pq_id)) = arena_to_pq.get(child_arena_id) {
                 ids.push(NodeId(*pq_id));
+                sizes.extend(arena.fileset_sizes.get(idx).copied());
+                kinds.extend(arena.fileset_kinds.get(idx).copied());
             }
         }
-        interleave_fileset_priority(&mut order, &node_slots, &ids);
+        interleave_fileset_priority(
+            &mut order,
+            &node_slots,
+            &ids,
+            fileset_weights.as_deref(),
+        );
+        fileset_sizes = (sizes.len() == ids.len()).then_some(sizes);
+        fileset_kinds = (kinds.len() == ids.len()).then_some(kinds);
         Some(ids)
     } else {
         None
@@ -836,6 +862,12 @@ pub fn build_order(
             code_lines.insert(*pq_id, Arc::clone(lines));
         }
     }
+    let mut truncations: HashMap<usize, String> = HashMap::new();
+    for (arena_idx, note) in &arena.truncations {
+        if let Some(Some(pq_id)) = arena_to_pq.get(*arena_idx) {
+            truncations.insert(*pq_id, note.clone());
+        }
+    }
     Ok(PriorityOrder {
         metrics,
         nodes,
@@ -847,7 +879,12 @@ pub fn build_order(
         total_nodes: total,
         object_type,
         code_lines,
+        truncations,
         fileset_children,
+        fileset_weights,
+        fileset_sizes,
+        fileset_kinds,
+        input_kind: arena.input_kind,
     })
 }
 
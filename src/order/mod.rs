@@ -1,3 +1,4 @@
+pub(crate) mod allocation;
 pub mod build;
 pub mod scoring;
 pub mod types;
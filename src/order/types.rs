@@ -14,6 +14,17 @@ pub struct PriorityConfig {
     // Indicates that rendering may favor structural breadth over deep string
     // expansion under line-capped previews.
     pub line_budget_only: bool,
+    // Parse string leaves holding serialized JSON/YAML into subtrees.
+    pub expand_embedded: bool,
+    // Fail on input that does not parse as its format, instead of ingesting
+    // broken fileset files as text.
+    pub strict_parse: bool,
+    // Read malformed JSON up to its first error and close what is still
+    // open, instead of failing.
+    pub recover_json: bool,
+    // Record the source line of each JSON/YAML value so the preview can show
+    // where it came from.
+    pub source_lines: bool,
 }
 
 impl PriorityConfig {
@@ -25,6 +36,10 @@ impl PriorityConfig {
             array_bias: ArrayBias::HeadMidTail,
             array_sampler: ArraySamplerStrategy::Default,
             line_budget_only: false,
+            expand_embedded: false,
+            strict_parse: false,
+            recover_json: false,
+            source_lines: false,
         }
     }
 
@@ -51,6 +66,10 @@ impl PriorityConfig {
             array_bias: ArrayBias::HeadMidTail,
             array_sampler,
             line_budget_only,
+            expand_embedded: false,
+            strict_parse: false,
+            recover_json: false,
+            source_lines: false,
         }
     }
 }
@@ -159,6 +178,11 @@ pub struct NodeMetrics {
     pub object_len: Option<usize>,
     pub string_len: Option<usize>,
     pub string_truncated: bool,
+    // Container expanded from a string value holding serialized JSON/YAML.
+    pub embedded: bool,
+    pub diff: Option<crate::diff::DiffMark>,
+    // Line in the source input, when ingest recorded it.
+    pub source_line: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
@@ -177,9 +201,36 @@ pub struct PriorityOrder {
     pub total_nodes: usize,
     pub object_type: Vec<ObjectType>,
     pub code_lines: HashMap<usize, Arc<Vec<String>>>,
+    // Truncation notes for containers where recovering JSON ingest cut off
+    // malformed input (by PQ id).
+    pub truncations: HashMap<usize, String>,
     // For filesets, preserve the ingest order of top-level children so rendering
     // can respect pre-sorting heuristics (e.g., frecency).
     pub fileset_children: Option<Vec<NodeId>>,
+    // Per-file budget shares (parallel to `fileset_children`) when files are
+    // not weighted equally.
+    pub fileset_weights: Option<Vec<f64>>,
+    // Input size in bytes of each file (parallel to `fileset_children`), when
+    // known.
+    pub fileset_sizes: Option<Vec<usize>>,
+    // How each file was ingested (parallel to `fileset_children`), when known.
+    pub fileset_kinds: Option<Vec<crate::FilesetInputKind>>,
+    // How a single (non-fileset) input was ingested, when known.
+    pub input_kind: Option<crate::FilesetInputKind>,
+}
+
+impl PriorityOrder {
+    /// Whether this order comes from a structural diff, which renders with a
+    /// `+`/`-` gutter.
+    pub fn has_diff_marks(&self) -> bool {
+        self.metrics.iter().any(|m| m.diff.is_some())
+    }
+
+    /// Highest source line recorded at ingest; Some when the preview should
+    /// carry a line-number gutter.
+    pub fn max_source_line(&self) -> Option<usize> {
+        self.metrics.iter().filter_map(|m| m.source_line).max()
+    }
 }
 
 pub const ROOT_PQ_ID: usize = 0;
@@ -1,7 +1,9 @@
 use crate::grep::{
-    GrepShow, GrepState, compute_grep_state, reorder_priority_with_must_keep,
+    GrepReport, GrepShow, GrepState, build_grep_report, compute_grep_state,
+    reorder_priority_with_must_keep,
 };
 use crate::order::{NodeId, ObjectType, ROOT_PQ_ID};
+use crate::stats::{RenderStats, StatsArgs, unselected_render_stats};
 use crate::utils::measure::{OutputStats, count_output_stats};
 use crate::{GrepConfig, PriorityOrder, RenderConfig};
 use std::collections::VecDeque;
@@ -34,6 +36,8 @@ struct SelectionOutcome {
     inclusion_flags: Vec<u32>,
     render_set_id: u32,
     selection_order: Option<Vec<NodeId>>,
+    // Budgets that kept the selection from growing.
+    binding: Vec<&'static str>,
 }
 
 impl Budget {
@@ -121,21 +125,55 @@ fn is_fileset_root(order_build: &PriorityOrder) -> bool {
         .is_some_and(|t| *t == ObjectType::Fileset)
 }
 
+/// Final render plus the grep match report (when grep is active).
+pub(crate) struct BudgetedRender {
+    pub text: String,
+    pub grep_report: Option<GrepReport>,
+    // The output leaves part of the input out.
+    pub truncated: bool,
+    pub stats: RenderStats,
+}
+
+struct FinalRender {
+    text: String,
+    inclusion_flags: Vec<u32>,
+    render_set_id: u32,
+}
+
 pub fn find_largest_render_under_budgets(
     order_build: &mut PriorityOrder,
     config: &RenderConfig,
     grep: &GrepConfig,
     budgets: Budgets,
 ) -> String {
+    render_under_budgets(order_build, config, grep, budgets).text
+}
+
+pub(crate) fn render_under_budgets(
+    order_build: &mut PriorityOrder,
+    config: &RenderConfig,
+    grep: &GrepConfig,
+    budgets: Budgets,
+) -> BudgetedRender {
     let total = order_build.total_nodes;
     if total == 0 {
-        return String::new();
+        return BudgetedRender {
+            text: String::new(),
+            grep_report: grep.is_active().then(GrepReport::default),
+            truncated: false,
+            stats: unselected_render_stats(order_build, config, budgets, ""),
+        };
     }
     let root_is_fileset = is_fileset_root(order_build);
     let mut grep_state = compute_grep_state(order_build, grep);
     if strong_fileset_grep_without_matches(grep, &grep_state, root_is_fileset)
     {
-        return String::new();
+        return BudgetedRender {
+            text: String::new(),
+            grep_report: Some(GrepReport::default()),
+            truncated: false,
+            stats: unselected_render_stats(order_build, config, budgets, ""),
+        };
     }
     filter_fileset_without_matches(
         order_build,
@@ -143,6 +181,9 @@ pub fn find_largest_render_under_budgets(
         grep,
         config.fileset_tree,
     );
+    if matches!(grep.show, GrepShow::Names) {
+        return names_only(order_build, grep_state.as_ref(), config, budgets);
+    }
     reorder_if_grep(order_build, &grep_state);
     let fileset_slots = FilesetSlots::new(order_build);
     let header_budgeting = header_budgeting_policy(order_build, config);
@@ -167,15 +208,106 @@ pub fn find_largest_render_under_budgets(
         grep_state: &grep_state,
         must_keep: must_keep_slice,
     };
-    finalize_render_from_selection(
+    let mut selection = select_for_show(&selection_ctx);
+    let binding = std::mem::take(&mut selection.binding);
+    let final_render = finalize_render_from_selection(
         order_build,
         config,
         header_budgeting,
-        select_best_k(&selection_ctx),
+        selection,
         root_is_fileset,
         &finalize_ctx,
-    )
-    .unwrap_or_default()
+    );
+    let grep_report = grep.is_active().then(|| {
+        let (flags, render_id) = final_render
+            .as_ref()
+            .map(|f| (f.inclusion_flags.as_slice(), f.render_set_id))
+            .unwrap_or((&[], 0));
+        build_grep_report(
+            order_build,
+            grep_state.as_ref(),
+            flags,
+            render_id,
+            config.primary_source_name.as_deref(),
+        )
+    });
+    let truncated = final_render.as_ref().is_none_or(|f| {
+        leaves_out_input(order_build, &f.inclusion_flags, f.render_set_id)
+    });
+    let stats = crate::stats::render_stats(StatsArgs {
+        order: order_build,
+        inclusion_flags: final_render
+            .as_ref()
+            .map_or(&[], |f| f.inclusion_flags.as_slice()),
+        render_id: final_render.as_ref().map_or(0, |f| f.render_set_id),
+        config,
+        budgets,
+        binding,
+        text: final_render.as_ref().map_or("", |f| f.text.as_str()),
+    });
+    BudgetedRender {
+        text: final_render.map(|f| f.text).unwrap_or_default(),
+        grep_report,
+        truncated,
+        stats,
+    }
+}
+
+// True when a node was left out of the render, or a rendered array or
+// string was already cut short at ingest.
+fn leaves_out_input(
+    order_build: &PriorityOrder,
+    inclusion_flags: &[u32],
+    render_set_id: u32,
+) -> bool {
+    (0..order_build.total_nodes).any(|id| {
+        let metrics = &order_build.metrics[id];
+        inclusion_flags.get(id) != Some(&render_set_id)
+            || metrics.string_truncated
+            || metrics
+                .array_len
+                .is_some_and(|len| len > order_build.children[id].len())
+    })
+}
+
+// `--grep-show names`: report per-file match counts without rendering.
+// Every match counts as shown since no budget pruned it.
+fn names_only(
+    order_build: &PriorityOrder,
+    grep_state: Option<&GrepState>,
+    config: &RenderConfig,
+    budgets: Budgets,
+) -> BudgetedRender {
+    let report = build_grep_report(
+        order_build,
+        grep_state,
+        &vec![1; order_build.total_nodes],
+        1,
+        config.primary_source_name.as_deref(),
+    );
+    let text = report.file_lines().join("\n");
+    let stats = unselected_render_stats(order_build, config, budgets, &text);
+    BudgetedRender {
+        text,
+        grep_report: Some(report),
+        truncated: false,
+        stats,
+    }
+}
+
+// `--grep-show summary` selects nothing beyond the must-keep set, which
+// finalization adds back for strong grep.
+fn select_for_show(ctx: &SelectionContext<'_>) -> SelectionOutcome {
+    if !matches!(ctx.grep.show, GrepShow::Summary) {
+        return select_best_k(ctx);
+    }
+    SelectionOutcome {
+        k: Some(0),
+        inclusion_flags: vec![0; ctx.order_build.total_nodes],
+        render_set_id: 1,
+        selection_order: None,
+        binding: Vec::new(),
+    }
 }
 
 struct FinalizeContext<'a> {
@@ -194,12 +326,13 @@ fn finalize_render_from_selection(
     selection: SelectionOutcome,
     root_is_fileset: bool,
     finalize_ctx: &FinalizeContext<'_>,
-) -> Option<String> {
+) -> Option<FinalRender> {
     let SelectionOutcome {
         k: k_opt,
         mut inclusion_flags,
         render_set_id,
         selection_order,
+        ..
     } = selection;
     let found_k = k_opt.is_some();
     let k = k_opt.unwrap_or(0);
@@ -229,7 +362,7 @@ fn finalize_render_from_selection(
         &mut inclusion_flags,
         render_set_id,
     );
-    if per_slot_caps_active && !config.count_fileset_headers_in_budgets {
+    if per_slot_caps_active && !config.charges_fileset_headers() {
         ensure_fileset_headers_for_empty_slots(
             order_build,
             render_set_id,
@@ -262,7 +395,12 @@ fn finalize_render_from_selection(
         );
     }
 
-    Some(crate::serialization::render_from_render_set(
+    let highlight_nodes = finalize_ctx
+        .grep_state
+        .as_ref()
+        .map(|s| s.predicate_hits.as_slice())
+        .filter(|hits| !hits.is_empty());
+    let text = crate::serialization::render_from_render_set_with_highlights(
         order_build,
         &inclusion_flags,
         render_set_id,
@@ -270,10 +408,17 @@ fn finalize_render_from_selection(
             grep_highlight: config
                 .grep_highlight
                 .clone()
-                .or_else(|| finalize_ctx.grep.regex.clone()),
+                .or_else(|| finalize_ctx.grep.highlight_regex()),
+            grep_active: config.grep_active || finalize_ctx.grep.is_active(),
             ..config.clone()
         },
-    ))
+        highlight_nodes,
+    );
+    Some(FinalRender {
+        text,
+        inclusion_flags,
+        render_set_id,
+    })
 }
 
 fn strong_fileset_grep_without_matches(
@@ -282,8 +427,8 @@ fn strong_fileset_grep_without_matches(
     root_is_fileset: bool,
 ) -> bool {
     !grep.weak
-        && matches!(grep.show, GrepShow::Matching)
-        && grep.regex.is_some()
+        && grep.show.drops_unmatched_files()
+        && grep.is_active()
         && state.is_none()
         && root_is_fileset
 }
@@ -415,7 +560,7 @@ fn filter_fileset_without_matches(
     if !s.is_enabled() {
         return;
     }
-    if matches!(grep.show, crate::grep::GrepShow::All) {
+    if !grep.show.drops_unmatched_files() {
         return;
     }
     if order_build
@@ -564,7 +709,7 @@ fn header_budgeting_policy(
     if !is_fileset_root(order_build) || !config.show_fileset_headers {
         return HeadersBudgeting::Free;
     }
-    if config.count_fileset_headers_in_budgets {
+    if config.charges_fileset_headers() {
         HeadersBudgeting::Charged
     } else {
         HeadersBudgeting::Free
@@ -611,7 +756,12 @@ fn round_robin_slot_priority(
     }
     let (mut buckets, unslotted) =
         bucket_nodes_by_slot(order_build, &slots.map, slot_count);
-    let mut out = drain_round_robin(&mut buckets);
+    let mut out = match order_build.fileset_weights.as_deref() {
+        Some(weights) => {
+            crate::order::allocation::drain_weighted(&mut buckets, weights)
+        }
+        None => drain_round_robin(&mut buckets),
+    };
     out.extend(unslotted);
     Some(out)
 }
@@ -782,6 +932,11 @@ fn measure_config(
         .is_some_and(|t| *t == crate::order::ObjectType::Fileset);
     let mut measure_cfg = config.clone();
     measure_cfg.color_enabled = false;
+    if config.template == crate::OutputTemplate::Html {
+        // Budgets cover the text an HTML page shows, not its markup.
+        measure_cfg.template =
+            crate::serialization::html_measure_template(order_build, config);
+    }
     measure_cfg.count_fileset_headers_in_budgets =
         header_budgeting.is_charged();
     if config.fileset_tree {
@@ -810,7 +965,9 @@ fn measure_must_keep_with_slots(
     let mut measure_cfg = measure_cfg.clone();
     if matches!(
         measure_cfg.template,
-        crate::OutputTemplate::Text | crate::OutputTemplate::Auto
+        crate::OutputTemplate::Text
+            | crate::OutputTemplate::Auto
+            | crate::OutputTemplate::Markdown
     ) {
         // Strip omission markers when measuring must-keep slices so free matches
         // don’t undercount non-matching context.
@@ -36,6 +36,9 @@ struct SearchState {
     inclusion_flags: Vec<u32>,
     render_set_id: u32,
     best_k: Option<usize>,
+    // Budgets exceeded by the smallest k that did not fit.
+    binding: Vec<&'static str>,
+    binding_k: usize,
 }
 
 #[allow(
@@ -217,10 +220,27 @@ fn evaluate_mid(
     state.render_set_id = state.render_set_id.wrapping_add(1).max(1);
     if fits_global && fits_per_slot {
         state.best_k = Some(mid);
-        true
-    } else {
-        false
+        return true;
+    }
+    if mid < state.binding_k {
+        state.binding_k = mid;
+        state.binding =
+            exceeded_budgets(ctx.budgets, (!fits_global, !fits_per_slot));
     }
+    false
+}
+
+fn exceeded_budgets(
+    budgets: Budgets,
+    (global, per_slot): (bool, bool),
+) -> Vec<&'static str> {
+    let global = budgets.global.filter(|_| global);
+    let per_slot = budgets.per_slot.filter(|_| per_slot);
+    global
+        .map(|b| super::kind_str(b.kind, false))
+        .into_iter()
+        .chain(per_slot.map(|b| super::kind_str(b.kind, true)))
+        .collect()
 }
 
 #[allow(
@@ -236,6 +256,8 @@ pub(crate) fn select_best_k(
         inclusion_flags: vec![0; ctx.order_build.total_nodes],
         render_set_id: 1,
         best_k: None,
+        binding: Vec::new(),
+        binding_k: usize::MAX,
     };
 
     if mk_info.apply {
@@ -246,6 +268,7 @@ pub(crate) fn select_best_k(
                     inclusion_flags: search_state.inclusion_flags,
                     render_set_id: search_state.render_set_id,
                     selection_order: prep.selection_order,
+                    binding: exceeded_budgets(ctx.budgets, (true, false)),
                 };
             }
         }
@@ -270,10 +293,19 @@ pub(crate) fn select_best_k(
             )
         },
     );
+    // The search never tries more nodes than a byte cap allows, so stopping
+    // at that bound means the byte cap was binding.
+    let stopped_early = search_state
+        .best_k
+        .is_some_and(|k| k < selection_order_ref.len());
+    if stopped_early && search_state.binding.is_empty() {
+        search_state.binding = exceeded_budgets(ctx.budgets, (true, false));
+    }
     super::SelectionOutcome {
         k: search_state.best_k,
         inclusion_flags: search_state.inclusion_flags,
         render_set_id: search_state.render_set_id,
         selection_order: prep.selection_order,
+        binding: search_state.binding,
     }
 }
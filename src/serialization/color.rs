@@ -42,6 +42,21 @@ pub fn omission_marker(enabled: bool) -> &'static str {
     }
 }
 
+// Diff gutter: the marker column plus one separating space.
+pub fn diff_gutter(
+    mark: Option<crate::diff::DiffMark>,
+    enabled: bool,
+) -> &'static str {
+    use crate::diff::DiffMark;
+    match (mark, enabled) {
+        (None, _) => "  ",
+        (Some(DiffMark::Added), false) => "+ ",
+        (Some(DiffMark::Removed), false) => "- ",
+        (Some(DiffMark::Added), true) => "\u{001b}[32m+\u{001b}[0m ",
+        (Some(DiffMark::Removed), true) => "\u{001b}[31m-\u{001b}[0m ",
+    }
+}
+
 pub fn color_comment<S: Into<String>>(body: S, enabled: bool) -> String {
     if !enabled {
         return body.into();
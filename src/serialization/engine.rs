@@ -1,8 +1,11 @@
 use crate::order::ObjectType;
 use crate::order::{NodeId, NodeKind, PriorityOrder, ROOT_PQ_ID, RankedNode};
 
+use std::rc::Rc;
+
+use super::key_path;
 use super::leaf::LeafRenderer;
-use super::output::Out;
+use super::output::{LineMark, Out};
 use super::templates::{ArrayCtx, ObjectCtx, render_array, render_object};
 
 type ArrayChildPair = (usize, (NodeKind, String));
@@ -16,6 +19,10 @@ pub(crate) struct RenderEngine<'a> {
     pub(crate) line_number_width: Option<usize>,
     pub(crate) slot_map: Option<&'a [Option<usize>]>,
     pub(crate) leaf: LeafRenderer<'a>,
+    // Structural diff: prefix lines with a `+`/`-` gutter.
+    pub(crate) diff_gutter: bool,
+    // Source line gutter width, when ingest recorded source lines.
+    pub(crate) source_gutter: Option<usize>,
 }
 
 impl<'a> RenderEngine<'a> {
@@ -55,6 +62,50 @@ impl<'a> RenderEngine<'a> {
             line_number_width,
             slot_map,
             leaf,
+            diff_gutter: order.has_diff_marks(),
+            source_gutter: order.max_source_line().map(super::util::digits),
+        }
+    }
+
+    // Turn on the gutters and path comments for an `Out` that renders node
+    // `id` with `template`; an inline node's opening line belongs to its
+    // parent, which marks it.
+    pub(crate) fn set_gutters(
+        &self,
+        out: &mut Out<'_>,
+        id: usize,
+        inline: bool,
+        template: crate::OutputTemplate,
+    ) {
+        let metrics = &self.order.metrics[id];
+        out.set_diff_gutter(self.diff_gutter, metrics.diff);
+        let opening_line = if inline { None } else { metrics.source_line };
+        out.set_source_gutter(self.source_gutter, opening_line);
+        let comment = self.key_path_comment(template);
+        let opening_path = comment
+            .filter(|_| !inline)
+            .map(|_| Rc::from(key_path::path_of(self.order, id)));
+        out.set_key_paths(comment, opening_path);
+    }
+
+    // Comment opener for key paths in `template`, when they are on. Strict
+    // and annotated JSON have no comment syntax, so they get none.
+    fn key_path_comment(
+        &self,
+        template: crate::OutputTemplate,
+    ) -> Option<&'static str> {
+        use crate::OutputTemplate as T;
+        if !self.config.key_paths {
+            return None;
+        }
+        let json = crate::utils::templates::map_json_template_for_style(
+            self.config.style,
+        );
+        match template {
+            T::Yaml => Some("#"),
+            T::Auto if json == T::Json => None,
+            T::Auto | T::Pseudo | T::Js | T::Markdown => Some("//"),
+            T::Json | T::Text | T::Code | T::Html | T::Gron => None,
         }
     }
 
@@ -63,6 +114,36 @@ impl<'a> RenderEngine<'a> {
             .and_then(|slots| slots.get(node_id).copied().flatten())
     }
 
+    // Gutter marks and key paths of the kept children of `id`, in render
+    // order.
+    fn kept_child_marks(&self, id: usize) -> Vec<LineMark> {
+        if !self.diff_gutter
+            && self.source_gutter.is_none()
+            && !self.config.key_paths
+        {
+            return Vec::new();
+        }
+        let parent_path = self
+            .config
+            .key_paths
+            .then(|| key_path::path_of(self.order, id));
+        self.order.children.get(id).map_or_else(Vec::new, |kids| {
+            kids.iter()
+                .filter(|cid| {
+                    self.inclusion_flags[cid.0] == self.render_set_id
+                })
+                .map(|cid| LineMark {
+                    diff: self.order.metrics[cid.0].diff,
+                    source_line: self.order.metrics[cid.0].source_line,
+                    path: parent_path.as_ref().map(|parent| {
+                        let segment = key_path::segment(self.order, cid.0);
+                        Rc::from(format!("{parent}{segment}"))
+                    }),
+                })
+                .collect()
+        })
+    }
+
     fn count_kept_children(&self, id: usize) -> usize {
         if let Some(kids) = self.order.children.get(id) {
             let mut kept = 0usize;
@@ -97,9 +178,13 @@ impl<'a> RenderEngine<'a> {
             omitted,
             depth,
             inline_open: inline,
+            space: &config.space,
             omitted_at_start: config.prefer_tail_arrays,
             source_hint: self.leaf.source_hint(id),
             code_highlight: self.leaf.code_highlights_for(id, config.template),
+            embedded: self.order.metrics[id].embedded,
+            truncation: self.order.truncations.get(&id).map(String::as_str),
+            child_marks: self.kept_child_marks(id),
         };
         render_array(config.template, &ctx, out)
     }
@@ -128,24 +213,33 @@ impl<'a> RenderEngine<'a> {
             fileset_root: id == ROOT_PQ_ID
                 && self.order.object_type.get(id)
                     == Some(&ObjectType::Fileset),
+            embedded: self.order.metrics[id].embedded,
+            truncation: self.order.truncations.get(&id).map(String::as_str),
+            child_marks: self.kept_child_marks(id),
         };
         let tmpl = match config.template {
-            crate::OutputTemplate::Auto => match config.style {
-                crate::serialization::types::Style::Strict => {
-                    crate::OutputTemplate::Json
-                }
-                crate::serialization::types::Style::Default => {
-                    crate::OutputTemplate::Pseudo
-                }
-                crate::serialization::types::Style::Detailed => {
-                    crate::OutputTemplate::Js
-                }
-            },
+            crate::OutputTemplate::Auto
+            | crate::OutputTemplate::Markdown
+            | crate::OutputTemplate::Html => {
+                crate::utils::templates::map_json_template_for_style(
+                    config.style,
+                )
+            }
             other => other,
         };
         render_object(tmpl, &ctx, out)
     }
 
+    // Containers print their own `$ = {}` line; a leaf root needs `$ = `.
+    fn push_gron_root_prefix(&self, id: usize, out: &mut Out<'_>) {
+        if !matches!(
+            self.order.nodes[id],
+            RankedNode::Array { .. } | RankedNode::Object { .. }
+        ) {
+            super::templates::gron::push_root_leaf_prefix(out);
+        }
+    }
+
     pub(crate) fn write_node(
         &mut self,
         id: usize,
@@ -154,6 +248,9 @@ impl<'a> RenderEngine<'a> {
         out: &mut Out<'_>,
     ) {
         out.set_current_slot(self.slot_for(id));
+        if depth == 0 && self.config.template == crate::OutputTemplate::Gron {
+            self.push_gron_root_prefix(id, out);
+        }
         match &self.order.nodes[id] {
             RankedNode::Array { .. } => {
                 self.write_array(id, depth, inline, out)
@@ -239,13 +336,7 @@ impl<'a> RenderEngine<'a> {
                 kept += 1;
                 let child = &self.order.nodes[child_id.0];
                 let raw_key = child.key_in_object().unwrap_or("");
-                let key = super::highlight::maybe_highlight_value(
-                    self.config,
-                    Some(raw_key),
-                    crate::utils::json::json_string(raw_key),
-                    super::highlight::HighlightKind::JsonString,
-                    self.leaf.grep_highlight(),
-                );
+                let key = self.object_key(raw_key, template);
                 let val = self.render_node_to_string_with_template(
                     child_id.0,
                     depth + 1,
@@ -258,6 +349,31 @@ impl<'a> RenderEngine<'a> {
         (children_pairs, kept)
     }
 
+    // Key as `template` prints it: a JSON string, or a path segment for gron.
+    fn object_key(
+        &self,
+        raw_key: &str,
+        template: crate::serialization::types::OutputTemplate,
+    ) -> String {
+        let (rendered, kind) = match template {
+            crate::OutputTemplate::Gron => (
+                key_path::key_segment(raw_key),
+                super::highlight::HighlightKind::TextLike,
+            ),
+            _ => (
+                crate::utils::json::json_string(raw_key),
+                super::highlight::HighlightKind::JsonString,
+            ),
+        };
+        super::highlight::maybe_highlight_value(
+            self.config,
+            Some(raw_key),
+            rendered,
+            kind,
+            self.leaf.grep_highlight(),
+        )
+    }
+
     pub(crate) fn write_array_with_template(
         &mut self,
         id: usize,
@@ -276,9 +392,13 @@ impl<'a> RenderEngine<'a> {
             omitted,
             depth,
             inline_open: inline,
+            space: &config.space,
             omitted_at_start: config.prefer_tail_arrays,
             source_hint: self.leaf.source_hint(id),
             code_highlight: self.leaf.code_highlights_for(id, template),
+            embedded: self.order.metrics[id].embedded,
+            truncation: self.order.truncations.get(&id).map(String::as_str),
+            child_marks: self.kept_child_marks(id),
         };
         render_array(template, &ctx, out)
     }
@@ -305,6 +425,9 @@ impl<'a> RenderEngine<'a> {
             fileset_root: id == ROOT_PQ_ID
                 && self.order.object_type.get(id)
                     == Some(&ObjectType::Fileset),
+            embedded: self.order.metrics[id].embedded,
+            truncation: self.order.truncations.get(&id).map(String::as_str),
+            child_marks: self.kept_child_marks(id),
         };
         render_object(template, &ctx, out)
     }
@@ -321,6 +444,7 @@ impl<'a> RenderEngine<'a> {
                 let mut s = String::new();
                 let mut ow =
                     Out::new(&mut s, self.config, self.line_number_width);
+                self.set_gutters(&mut ow, id, inline, template);
                 self.write_array_with_template(
                     id, depth, inline, &mut ow, template,
                 );
@@ -330,6 +454,7 @@ impl<'a> RenderEngine<'a> {
                 let mut s = String::new();
                 let mut ow =
                     Out::new(&mut s, self.config, self.line_number_width);
+                self.set_gutters(&mut ow, id, inline, template);
                 self.write_object_with_template(
                     id, depth, inline, &mut ow, template,
                 );
@@ -1,5 +1,5 @@
 use super::engine::RenderEngine;
-use crate::ingest::format::Format;
+use crate::ingest::fileset::FilesetInputKind;
 use crate::order::{NodeId, ObjectType, ROOT_PQ_ID};
 use crate::serialization::color::{self, ColorRole};
 use crate::serialization::types::OutputTemplate;
@@ -115,8 +115,8 @@ impl<'a> RenderEngine<'a> {
                 out.push_str(&self.fileset_header_line(depth, raw_key));
             }
             out.set_current_slot(Some(slot_idx));
-            let rendered =
-                self.fileset_render_child(child_id.0, depth, raw_key);
+            let rendered = self
+                .fileset_render_child(child_id.0, slot_idx, depth, raw_key);
             out.push_str(&rendered);
         }
         kept
@@ -139,17 +139,56 @@ impl<'a> RenderEngine<'a> {
             let raw_key =
                 self.order.nodes[child_id.0].key_in_object().unwrap_or("");
             let segments = Self::split_path_segments(raw_key);
+            if self.config.fileset_tree_stats {
+                let stats =
+                    self.tree_file_stats(child_id.0, slot_idx, raw_key);
+                inputs.file_stats.push((segments.clone(), stats));
+            }
             if self.inclusion_flags[child_id.0] != self.render_set_id {
                 inputs.track_omission_for_path(&segments);
                 continue;
             }
-            let rendered =
-                self.fileset_render_child(child_id.0, depth, raw_key);
+            let rendered = self
+                .fileset_render_child(child_id.0, slot_idx, depth, raw_key);
             inputs.entries.push((segments, rendered, slot_idx));
         }
         inputs
     }
 
+    fn tree_file_stats(
+        &self,
+        child_id: usize,
+        slot_idx: usize,
+        raw_key: &str,
+    ) -> FileStats {
+        let bytes = self
+            .order
+            .fileset_sizes
+            .as_ref()
+            .and_then(|sizes| sizes.get(slot_idx).copied());
+        let template = match self.config.template {
+            OutputTemplate::Auto
+            | OutputTemplate::Markdown
+            | OutputTemplate::Html => {
+                self.fileset_template_for(slot_idx, raw_key)
+            }
+            other => other,
+        };
+        let line_based =
+            matches!(template, OutputTemplate::Text | OutputTemplate::Code);
+        let metrics = self.order.metrics.get(child_id);
+        let count = match (
+            metrics.and_then(|m| m.array_len),
+            metrics.and_then(|m| m.object_len),
+        ) {
+            (Some(n), _) if line_based => Some((n, "line")),
+            (Some(n), _) => Some((n, "item")),
+            (None, Some(n)) => Some((n, "key")),
+            (None, None) => None,
+        };
+        FileStats { bytes, count }
+    }
+
     fn build_tree(&self, root: &mut TreeNode, inputs: &TreeInputs) {
         for (segments, rendered, slot) in &inputs.entries {
             root.insert(*slot, segments, rendered.clone(), self.config);
@@ -164,6 +203,12 @@ impl<'a> RenderEngine<'a> {
             root.ensure_path(path);
         }
         root.apply_omitted_counts(&omitted, &mut Vec::new());
+        for (segments, stats) in &inputs.file_stats {
+            root.add_file_stats(segments, *stats);
+        }
+        for segments in &inputs.omitted_files {
+            root.add_hidden_file(segments);
+        }
     }
 
     fn render_tree_output(
@@ -174,13 +219,22 @@ impl<'a> RenderEngine<'a> {
         out: &mut crate::serialization::output::Out<'_>,
     ) {
         let indent = self.config.indent_unit.repeat(depth);
-        if render_scaffold_lines {
+        // A Markdown list needs no root entry.
+        if render_scaffold_lines && !self.is_markdown() {
             out.set_current_slot(None);
             out.push_str(&indent);
             out.push_char('.');
+            if self.config.fileset_tree_stats {
+                let totals = root.totals.annotation();
+                out.push_str(&colorize_pipe(
+                    &totals,
+                    self.config.color_enabled,
+                ));
+            }
             out.push_str(&self.config.newline);
         }
         let mut root_children = root.children;
+        root_children.iter_mut().for_each(TreeNode::mark_outline);
         if root.omitted > 0 {
             root_children.push(TreeNode::omission(root.omitted));
         }
@@ -222,25 +276,38 @@ impl<'a> RenderEngine<'a> {
         let indent = self.config.indent_unit.repeat(depth);
         let mut s = String::with_capacity(indent.len() + key.len() + 8);
         s.push_str(&indent);
-        s.push_str("==> ");
-        s.push_str(key);
-        s.push_str(" <==");
+        if self.is_markdown() {
+            s.push_str("## ");
+            s.push_str(key);
+        } else {
+            s.push_str("==> ");
+            s.push_str(key);
+            s.push_str(" <==");
+        }
         s.push_str(nl);
         s
     }
 
     fn fileset_summary_line(&self, depth: usize, omitted: usize) -> String {
         let indent = self.config.indent_unit.repeat(depth);
+        if self.is_markdown() {
+            return format!("{indent}_{omitted} more files_");
+        }
         format!("{indent}==> {omitted} more files <==")
     }
 
+    fn is_markdown(&self) -> bool {
+        self.config.template == OutputTemplate::Markdown
+    }
+
     fn fileset_render_child(
         &mut self,
         child_id: usize,
+        slot_idx: usize,
         depth: usize,
         raw_key: &str,
     ) -> String {
-        if self.config.count_fileset_headers_in_budgets
+        if self.config.charges_fileset_headers()
             && !self.node_has_included_descendants(child_id)
             && !self.node_is_included_leaf(child_id)
             && self.node_has_children(child_id)
@@ -249,42 +316,60 @@ impl<'a> RenderEngine<'a> {
             // a body/omission marker so we don't exceed the caller’s cap.
             return String::new();
         }
-        if matches!(self.config.template, OutputTemplate::Auto) {
-            let template = self.fileset_template_for(raw_key);
-            return self.render_node_to_string_with_template(
-                child_id, depth, false, template,
-            );
+        match self.config.template {
+            OutputTemplate::Auto | OutputTemplate::Html => {
+                let template = self.fileset_template_for(slot_idx, raw_key);
+                self.render_node_to_string_with_template(
+                    child_id, depth, false, template,
+                )
+            }
+            OutputTemplate::Markdown => {
+                let kind = self.fileset_kind_for(slot_idx, raw_key);
+                let body = self.render_node_to_string_with_template(
+                    child_id,
+                    depth,
+                    false,
+                    crate::utils::templates::template_for_input_kind(
+                        kind,
+                        self.config.style,
+                    ),
+                );
+                super::markdown::fence(
+                    &body,
+                    &super::markdown::fence_language(kind, Some(raw_key)),
+                    &self.config.newline,
+                )
+            }
+            other => self.render_node_to_string_with_template(
+                child_id, depth, false, other,
+            ),
         }
-        self.render_node_to_string_with_template(
-            child_id,
-            depth,
-            false,
-            self.config.template,
+    }
+
+    // Template matching how the file was ingested.
+    fn fileset_template_for(
+        &self,
+        slot_idx: usize,
+        raw_key: &str,
+    ) -> OutputTemplate {
+        crate::utils::templates::template_for_input_kind(
+            self.fileset_kind_for(slot_idx, raw_key),
+            self.config.style,
         )
     }
 
-    fn fileset_template_for(&self, raw_key: &str) -> OutputTemplate {
-        match Format::from_filename(raw_key) {
-            Format::Yaml => OutputTemplate::Yaml,
-            Format::Json => match self.config.style {
-                crate::serialization::types::Style::Strict => {
-                    OutputTemplate::Json
-                }
-                crate::serialization::types::Style::Default => {
-                    OutputTemplate::Pseudo
-                }
-                crate::serialization::types::Style::Detailed => {
-                    OutputTemplate::Js
-                }
-            },
-            Format::Unknown => {
-                if crate::utils::extensions::is_code_like_name(raw_key) {
-                    OutputTemplate::Code
-                } else {
-                    OutputTemplate::Text
-                }
-            }
-        }
+    // How the file was ingested; falls back to its name for orders built
+    // without per-file kinds.
+    fn fileset_kind_for(
+        &self,
+        slot_idx: usize,
+        raw_key: &str,
+    ) -> FilesetInputKind {
+        self.order
+            .fileset_kinds
+            .as_ref()
+            .and_then(|kinds| kinds.get(slot_idx).copied())
+            .unwrap_or_else(|| FilesetInputKind::from_filename(raw_key))
     }
 
     fn node_has_included_descendants(&self, node_idx: usize) -> bool {
@@ -347,6 +432,9 @@ impl<'a> RenderEngine<'a> {
 #[derive(Default)]
 struct TreeInputs {
     entries: Vec<(Vec<String>, String, usize)>,
+    // Every file, kept or omitted, so directory totals cover the whole tree.
+    file_stats: Vec<(Vec<String>, FileStats)>,
+    omitted_files: Vec<Vec<String>>,
     omitted_map: std::collections::HashMap<Vec<String>, usize>,
     omitted_paths_in_order: Vec<Vec<String>>,
     root_direct_omitted: usize,
@@ -355,20 +443,19 @@ struct TreeInputs {
 impl TreeInputs {
     fn track_omission_for_path(&mut self, segments: &[String]) {
         if segments.len() > 1 {
-            let mut prefix: Vec<String> = Vec::new();
-            for seg in &segments[..segments.len() - 1] {
-                prefix.push(seg.clone());
-                let entry =
-                    self.omitted_map.entry(prefix.clone()).or_insert(0);
-                if *entry == 0 {
-                    self.omitted_paths_in_order.push(prefix.clone());
-                }
-                *entry += 1;
+            // Count the file on its own folder only; ancestors report it
+            // through that folder (or its summary line).
+            let folder = segments[..segments.len() - 1].to_vec();
+            let entry = self.omitted_map.entry(folder.clone()).or_insert(0);
+            if *entry == 0 {
+                self.omitted_paths_in_order.push(folder);
             }
+            *entry += 1;
         } else {
             // Root-level omission (no folder to pin it to).
             self.root_direct_omitted += 1;
         }
+        self.omitted_files.push(segments.to_vec());
     }
 
     fn is_empty(&self) -> bool {
@@ -378,6 +465,115 @@ impl TreeInputs {
     }
 }
 
+/// Size and top-level line/element count of one file, shown by
+/// `--tree-stats`.
+#[derive(Clone, Copy, Default)]
+struct FileStats {
+    bytes: Option<usize>,
+    count: Option<(usize, &'static str)>,
+}
+
+impl FileStats {
+    fn annotation(self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(bytes) = self.bytes {
+            parts.push(human_size(bytes));
+        }
+        if let Some((n, unit)) = self.count {
+            parts.push(count_label(n, unit));
+        }
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", parts.join(", "))
+        }
+    }
+}
+
+/// Aggregated file count and size below a directory.
+#[derive(Clone, Copy, Default)]
+struct TreeTotals {
+    files: usize,
+    bytes: Option<usize>,
+}
+
+impl TreeTotals {
+    fn add(&mut self, stats: FileStats) {
+        self.files += 1;
+        if let Some(bytes) = stats.bytes {
+            self.bytes = Some(self.bytes.unwrap_or(0).saturating_add(bytes));
+        }
+    }
+
+    fn annotation(self) -> String {
+        if self.files == 0 {
+            return String::new();
+        }
+        let files = count_label(self.files, "file");
+        match self.bytes {
+            Some(bytes) => format!(" ({files}, {})", human_size(bytes)),
+            None => format!(" ({files})"),
+        }
+    }
+}
+
+// Extensions listed individually on a folder summary line; the rest are
+// grouped as "other".
+const SUMMARY_KINDS: usize = 3;
+
+/// Omitted files below a folder, tallied by extension, for the one-line
+/// summary of folders where no file fits.
+#[derive(Clone, Default)]
+struct HiddenFiles {
+    kinds: Vec<(String, usize)>,
+    other: usize,
+}
+
+impl HiddenFiles {
+    fn add(&mut self, name: &str) {
+        let Some(ext) = std::path::Path::new(name).extension() else {
+            self.other += 1;
+            return;
+        };
+        let kind = format!(".{}", ext.to_string_lossy());
+        match self.kinds.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, n)) => *n += 1,
+            None => self.kinds.push((kind, 1)),
+        }
+    }
+
+    fn summary(&self, bytes: Option<usize>) -> Option<String> {
+        let total =
+            self.other + self.kinds.iter().map(|(_, n)| n).sum::<usize>();
+        if total == 0 {
+            return None;
+        }
+        let mut kinds = self.kinds.clone();
+        kinds.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+        let mut parts: Vec<String> = kinds
+            .iter()
+            .take(SUMMARY_KINDS)
+            .map(|(kind, n)| format!("{} {kind}", group_thousands(*n)))
+            .collect();
+        let rest = self.other
+            + kinds
+                .iter()
+                .skip(SUMMARY_KINDS)
+                .map(|(_, n)| n)
+                .sum::<usize>();
+        if rest > 0 {
+            parts.push(format!("{} other", group_thousands(rest)));
+        }
+        let size = bytes.map(|b| format!(", {}", human_size(b)));
+        Some(format!(
+            " ({}{}: {})",
+            count_label(total, "file"),
+            size.unwrap_or_default(),
+            parts.join(", ")
+        ))
+    }
+}
+
 struct TreeNode {
     name: String,
     slot: Option<usize>,
@@ -385,6 +581,11 @@ struct TreeNode {
     content: Option<Vec<String>>,
     omitted: usize,
     is_omission: bool,
+    stats: Option<FileStats>,
+    totals: TreeTotals,
+    hidden: HiddenFiles,
+    // On the top-level outline, which is kept even when nothing fits.
+    outline: bool,
 }
 
 struct CollapsedNode {
@@ -394,6 +595,34 @@ struct CollapsedNode {
     content: Option<Vec<String>>,
     omitted: usize,
     is_omission: bool,
+    stats: Option<FileStats>,
+    totals: TreeTotals,
+    hidden: HiddenFiles,
+    has_kept_files: bool,
+    outline: bool,
+}
+
+impl CollapsedNode {
+    /// One-line summary for a folder where none of the files fit, e.g.
+    /// ` (1,204 files: 900 .json, 300 .yaml)`.
+    fn summary(&self, config: &crate::RenderConfig) -> Option<String> {
+        if self.content.is_some() || self.is_omission || self.has_kept_files {
+            return None;
+        }
+        let bytes = if config.fileset_tree_stats {
+            self.totals.bytes
+        } else {
+            None
+        };
+        self.hidden.summary(bytes)
+    }
+
+    /// Whether the folder renders as its summary line alone. Folders on the
+    /// top-level outline still list their subfolders, summarized in turn.
+    fn folds(&self, summary: Option<&String>) -> bool {
+        summary.is_some()
+            && !(self.outline && self.children.iter().any(TreeNode::is_dir))
+    }
 }
 
 impl TreeNode {
@@ -405,6 +634,10 @@ impl TreeNode {
             content: None,
             omitted: 0,
             is_omission: false,
+            stats: None,
+            totals: TreeTotals::default(),
+            hidden: HiddenFiles::default(),
+            outline: false,
         }
     }
 
@@ -416,6 +649,10 @@ impl TreeNode {
             content: None,
             omitted: 0,
             is_omission: false,
+            stats: None,
+            totals: TreeTotals::default(),
+            hidden: HiddenFiles::default(),
+            outline: false,
         }
     }
 
@@ -487,6 +724,77 @@ impl TreeNode {
         }
     }
 
+    fn add_file_stats(&mut self, segments: &[String], stats: FileStats) {
+        self.totals.add(stats);
+        let Some((head, rest)) = segments.split_first() else {
+            return;
+        };
+        let Some(child) = self.children.iter_mut().find(|c| c.name == *head)
+        else {
+            return;
+        };
+        if rest.is_empty() {
+            child.stats = Some(stats);
+        } else {
+            child.add_file_stats(rest, stats);
+        }
+    }
+
+    fn add_hidden_file(&mut self, segments: &[String]) {
+        let Some((head, rest)) = segments.split_first() else {
+            return;
+        };
+        if rest.is_empty() {
+            return;
+        }
+        let Some(child) = self.children.iter_mut().find(|c| c.name == *head)
+        else {
+            return;
+        };
+        child.hidden.add(&segments[segments.len() - 1]);
+        child.add_hidden_file(rest);
+    }
+
+    /// Put this top-level entry on the outline, continuing through lone
+    /// subfolders.
+    fn mark_outline(&mut self) {
+        self.outline = true;
+        if let [child] = self.children.as_mut_slice()
+            && self.omitted == 0
+        {
+            child.mark_outline();
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.content.is_none() && !self.is_omission
+    }
+
+    fn has_kept_files(&self) -> bool {
+        self.content.is_some()
+            || self.children.iter().any(TreeNode::has_kept_files)
+    }
+
+    fn annotation(
+        summary: Option<String>,
+        stats: Option<FileStats>,
+        totals: TreeTotals,
+        is_leaf: bool,
+        config: &crate::RenderConfig,
+    ) -> String {
+        if let Some(summary) = summary {
+            return summary;
+        }
+        if !config.fileset_tree_stats {
+            return String::new();
+        }
+        if is_leaf {
+            stats.map(FileStats::annotation).unwrap_or_default()
+        } else {
+            totals.annotation()
+        }
+    }
+
     #[allow(
         clippy::cognitive_complexity,
         reason = "Tree render branches are simple; splitting further would hurt clarity"
@@ -500,18 +808,22 @@ impl TreeNode {
         render_scaffold_lines: bool,
     ) {
         let collapsed = self.collapse();
-        let mut children = collapsed.children;
+        // Folders where no file fits render as a single summary line.
+        let summary = collapsed.summary(config);
+        let (mut children, omitted) = if collapsed.folds(summary.as_ref()) {
+            (Vec::new(), 0)
+        } else {
+            (collapsed.children, collapsed.omitted)
+        };
         let is_leaf = collapsed.content.is_some();
         let content = collapsed.content;
-        let omitted = collapsed.omitted;
         let slot = collapsed.slot;
-        let slot_for_scaffold = if render_scaffold_lines
-            && !config.count_fileset_headers_in_budgets
-        {
-            None
-        } else {
-            slot
-        };
+        let slot_for_scaffold =
+            if render_scaffold_lines && !config.charges_fileset_headers() {
+                None
+            } else {
+                slot
+            };
         let nl = &config.newline;
         // Tree scaffolding (pipes/names) keeps syntax coloring even in
         // highlight-only grep mode. Those glyphs never receive grep highlights,
@@ -529,50 +841,63 @@ impl TreeNode {
             down: connects_down || !is_last,
             right: true,
         };
+        let markdown = config.template == OutputTemplate::Markdown;
         if render_scaffold_lines {
-            let branch = scaffold_segment(prefix, branch_edges, color_on);
-            out.set_current_slot(slot_for_scaffold);
-            out.push_str(&branch);
-            let display_name = if is_leaf {
-                collapsed.name
+            let branch = if markdown {
+                format!("{prefix}- ")
             } else {
-                format!("{}/", collapsed.name)
+                scaffold_segment(prefix, branch_edges, color_on)
             };
+            out.set_current_slot(slot_for_scaffold);
+            out.push_str(&branch);
+            let mut display_name = collapsed.name;
+            if !is_leaf {
+                display_name.push('/');
+            }
+            if markdown && !collapsed.is_omission {
+                display_name = format!("`{display_name}`");
+            }
             if collapsed.is_omission {
                 out.push_str(&colorize_pipe(&display_name, color_on));
             } else {
                 out.push_str(&colorize_name(&display_name, color_on));
+                let annotation = Self::annotation(
+                    summary,
+                    collapsed.stats,
+                    collapsed.totals,
+                    is_leaf,
+                    config,
+                );
+                if !annotation.is_empty() {
+                    out.push_str(&colorize_pipe(&annotation, color_on));
+                }
             }
             out.push_str(nl);
-        } else if collapsed.is_omission {
+        } else if collapsed.is_omission || summary.is_some() {
             out.set_current_slot(slot);
             out.push_str(&collapsed.name);
+            if let Some(summary) = summary {
+                out.push_char('/');
+                out.push_str(&summary);
+            }
             out.push_str(nl);
         }
 
         let gutter_edges = Edges::with_up_down(has_parent, true);
-        let content_prefix = if render_scaffold_lines {
-            // Keep the gutter visible even for last children so lines stay aligned.
-            scaffold_segment(prefix, gutter_edges, color_on)
-        } else {
+        let content_prefix = if !render_scaffold_lines {
             String::new()
-        };
-        let child_prefix = if render_scaffold_lines {
-            // Keep gutters visible for nested nodes even when this entry is last.
-            scaffold_segment(prefix, gutter_edges, color_on)
+        } else if markdown {
+            // Continuation lines of a list item line up with its text.
+            format!("{prefix}  ")
         } else {
-            String::new()
+            // Keep the gutter visible even for last children so lines stay aligned.
+            scaffold_segment(prefix, gutter_edges, color_on)
         };
+        // Keep gutters visible for nested nodes even when this entry is last.
+        let child_prefix = content_prefix.clone();
         if let Some(lines) = content {
             for line in lines {
-                let prefix_slot = if render_scaffold_lines
-                    && !config.count_fileset_headers_in_budgets
-                {
-                    slot_for_scaffold
-                } else {
-                    slot
-                };
-                out.set_current_slot(prefix_slot);
+                out.set_current_slot(slot_for_scaffold);
                 out.push_str(&content_prefix);
                 out.set_current_slot(slot);
                 out.push_str(&line);
@@ -612,12 +937,17 @@ impl TreeNode {
     }
 
     fn collapse(self) -> CollapsedNode {
+        let has_kept_files = self.has_kept_files();
+        let outline = self.outline;
         let mut name = self.name;
         let mut slot = self.slot;
         let mut content = self.content;
         let mut children = self.children;
         let mut omitted = self.omitted;
         let mut is_omission = self.is_omission;
+        let mut stats = self.stats;
+        let mut totals = self.totals;
+        let mut hidden = self.hidden;
         while content.is_none()
             && omitted == 0
             && children.len() == 1
@@ -630,6 +960,9 @@ impl TreeNode {
                 omitted = omitted.saturating_add(child.omitted);
                 children = child.children;
                 is_omission = child.is_omission;
+                stats = child.stats;
+                totals = child.totals;
+                hidden = child.hidden;
             } else {
                 break;
             }
@@ -641,6 +974,11 @@ impl TreeNode {
             content,
             omitted,
             is_omission,
+            stats,
+            totals,
+            hidden,
+            has_kept_files,
+            outline,
         }
     }
 
@@ -652,8 +990,44 @@ impl TreeNode {
             content: Some(Vec::new()),
             omitted: 0,
             is_omission: true,
+            stats: None,
+            totals: TreeTotals::default(),
+            hidden: HiddenFiles::default(),
+            outline: false,
+        }
+    }
+}
+
+fn human_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    // Work in tenths of a unit to keep one decimal without float casts.
+    let mut tenths = (bytes as u128) * 10 / 1024;
+    let mut unit = 0;
+    while tenths >= 10 * 1024 && unit + 1 < UNITS.len() {
+        tenths /= 1024;
+        unit += 1;
+    }
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+fn count_label(n: usize, unit: &str) -> String {
+    let plural = if n == 1 { "" } else { "s" };
+    format!("{} {unit}{plural}", group_thousands(n))
+}
+
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            grouped.push(',');
         }
+        grouped.push(ch);
     }
+    grouped
 }
 
 fn colorize_pipe(s: &str, enabled: bool) -> String {
@@ -724,6 +1098,7 @@ mod tests {
             out.push_str(&config.newline);
         }
         let mut root_children = root.children;
+        root_children.iter_mut().for_each(TreeNode::mark_outline);
         if root.omitted > 0 {
             root_children.push(TreeNode::omission(root.omitted));
         }
@@ -759,6 +1134,7 @@ mod tests {
             fileset_tree: true,
             count_fileset_headers_in_budgets: false,
             grep_highlight: None,
+            ..crate::RenderConfig::default()
         };
 
         let mut root = TreeNode::root();
@@ -817,6 +1193,7 @@ mod tests {
             fileset_tree: true,
             count_fileset_headers_in_budgets: false,
             grep_highlight: None,
+            ..crate::RenderConfig::default()
         };
 
         let mut root = TreeNode::root();
@@ -867,6 +1244,7 @@ mod tests {
             fileset_tree: true,
             count_fileset_headers_in_budgets: false,
             grep_highlight: None,
+            ..crate::RenderConfig::default()
         };
 
         let mut root = TreeNode::root();
@@ -920,6 +1298,7 @@ mod tests {
             fileset_tree: true,
             count_fileset_headers_in_budgets: false,
             grep_highlight: None,
+            ..crate::RenderConfig::default()
         };
 
         let mut root = TreeNode::root();
@@ -934,4 +1313,24 @@ mod tests {
             "when no files are kept, omissions should only appear once at the root"
         );
     }
+
+    #[test]
+    fn tree_stats_format_human_sizes() {
+        let cases = [
+            (0, "0 B"),
+            (1023, "1023 B"),
+            (1536, "1.5 KB"),
+            (5 * 1024 * 1024, "5.0 MB"),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(super::human_size(bytes), expected);
+        }
+    }
+
+    #[test]
+    fn tree_stats_format_grouped_counts() {
+        assert_eq!(super::count_label(1, "line"), "1 line");
+        assert_eq!(super::count_label(1204, "file"), "1,204 files");
+        assert_eq!(super::group_thousands(1_234_567), "1,234,567");
+    }
 }
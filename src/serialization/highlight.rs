@@ -3,8 +3,9 @@ use std::path::Path;
 use once_cell::sync::Lazy;
 use syntect::{
     easy::HighlightLines,
-    highlighting::{Style, Theme, ThemeSet},
-    parsing::{SyntaxReference, SyntaxSet},
+    highlighting::{Color, Highlighter, Style, Theme, ThemeSet},
+    html::{IncludeBackground, styled_line_to_highlighted_html},
+    parsing::{Scope, SyntaxReference, SyntaxSet},
     util::as_24_bit_terminal_escaped,
 };
 
@@ -20,6 +21,9 @@ static THEME: Lazy<Theme> = Lazy::new(|| {
         .or_else(|| themes.themes.values().next().cloned())
         .unwrap_or_else(Theme::default)
 });
+// Highlights an entire value; used for leaves matched by a predicate.
+pub(crate) static WHOLE_VALUE: Lazy<Option<regex::Regex>> =
+    Lazy::new(|| regex::Regex::new("(?s).+").ok());
 
 pub struct CodeHighlighter<'a> {
     inner: HighlightLines<'a>,
@@ -33,6 +37,22 @@ impl CodeHighlighter<'static> {
         }
     }
 
+    /// Like [`Self::highlight_line`], as HTML spans with inline colors.
+    pub fn highlight_line_html(&mut self, line: &str) -> String {
+        let text = format!("{line}\n");
+        let ranges = self
+            .inner
+            .highlight_line(&text, &SYNTAXES)
+            .unwrap_or_else(|_| vec![(Style::default(), text.as_str())]);
+        let mut html =
+            styled_line_to_highlighted_html(&ranges, IncludeBackground::No)
+                .unwrap_or_default();
+        if let Some(pos) = html.rfind('\n') {
+            html.remove(pos);
+        }
+        html
+    }
+
     pub fn highlight_line(&mut self, line: &str) -> String {
         let mut owned;
         let appended_newline;
@@ -62,10 +82,25 @@ impl CodeHighlighter<'static> {
     }
 }
 
+/// Syntax-highlight the lines of a code array; when `grep` is set, matches
+/// are layered on top (see [`layer_grep_matches`]).
 pub(crate) fn code_highlight_lines(
     order: &crate::PriorityOrder,
     array_id: usize,
     source_hint: Option<&str>,
+    grep: Option<&regex::Regex>,
+) -> Vec<String> {
+    let lines = syntax_highlight_lines(order, array_id, source_hint);
+    match grep {
+        Some(re) => lines.iter().map(|l| layer_grep_matches(re, l)).collect(),
+        None => lines,
+    }
+}
+
+fn syntax_highlight_lines(
+    order: &crate::PriorityOrder,
+    array_id: usize,
+    source_hint: Option<&str>,
 ) -> Vec<String> {
     let root = code_root_array_id(order, array_id);
     if let Some(full) = order.code_lines.get(&root) {
@@ -166,6 +201,53 @@ fn syntax_for_hint(hint: Option<&str>) -> &'static SyntaxReference {
     SYNTAXES.find_syntax_plain_text()
 }
 
+/// CSS custom properties carrying the code theme's colors, so HTML output
+/// matches syntax-highlighted code.
+pub(crate) fn theme_css_vars() -> String {
+    let highlighter = Highlighter::new(&THEME);
+    let fg = THEME.settings.foreground.unwrap_or(Color::WHITE);
+    let bg = THEME.settings.background.unwrap_or(Color::BLACK);
+    let scope_color = |scope: &str| {
+        Scope::new(scope)
+            .map(|s| highlighter.style_for_stack(&[s]).foreground)
+            .unwrap_or(fg)
+    };
+    format!(
+        ":root{{--bg:{};--fg:{};--key:{};--str:{};--num:{};--const:{};\
+         --muted:{}}}",
+        css_hex(bg),
+        css_hex(fg),
+        css_hex(scope_color("entity.name.tag")),
+        css_hex(scope_color("string.quoted.double")),
+        css_hex(scope_color("constant.numeric")),
+        css_hex(scope_color("constant.language")),
+        css_hex(scope_color("comment")),
+    )
+}
+
+fn css_hex(c: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+}
+
+/// Info string for a Markdown code fence around the code in `hint`, e.g.
+/// `rust` or `python`; None when no syntax matches the name.
+pub(crate) fn fence_language_for_hint(hint: Option<&str>) -> Option<String> {
+    let syntax = syntax_for_hint(hint);
+    if syntax.name == SYNTAXES.find_syntax_plain_text().name {
+        return None;
+    }
+    let name = syntax.name.to_ascii_lowercase();
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-'))
+    {
+        Some(name)
+    } else {
+        // Names like "Bourne Again Shell (bash)" make poor info strings.
+        syntax.file_extensions.first().cloned()
+    }
+}
+
 fn syntax_alias_for_extension(ext: &str) -> Option<&'static str> {
     if ext.eq_ignore_ascii_case("ts") || ext.eq_ignore_ascii_case("tsx") {
         return Some("JavaScript");
@@ -179,47 +261,62 @@ pub(crate) enum HighlightKind {
     JsonString,
 }
 
+// Start/end SGR pairs for grep matches: red when syntax colors are
+// suppressed, inverse video when layered over syntax colors so the
+// surrounding foreground color survives.
+type MatchMarks = (&'static str, &'static str);
+const RED_MARKS: MatchMarks = ("\u{001b}[31m", "\u{001b}[39m");
+const INVERSE_MARKS: MatchMarks = ("\u{001b}[7m", "\u{001b}[27m");
+
 pub(crate) fn maybe_highlight_value(
     config: &crate::RenderConfig,
     raw: Option<&str>,
     rendered: String,
     kind: HighlightKind,
-    grep_highlight: &Option<regex::Regex>,
+    grep_highlight: Option<&regex::Regex>,
 ) -> String {
-    match config.color_strategy() {
+    let marks = match config.color_strategy() {
         crate::serialization::types::ColorStrategy::None
-        | crate::serialization::types::ColorStrategy::Syntax => rendered,
-        crate::serialization::types::ColorStrategy::HighlightOnly => {
-            if let Some(re) = grep_highlight {
-                return match kind {
-                    HighlightKind::JsonString => raw
-                        .map(|r| highlight_json_string(re, r))
-                        .unwrap_or(rendered),
-                    HighlightKind::TextLike => {
-                        highlight_matches(re, &rendered)
-                    }
-                };
-            }
-            rendered
+        | crate::serialization::types::ColorStrategy::Syntax => {
+            return rendered;
         }
+        crate::serialization::types::ColorStrategy::HighlightOnly => RED_MARKS,
+        crate::serialization::types::ColorStrategy::Combined => INVERSE_MARKS,
+    };
+    let Some(re) = grep_highlight else {
+        return rendered;
+    };
+    match kind {
+        HighlightKind::JsonString => raw
+            .map(|r| highlight_json_string(re, r, marks))
+            .unwrap_or(rendered),
+        HighlightKind::TextLike => highlight_matches(re, &rendered, marks),
     }
 }
 
-fn highlight_matches(re: &regex::Regex, text: &str) -> String {
+fn highlight_matches(
+    re: &regex::Regex,
+    text: &str,
+    marks: MatchMarks,
+) -> String {
     let mut out = String::with_capacity(text.len());
     let mut last = 0usize;
     for m in re.find_iter(text) {
         out.push_str(&text[last..m.start()]);
-        out.push_str("\u{001b}[31m");
+        out.push_str(marks.0);
         out.push_str(m.as_str());
-        out.push_str("\u{001b}[39m");
+        out.push_str(marks.1);
         last = m.end();
     }
     out.push_str(&text[last..]);
     out
 }
 
-fn highlight_json_string(re: &regex::Regex, raw: &str) -> String {
+fn highlight_json_string(
+    re: &regex::Regex,
+    raw: &str,
+    marks: MatchMarks,
+) -> String {
     // Build a JSON string literal while inserting highlight escapes around
     // matched spans computed on the raw (unescaped) value.
     let mut out = String::with_capacity(raw.len() + 16);
@@ -227,9 +324,9 @@ fn highlight_json_string(re: &regex::Regex, raw: &str) -> String {
     let mut last = 0usize;
     for m in re.find_iter(raw) {
         out.push_str(&escape_json_fragment(&raw[last..m.start()]));
-        out.push_str("\u{001b}[31m");
+        out.push_str(marks.0);
         out.push_str(&escape_json_fragment(m.as_str()));
-        out.push_str("\u{001b}[39m");
+        out.push_str(marks.1);
         last = m.end();
     }
     out.push_str(&escape_json_fragment(&raw[last..]));
@@ -237,6 +334,61 @@ fn highlight_json_string(re: &regex::Regex, raw: &str) -> String {
     out
 }
 
+// Length of the ANSI CSI sequence starting at `i` (ESC '[' ... final byte),
+// or 0 when `i` does not start one.
+fn csi_len(bytes: &[u8], i: usize) -> usize {
+    if bytes.get(i) != Some(&0x1b) || bytes.get(i + 1) != Some(&b'[') {
+        return 0;
+    }
+    let end = bytes[i + 2..]
+        .iter()
+        .position(|b| (0x40..=0x7e).contains(b))
+        .map_or(bytes.len(), |p| i + 2 + p + 1);
+    end - i
+}
+
+// Text with ANSI escapes stripped, plus the byte offset in `s` of every
+// visible byte, so matches on the visible text can be mapped back into the
+// escaped string.
+fn visible_with_offsets(s: &str) -> (String, Vec<usize>) {
+    let bytes = s.as_bytes();
+    let mut visible = String::with_capacity(s.len());
+    let mut offsets = Vec::with_capacity(s.len());
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let skip = csi_len(bytes, i);
+        if skip > 0 {
+            i += skip;
+            continue;
+        }
+        let ch = s[i..].chars().next().unwrap_or_default();
+        offsets.extend(i..i + ch.len_utf8());
+        visible.push(ch);
+        i += ch.len_utf8();
+    }
+    (visible, offsets)
+}
+
+/// Mark grep matches in an already syntax-colored line with inverse video.
+/// Matching runs on the visible text, so escapes never split or shift a
+/// match, and the existing syntax colors are left in place.
+pub(crate) fn layer_grep_matches(re: &regex::Regex, colored: &str) -> String {
+    let (visible, offsets) = visible_with_offsets(colored);
+    let mut out = String::with_capacity(colored.len() + 16);
+    let mut last = 0usize;
+    for m in re.find_iter(&visible).filter(|m| !m.is_empty()) {
+        // End right after the last matched byte, before any trailing escape.
+        let (start, end) = (offsets[m.start()], offsets[m.end() - 1] + 1);
+        out.push_str(&colored[last..start]);
+        out.push_str(INVERSE_MARKS.0);
+        out.push_str(&colored[start..end]);
+        out.push_str(INVERSE_MARKS.1);
+        last = end;
+    }
+    out.push_str(&colored[last..]);
+    out
+}
+
 fn escape_json_fragment(s: &str) -> String {
     let quoted = crate::utils::json::json_string(s);
     // Strip surrounding quotes from a valid JSON string literal.
@@ -247,6 +399,19 @@ fn escape_json_fragment(s: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn layered_matches_follow_visible_text() {
+        let re = regex::Regex::new("needle").unwrap();
+        let colored =
+            "\u{1b}[38;2;1;2;3mlet\u{1b}[0m nee\u{1b}[32mdle\u{1b}[0m";
+        let layered = layer_grep_matches(&re, colored);
+        assert_eq!(
+            layered,
+            "\u{1b}[38;2;1;2;3mlet\u{1b}[0m \u{1b}[7mnee\u{1b}[32mdle\u{1b}[27m\u{1b}[0m"
+        );
+        assert_eq!(visible_with_offsets(&layered).0, "let needle");
+    }
+
     #[test]
     fn detects_typescript_syntax_from_extension() {
         let syntax = syntax_for_hint(Some("example.ts"));
@@ -0,0 +1,466 @@
+use std::collections::HashSet;
+
+use crate::FilesetInputKind;
+use crate::order::{
+    NodeId, ObjectType, PriorityOrder, ROOT_PQ_ID, RankedNode,
+};
+use crate::serialization::types::OutputTemplate;
+
+use super::engine::RenderEngine;
+use super::highlight::{CodeHighlighter, theme_css_vars};
+
+const STYLE: &str = "\
+body{margin:0;padding:1rem 1.5rem;background:var(--bg);color:var(--fg);\
+font:13px/1.5 ui-monospace,SFMono-Regular,Menlo,Consolas,monospace}\
+a{color:inherit}h2{font-size:1em;margin:1.5em 0 .5em}\
+nav ul,ul.v{list-style:none;margin:0;padding-left:1.5em}nav ul{padding:0}\
+summary{cursor:pointer;list-style:none}\
+summary::-webkit-details-marker{display:none}\
+details[open]>summary .cnt,details[open]>summary .x{display:none}\
+pre{margin:0}.k{color:var(--key)}.s{color:var(--str)}.n{color:var(--num)}\
+.c{color:var(--const)}.o,.ln,.cnt{color:var(--muted)}.o{font-style:italic}\
+mark{background:var(--fg);color:var(--bg)}";
+
+/// Render the kept nodes as a standalone HTML page.
+pub(super) fn render_page(
+    order: &PriorityOrder,
+    inclusion_flags: &[u32],
+    render_id: u32,
+    config: &crate::RenderConfig,
+) -> String {
+    // Leaves come from the text renderer; colors are CSS here.
+    let mut text_cfg = config.clone();
+    text_cfg.color_enabled = false;
+    text_cfg.grep_highlight = None;
+    let mut page = Page {
+        engine: RenderEngine::new(
+            order,
+            inclusion_flags,
+            render_id,
+            &text_cfg,
+            None,
+            None,
+        ),
+        grep: config.grep_highlight.as_ref(),
+        anchors: HashSet::new(),
+        body: String::new(),
+    };
+    let title = config.primary_source_name.as_deref().unwrap_or("hson");
+    if order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset) {
+        page.fileset();
+    } else {
+        let kind = super::root_input_kind(order, config);
+        page.input(ROOT_PQ_ID, kind, title);
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{}{STYLE}</style>\n</head>\n<body>\n{}\
+         </body>\n</html>",
+        escape(title),
+        theme_css_vars(),
+        page.body
+    )
+}
+
+#[derive(Copy, Clone)]
+enum Syntax {
+    Json,
+    Yaml,
+}
+
+struct Page<'a> {
+    engine: RenderEngine<'a>,
+    grep: Option<&'a regex::Regex>,
+    anchors: HashSet<String>,
+    body: String,
+}
+
+impl Page<'_> {
+    fn kept(&self, id: usize) -> bool {
+        self.engine.inclusion_flags.get(id) == Some(&self.engine.render_set_id)
+    }
+
+    fn kept_children(&self, id: usize) -> Vec<NodeId> {
+        self.engine.order.children[id]
+            .iter()
+            .copied()
+            .filter(|child| self.kept(child.0))
+            .collect()
+    }
+
+    fn fileset(&mut self) {
+        let order = self.engine.order;
+        let files = order
+            .fileset_children
+            .clone()
+            .unwrap_or_else(|| order.children[ROOT_PQ_ID].clone());
+        let mut kept: Vec<(usize, NodeId, String)> = Vec::new();
+        for (slot, id) in files.iter().enumerate() {
+            if self.kept(id.0) {
+                let name = order.nodes[id.0].key_in_object().unwrap_or("");
+                kept.push((slot, *id, self.anchor_for(name)));
+            }
+        }
+        let total =
+            order.metrics[ROOT_PQ_ID].object_len.unwrap_or(files.len());
+        let hidden = total.saturating_sub(kept.len());
+        self.nav(&kept, hidden);
+        for (slot, id, anchor) in &kept {
+            let name = order.nodes[id.0].key_in_object().unwrap_or("");
+            let kind = order
+                .fileset_kinds
+                .as_ref()
+                .and_then(|kinds| kinds.get(*slot).copied())
+                .unwrap_or_else(|| FilesetInputKind::from_filename(name));
+            self.body.push_str(&format!(
+                "<section id=\"{anchor}\">\n<h2><a href=\"#{anchor}\">{}</a></h2>\n",
+                escape(name)
+            ));
+            self.input(id.0, kind, name);
+            self.body.push_str("</section>\n");
+        }
+        if hidden > 0 {
+            self.body.push_str(&format!(
+                "<p class=\"o\">… {}</p>\n",
+                count_label(hidden, "more file")
+            ));
+        }
+    }
+
+    fn nav(&mut self, kept: &[(usize, NodeId, String)], hidden: usize) {
+        self.body.push_str("<nav>\n<ul>\n");
+        for (_, id, anchor) in kept {
+            let name =
+                self.engine.order.nodes[id.0].key_in_object().unwrap_or("");
+            self.body.push_str(&format!(
+                "<li><a href=\"#{anchor}\">{}</a></li>\n",
+                escape(name)
+            ));
+        }
+        if hidden > 0 {
+            self.body.push_str(&format!(
+                "<li class=\"o\">… {}</li>\n",
+                count_label(hidden, "more file")
+            ));
+        }
+        self.body.push_str("</ul>\n</nav>\n");
+    }
+
+    // Readable, unique section id for a file name.
+    fn anchor_for(&mut self, name: &str) -> String {
+        let slug: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let base = format!("file-{}", slug.trim_matches('-'));
+        let mut anchor = base.clone();
+        let mut n = 2;
+        while !self.anchors.insert(anchor.clone()) {
+            anchor = format!("{base}-{n}");
+            n += 1;
+        }
+        anchor
+    }
+
+    fn input(&mut self, id: usize, kind: FilesetInputKind, name: &str) {
+        match kind {
+            FilesetInputKind::Json => self.structured(id, Syntax::Json),
+            FilesetInputKind::Yaml => self.structured(id, Syntax::Yaml),
+            FilesetInputKind::Text { atomic_lines } => {
+                let highlighter =
+                    atomic_lines.then(|| CodeHighlighter::new(Some(name)));
+                self.lines(id, highlighter);
+            }
+        }
+    }
+
+    fn structured(&mut self, id: usize, syntax: Syntax) {
+        let mut html = String::new();
+        self.value(id, syntax, &mut html);
+        self.body.push_str("<div class=\"v\">");
+        self.body.push_str(&html);
+        self.body.push_str("</div>\n");
+    }
+
+    fn value(&mut self, id: usize, syntax: Syntax, html: &mut String) {
+        match &self.engine.order.nodes[id] {
+            RankedNode::Object { .. } | RankedNode::Array { .. } => {
+                self.container(id, syntax, html);
+            }
+            RankedNode::SplittableLeaf { .. } => {
+                let kept = self.kept_children(id).len();
+                let template = match syntax {
+                    Syntax::Json => crate::map_json_template_for_style(
+                        self.engine.config.style,
+                    ),
+                    Syntax::Yaml => OutputTemplate::Yaml,
+                };
+                let text = self
+                    .engine
+                    .leaf
+                    .serialize_string_for_template(id, kept, template);
+                self.token(html, "s", &text);
+            }
+            RankedNode::AtomicLeaf { .. } => {
+                let text = self.engine.leaf.serialize_atomic(id);
+                let class =
+                    if matches!(text.as_str(), "null" | "true" | "false") {
+                        "c"
+                    } else {
+                        "n"
+                    };
+                self.token(html, class, &text);
+            }
+            RankedNode::LeafPart { .. } => {}
+        }
+    }
+
+    fn token(&self, html: &mut String, class: &str, text: &str) {
+        html.push_str(&format!("<span class=\"{class}\">"));
+        push_marked(html, text, self.grep);
+        html.push_str("</span>");
+    }
+
+    // Objects and arrays fold into `<details>`; the summary keeps the size
+    // visible while folded.
+    fn container(&mut self, id: usize, syntax: Syntax, html: &mut String) {
+        let order = self.engine.order;
+        let is_array = matches!(order.nodes[id], RankedNode::Array { .. });
+        let (open, close) = brackets(syntax, is_array);
+        let unit = if is_array { "item" } else { "key" };
+        let kids = self.kept_children(id);
+        let total = self.len_of(id, is_array);
+        if kids.is_empty() {
+            push_collapsed(html, (open, close), total, unit);
+            return;
+        }
+        html.push_str("<details open><summary>");
+        push_punct(html, open, "p");
+        html.push_str(&format!(
+            "<span class=\"cnt\"> {} </span>",
+            count_label(total, unit)
+        ));
+        push_punct(html, close, "p x");
+        html.push_str("</summary><ul class=\"v\">");
+        self.items(&kids, total, syntax, is_array, html);
+        if let Some(note) = order.truncations.get(&id) {
+            html.push_str(&format!("<li class=\"o\">… {}</li>", escape(note)));
+        }
+        html.push_str("</ul>");
+        push_punct(html, close, "p");
+        html.push_str("</details>");
+    }
+
+    fn len_of(&self, id: usize, is_array: bool) -> usize {
+        let order = self.engine.order;
+        let metrics = &order.metrics[id];
+        let len = if is_array {
+            metrics.array_len
+        } else {
+            metrics.object_len
+        };
+        len.unwrap_or(order.children[id].len())
+    }
+
+    // Kept entries as list items, with a row for each run of omitted ones.
+    fn items(
+        &mut self,
+        kids: &[NodeId],
+        total: usize,
+        syntax: Syntax,
+        is_array: bool,
+        html: &mut String,
+    ) {
+        let unit = if is_array { "item" } else { "key" };
+        let mut next = 0;
+        for child in kids {
+            let index = if is_array {
+                self.engine.order.index_in_parent_array[child.0]
+                    .unwrap_or(next)
+            } else {
+                next
+            };
+            push_gap(html, index.saturating_sub(next), unit);
+            html.push_str("<li>");
+            self.entry_prefix(child.0, syntax, is_array, html);
+            self.value(child.0, syntax, html);
+            html.push_str("</li>");
+            next = index + 1;
+        }
+        push_gap(html, total.saturating_sub(next.max(kids.len())), unit);
+    }
+
+    fn entry_prefix(
+        &self,
+        id: usize,
+        syntax: Syntax,
+        is_array: bool,
+        html: &mut String,
+    ) {
+        if is_array {
+            if matches!(syntax, Syntax::Yaml) {
+                html.push_str("<span class=\"p\">- </span>");
+            }
+            return;
+        }
+        let key = self.engine.order.nodes[id].key_in_object().unwrap_or("");
+        let key = match syntax {
+            Syntax::Json => crate::utils::json::json_string(key),
+            Syntax::Yaml => key.to_string(),
+        };
+        self.token(html, "k", &key);
+        html.push_str("<span class=\"p\">: </span>");
+    }
+
+    // Text and code as numbered lines; jumps in the numbering become
+    // omission rows.
+    fn lines(
+        &mut self,
+        id: usize,
+        mut highlighter: Option<CodeHighlighter<'static>>,
+    ) {
+        let mut lines = Vec::new();
+        self.collect_lines(id, &mut lines);
+        let total = self
+            .engine
+            .order
+            .code_lines
+            .get(&id)
+            .map(|all| all.len())
+            .or(self.engine.order.metrics[id].array_len)
+            .unwrap_or(0);
+        let width = total.max(1).to_string().len();
+        self.body.push_str("<pre>");
+        let mut next = 0;
+        for (index, text) in lines {
+            push_line_gap(&mut self.body, index.saturating_sub(next));
+            self.body.push_str(&format!(
+                "<span class=\"ln\">{:>width$}</span>  ",
+                index + 1
+            ));
+            match highlighter.as_mut() {
+                Some(hl) => self.body.push_str(&hl.highlight_line_html(&text)),
+                None => push_marked(&mut self.body, &text, self.grep),
+            }
+            self.body.push('\n');
+            next = index + 1;
+        }
+        push_line_gap(&mut self.body, total.saturating_sub(next));
+        self.body.push_str("</pre>\n");
+    }
+
+    // Kept lines in document order with their 0-based line numbers; code
+    // nests blocks as arrays of lines.
+    fn collect_lines(&mut self, id: usize, lines: &mut Vec<(usize, String)>) {
+        for child in self.kept_children(id) {
+            let index = self.engine.order.index_in_parent_array[child.0]
+                .unwrap_or(lines.len());
+            match &self.engine.order.nodes[child.0] {
+                RankedNode::Array { .. } | RankedNode::Object { .. } => {
+                    self.collect_lines(child.0, lines);
+                }
+                RankedNode::SplittableLeaf { .. } => {
+                    let kept = self.kept_children(child.0).len();
+                    let text = self.engine.leaf.serialize_string_for_template(
+                        child.0,
+                        kept,
+                        OutputTemplate::Text,
+                    );
+                    lines.push((index, text));
+                }
+                RankedNode::AtomicLeaf { .. } => {
+                    lines.push((
+                        index,
+                        self.engine.leaf.serialize_atomic(child.0),
+                    ));
+                }
+                RankedNode::LeafPart { .. } => {}
+            }
+        }
+    }
+}
+
+fn brackets(syntax: Syntax, is_array: bool) -> (&'static str, &'static str) {
+    match (syntax, is_array) {
+        (Syntax::Json, true) => ("[", "]"),
+        (Syntax::Json, false) => ("{", "}"),
+        (Syntax::Yaml, _) => ("", ""),
+    }
+}
+
+// A container with nothing kept stays on one line, e.g. `{ … 3 keys }`.
+fn push_collapsed(
+    html: &mut String,
+    (open, close): (&str, &str),
+    total: usize,
+    unit: &str,
+) {
+    push_punct(html, open, "p");
+    if total > 0 {
+        html.push_str(&format!(
+            "<span class=\"o\"> … {} </span>",
+            count_label(total, unit)
+        ));
+    }
+    push_punct(html, close, "p");
+}
+
+// YAML has no brackets; skip the empty spans.
+fn push_punct(html: &mut String, text: &str, class: &str) {
+    if !text.is_empty() {
+        html.push_str(&format!("<span class=\"{class}\">{text}</span>"));
+    }
+}
+
+fn push_gap(html: &mut String, omitted: usize, unit: &str) {
+    if omitted > 0 {
+        html.push_str(&format!(
+            "<li class=\"o\">… {}</li>",
+            count_label(omitted, &format!("more {unit}"))
+        ));
+    }
+}
+
+fn push_line_gap(html: &mut String, omitted: usize) {
+    if omitted > 0 {
+        html.push_str(&format!(
+            "<span class=\"o\">… {}</span>\n",
+            count_label(omitted, "more line")
+        ));
+    }
+}
+
+fn count_label(n: usize, unit: &str) -> String {
+    let plural = if n == 1 { "" } else { "s" };
+    format!("{n} {unit}{plural}")
+}
+
+// Escaped text with grep matches wrapped in `<mark>`.
+fn push_marked(html: &mut String, text: &str, grep: Option<&regex::Regex>) {
+    let mut last = 0;
+    for m in grep.into_iter().flat_map(|re| re.find_iter(text)) {
+        if m.as_str().is_empty() {
+            continue;
+        }
+        html.push_str(&escape(&text[last..m.start()]));
+        html.push_str("<mark>");
+        html.push_str(&escape(m.as_str()));
+        html.push_str("</mark>");
+        last = m.end();
+    }
+    html.push_str(&escape(&text[last..]));
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,77 @@
+//! Paths naming a node from the document root, as in
+//! `$.items[412].spec["container image"]`: `--key-paths` comments and the
+//! `gron` template build them from the same segments.
+
+use crate::order::{NodeId, ObjectType, PriorityOrder, ROOT_PQ_ID};
+
+pub(crate) const ROOT: &str = "$";
+
+/// Segment for an object member: `.key` when the key is an identifier,
+/// else a bracketed JSON string.
+pub(crate) fn key_segment(key: &str) -> String {
+    let mut chars = key.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        format!(".{key}")
+    } else {
+        format!("[{}]", crate::utils::json::json_string(key))
+    }
+}
+
+pub(crate) fn index_segment(index: usize) -> String {
+    format!("[{index}]")
+}
+
+/// Segment leading from a node's parent to the node itself.
+pub(crate) fn segment(order: &PriorityOrder, id: usize) -> String {
+    match order.nodes[id].key_in_object() {
+        Some(key) => key_segment(key),
+        None => index_segment(
+            order
+                .index_in_parent_array
+                .get(id)
+                .copied()
+                .flatten()
+                .unwrap_or(0),
+        ),
+    }
+}
+
+/// Full path of `id`. Files of a fileset each get their own root.
+pub(crate) fn path_of(order: &PriorityOrder, id: usize) -> String {
+    let mut segments = Vec::new();
+    let mut cursor = id;
+    while let Some(NodeId(parent)) =
+        order.parent.get(cursor).copied().flatten()
+    {
+        if parent == ROOT_PQ_ID
+            && order.object_type.get(parent) == Some(&ObjectType::Fileset)
+        {
+            break;
+        }
+        segments.push(segment(order, cursor));
+        cursor = parent;
+    }
+    segments.iter().rev().fold(ROOT.to_string(), |mut path, s| {
+        path.push_str(s);
+        path
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_that_are_not_identifiers_are_bracketed() {
+        let segments: Vec<String> =
+            ["image", "_id$2", "a b", "2x", ""].map(key_segment).into();
+        assert_eq!(
+            segments,
+            [".image", "._id$2", "[\"a b\"]", "[\"2x\"]", "[\"\"]"]
+        );
+    }
+}
@@ -11,6 +11,8 @@ pub(super) struct LeafRenderer<'a> {
     order: &'a PriorityOrder,
     config: &'a crate::RenderConfig,
     grep_highlight: Option<Regex>,
+    // Per-node flags for leaves highlighted as a whole (predicate hits).
+    highlight_nodes: Option<&'a [bool]>,
     code_highlight_cache: HashMap<usize, Arc<Vec<String>>>,
     source_hint: Box<dyn Fn(usize) -> Option<&'a str> + 'a>,
 }
@@ -29,13 +31,30 @@ impl<'a> LeafRenderer<'a> {
             order,
             config,
             grep_highlight,
+            highlight_nodes: None,
             code_highlight_cache: HashMap::new(),
             source_hint: Box::new(source_hint),
         }
     }
 
-    pub(super) fn grep_highlight(&self) -> &Option<Regex> {
-        &self.grep_highlight
+    pub(super) fn grep_highlight(&self) -> Option<&Regex> {
+        self.grep_highlight.as_ref()
+    }
+
+    pub(super) fn set_highlight_nodes(&mut self, nodes: Option<&'a [bool]>) {
+        self.highlight_nodes = nodes;
+    }
+
+    fn highlight_for(&self, id: usize) -> Option<&Regex> {
+        let whole = self
+            .highlight_nodes
+            .and_then(|flags| flags.get(id))
+            .copied()
+            .unwrap_or(false);
+        if whole {
+            return super::highlight::WHOLE_VALUE.as_ref();
+        }
+        self.grep_highlight.as_ref()
     }
 
     pub(super) fn source_hint(&self, id: usize) -> Option<&'a str> {
@@ -93,8 +112,12 @@ impl<'a> LeafRenderer<'a> {
         let render_prefix = self.render_prefix_len(kept_graphemes);
         let omitted = self.omitted_for(id, render_prefix).unwrap_or(0);
         let full = self.full_string(id);
+        let annotated = template
+            == crate::serialization::types::OutputTemplate::Json
+            && self.config.style
+                == crate::serialization::types::Style::Annotated;
         let truncated_buf =
-            self.truncated_display(full, render_prefix, omitted);
+            truncated_display(full, render_prefix, omitted, annotated);
         let raw_for_highlight = truncated_buf.as_deref().unwrap_or(full);
         let rendered = self.render_for_template(raw_for_highlight, template);
         let highlight_kind = self.highlight_kind_for(template);
@@ -103,7 +126,7 @@ impl<'a> LeafRenderer<'a> {
             Some(raw_for_highlight),
             rendered,
             highlight_kind,
-            &self.grep_highlight,
+            self.highlight_for(id),
         )
     }
 
@@ -117,7 +140,7 @@ impl<'a> LeafRenderer<'a> {
             None,
             rendered,
             HighlightKind::TextLike,
-            &self.grep_highlight,
+            self.highlight_for(id),
         )
     }
 
@@ -126,10 +149,8 @@ impl<'a> LeafRenderer<'a> {
         array_id: usize,
         template: crate::OutputTemplate,
     ) -> Option<Arc<Vec<String>>> {
-        if !matches!(
-            self.config.color_strategy(),
-            crate::serialization::types::ColorStrategy::Syntax
-        ) {
+        let strategy = self.config.color_strategy();
+        if !strategy.syntax_colors() {
             return None;
         }
         if !matches!(template, crate::OutputTemplate::Code) {
@@ -146,6 +167,9 @@ impl<'a> LeafRenderer<'a> {
                 self.order,
                 root,
                 (self.source_hint)(root),
+                self.grep_highlight.as_ref().filter(|_| {
+                    strategy == crate::serialization::types::ColorStrategy::Combined
+                }),
             ));
         self.code_highlight_cache.insert(root, computed.clone());
         Some(computed)
@@ -167,21 +191,6 @@ impl<'a> LeafRenderer<'a> {
         }
     }
 
-    fn truncated_display(
-        &self,
-        full: &str,
-        render_prefix: usize,
-        omitted: usize,
-    ) -> Option<String> {
-        if omitted == 0 {
-            None
-        } else {
-            let prefix =
-                crate::utils::text::take_n_graphemes(full, render_prefix);
-            Some(format!("{prefix}…"))
-        }
-    }
-
     fn highlight_kind_for(
         &self,
         template: crate::serialization::types::OutputTemplate,
@@ -213,3 +222,22 @@ impl<'a> LeafRenderer<'a> {
         }
     }
 }
+
+// Kept prefix of a cut string followed by `…`; annotated JSON also records
+// how many graphemes were cut, as in `abc…[+120 chars]`.
+fn truncated_display(
+    full: &str,
+    render_prefix: usize,
+    omitted: usize,
+    annotated: bool,
+) -> Option<String> {
+    if omitted == 0 {
+        return None;
+    }
+    let prefix = crate::utils::text::take_n_graphemes(full, render_prefix);
+    Some(if annotated {
+        format!("{prefix}…[+{omitted} chars]")
+    } else {
+        format!("{prefix}…")
+    })
+}
@@ -0,0 +1,63 @@
+use crate::FilesetInputKind;
+
+use super::highlight::fence_language_for_hint;
+
+/// Info string for the fence around an input ingested as `kind`; code
+/// takes its language from the file name.
+pub(super) fn fence_language(
+    kind: FilesetInputKind,
+    name: Option<&str>,
+) -> String {
+    match kind {
+        FilesetInputKind::Json => "json".to_string(),
+        FilesetInputKind::Yaml => "yaml".to_string(),
+        FilesetInputKind::Text { atomic_lines: true } => {
+            fence_language_for_hint(name).unwrap_or_else(|| "text".into())
+        }
+        FilesetInputKind::Text {
+            atomic_lines: false,
+        } => "text".to_string(),
+    }
+}
+
+/// Wrap `body` in a fenced code block. The fence is one backtick longer
+/// than any run opening a line of the body, so previews of Markdown files
+/// cannot close it early.
+pub(super) fn fence(body: &str, language: &str, newline: &str) -> String {
+    let longest = body
+        .lines()
+        .map(|line| line.trim_start().bytes().take_while(|b| *b == b'`'))
+        .map(Iterator::count)
+        .max()
+        .unwrap_or(0);
+    let ticks = "`".repeat(longest.max(2) + 1);
+    let body = body.strip_suffix(newline).unwrap_or(body);
+    if body.is_empty() {
+        return format!("{ticks}{language}{newline}{ticks}");
+    }
+    format!("{ticks}{language}{newline}{body}{newline}{ticks}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fence_outgrows_backtick_runs_in_body() {
+        assert_eq!(fence("a\n", "json", "\n"), "```json\na\n```");
+        assert_eq!(
+            fence("```rust\nx\n```", "markdown", "\n"),
+            "````markdown\n```rust\nx\n```\n````"
+        );
+        assert_eq!(fence("", "text", "\n"), "```text\n```");
+    }
+
+    #[test]
+    fn code_fences_use_detected_language() {
+        let code = FilesetInputKind::Text { atomic_lines: true };
+        assert_eq!(fence_language(code, Some("main.rs")), "rust");
+        assert_eq!(fence_language(code, Some("run.sh")), "sh");
+        assert_eq!(fence_language(code, None), "text");
+        assert_eq!(fence_language(FilesetInputKind::Yaml, None), "yaml");
+    }
+}
@@ -5,7 +5,10 @@ pub mod color;
 mod engine;
 mod fileset;
 mod highlight;
+mod html;
+mod key_path;
 mod leaf;
+mod markdown;
 pub mod output;
 pub mod templates;
 pub mod types;
@@ -22,6 +25,7 @@ struct RenderRun<'a> {
     config: &'a crate::RenderConfig,
     slot_map: Option<&'a [Option<usize>]>,
     recorder: Option<crate::serialization::output::SlotStatsRecorder>,
+    highlight_nodes: Option<&'a [bool]>,
 }
 
 impl<'a> RenderRun<'a> {
@@ -40,6 +44,7 @@ impl<'a> RenderRun<'a> {
             config,
             slot_map,
             recorder,
+            highlight_nodes: None,
         }
     }
 
@@ -54,6 +59,7 @@ impl<'a> RenderRun<'a> {
                 || (matches!(
                     self.config.template,
                     crate::OutputTemplate::Auto
+                        | crate::OutputTemplate::Markdown
                 ) && self.root_is_fileset());
         if !should_measure_line_numbers {
             return None;
@@ -79,9 +85,61 @@ impl<'a> RenderRun<'a> {
         }
     }
 
+    // Template and fence language for a single input rendered as Markdown;
+    // filesets fence each file while rendering sections.
+    fn markdown_inner(&self) -> Option<(crate::OutputTemplate, String)> {
+        if self.config.template != crate::OutputTemplate::Markdown
+            || self.root_is_fileset()
+        {
+            return None;
+        }
+        let name = self.config.primary_source_name.as_deref();
+        let kind = root_input_kind(self.order_build, self.config);
+        Some((
+            crate::utils::templates::template_for_input_kind(
+                kind,
+                self.config.style,
+            ),
+            markdown::fence_language(kind, name),
+        ))
+    }
+
+    fn render_markdown(
+        self,
+        template: crate::OutputTemplate,
+        language: &str,
+    ) -> (String, Option<Vec<crate::utils::measure::OutputStats>>) {
+        let mut inner_cfg = self.config.clone();
+        inner_cfg.template = template;
+        let mut inner = RenderRun::new(
+            self.order_build,
+            self.inclusion_flags,
+            self.render_id,
+            &inner_cfg,
+            self.slot_map,
+            self.recorder,
+        );
+        inner.highlight_nodes = self.highlight_nodes;
+        let (body, slot_stats) = inner.render();
+        let text = markdown::fence(&body, language, &self.config.newline);
+        (text, slot_stats)
+    }
+
     fn render(
         mut self,
     ) -> (String, Option<Vec<crate::utils::measure::OutputStats>>) {
+        if self.config.template == crate::OutputTemplate::Html {
+            let page = html::render_page(
+                self.order_build,
+                self.inclusion_flags,
+                self.render_id,
+                self.config,
+            );
+            return (page, None);
+        }
+        if let Some((template, language)) = self.markdown_inner() {
+            return self.render_markdown(template, &language);
+        }
         let line_number_width = self.line_number_width();
         let mut engine = RenderEngine::new(
             self.order_build,
@@ -91,6 +149,7 @@ impl<'a> RenderRun<'a> {
             line_number_width,
             self.slot_map,
         );
+        engine.leaf.set_highlight_nodes(self.highlight_nodes);
         let mut s = String::new();
         let recorded = self.recorder.is_some();
         let mut out = Out::new_with_recorder(
@@ -99,12 +158,45 @@ impl<'a> RenderRun<'a> {
             line_number_width,
             self.recorder.take(),
         );
+        engine.set_gutters(&mut out, ROOT_PQ_ID, false, self.config.template);
         engine.write_node(ROOT_PQ_ID, 0, false, &mut out);
         let slot_stats = out.into_slot_stats();
         (s, self.guard_slot_stats(slot_stats, recorded))
     }
 }
 
+// How a single (non-fileset) input was ingested: recorded at ingest, else
+// guessed from its name, else JSON.
+fn root_input_kind(
+    order: &PriorityOrder,
+    config: &crate::RenderConfig,
+) -> crate::FilesetInputKind {
+    order
+        .input_kind
+        .or_else(|| {
+            config
+                .primary_source_name
+                .as_deref()
+                .map(crate::FilesetInputKind::from_filename)
+        })
+        .unwrap_or(crate::FilesetInputKind::Json)
+}
+
+/// Template whose text an HTML page is budgeted by: per-file templates for
+/// filesets, else the one matching how the input was ingested.
+pub(crate) fn html_measure_template(
+    order: &PriorityOrder,
+    config: &crate::RenderConfig,
+) -> crate::OutputTemplate {
+    if order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset) {
+        return crate::OutputTemplate::Auto;
+    }
+    crate::utils::templates::template_for_input_kind(
+        root_input_kind(order, config),
+        config.style,
+    )
+}
+
 /// Render using a previously prepared render set (inclusion flags matching `render_id`).
 pub fn render_from_render_set(
     order_build: &PriorityOrder,
@@ -123,6 +215,27 @@ pub fn render_from_render_set(
     .0
 }
 
+/// Like [`render_from_render_set`], additionally highlighting whole leaves
+/// flagged in `highlight_nodes` (indexed by node id).
+pub(crate) fn render_from_render_set_with_highlights(
+    order_build: &PriorityOrder,
+    inclusion_flags: &[u32],
+    render_id: u32,
+    config: &crate::RenderConfig,
+    highlight_nodes: Option<&[bool]>,
+) -> String {
+    let mut run = RenderRun::new(
+        order_build,
+        inclusion_flags,
+        render_id,
+        config,
+        None,
+        None,
+    );
+    run.highlight_nodes = highlight_nodes;
+    run.render().0
+}
+
 pub fn render_from_render_set_with_slots(
     order_build: &PriorityOrder,
     inclusion_flags: &[u32],
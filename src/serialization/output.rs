@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use super::color;
 use crate::utils::measure::OutputStats;
 use crate::utils::measure::{count_line_breaks, ends_with_break};
@@ -81,6 +83,14 @@ impl SlotStatsRecorder {
     }
 }
 
+/// What a rendered line belongs to, for the gutters in front of it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LineMark {
+    pub diff: Option<crate::diff::DiffMark>,
+    pub source_line: Option<usize>,
+    pub path: Option<Rc<str>>,
+}
+
 // Simple output layer that centralizes colored and structured pushes
 // while still rendering into a String buffer (to preserve sizing/measurement).
 pub struct Out<'a> {
@@ -88,12 +98,26 @@ pub struct Out<'a> {
     newline: String,
     indent_unit: String,
     // Syntax/role colors are only emitted when both color_enabled is true
-    // and the strategy allows syntax coloring (ColorStrategy::Syntax).
+    // and the strategy allows syntax coloring (Syntax or Combined).
     role_colors_enabled: bool,
     style: crate::serialization::types::Style,
     line_number_width: Option<usize>,
     recorder: Option<SlotStatsRecorder>,
     current_slot: Option<usize>,
+    // Structural diff output: every line starts with a gutter column holding
+    // the mark of the node the line belongs to.
+    diff_gutter: bool,
+    line_mark: Option<crate::diff::DiffMark>,
+    // Source line gutter: the width of the widest line number, and the line
+    // for this node's opening line (none once written, or when the opener
+    // sits on the parent's line).
+    source_gutter: Option<usize>,
+    opening_line: Option<usize>,
+    // Key path comments: the comment opener, this node's path for its
+    // opening line, and the path owed to the line being written.
+    key_paths: Option<&'static str>,
+    opening_path: Option<Rc<str>>,
+    pending_path: Option<Rc<str>>,
 }
 
 impl<'a> Out<'a> {
@@ -111,10 +135,7 @@ impl<'a> Out<'a> {
         line_number_width: Option<usize>,
         recorder: Option<SlotStatsRecorder>,
     ) -> Self {
-        let role_colors_enabled = matches!(
-            config.color_strategy(),
-            crate::serialization::types::ColorStrategy::Syntax
-        );
+        let role_colors_enabled = config.color_strategy().syntax_colors();
         Self {
             buf,
             newline: config.newline.clone(),
@@ -124,9 +145,48 @@ impl<'a> Out<'a> {
             line_number_width,
             recorder,
             current_slot: None,
+            diff_gutter: false,
+            line_mark: None,
+            source_gutter: None,
+            opening_line: None,
+            key_paths: None,
+            opening_path: None,
+            pending_path: None,
         }
     }
 
+    /// Enable the diff gutter; lines opened by `push_indent` carry `mark`.
+    pub fn set_diff_gutter(
+        &mut self,
+        enabled: bool,
+        mark: Option<crate::diff::DiffMark>,
+    ) {
+        self.diff_gutter = enabled && !self.newline.is_empty();
+        self.line_mark = mark;
+    }
+
+    /// Enable the source line gutter; the first line opened by
+    /// `push_indent` carries `opening_line`, later ones a blank gutter.
+    pub fn set_source_gutter(
+        &mut self,
+        width: Option<usize>,
+        opening_line: Option<usize>,
+    ) {
+        self.source_gutter = width.filter(|_| !self.newline.is_empty());
+        self.opening_line = opening_line;
+    }
+
+    /// Enable key path comments opened by `comment`; the first line opened
+    /// by `push_indent` ends with `opening_path`.
+    pub fn set_key_paths(
+        &mut self,
+        comment: Option<&'static str>,
+        opening_path: Option<Rc<str>>,
+    ) {
+        self.key_paths = comment.filter(|_| !self.newline.is_empty());
+        self.opening_path = opening_path;
+    }
+
     pub fn set_current_slot(&mut self, slot: Option<usize>) {
         self.current_slot = slot;
     }
@@ -138,10 +198,37 @@ impl<'a> Out<'a> {
     }
 
     pub fn push_str(&mut self, s: &str) {
+        // A child rendered inline ends its first line inside `s`; that line
+        // owes the path comment.
+        let first_break = self
+            .pending_path
+            .as_ref()
+            .and_then(|_| s.find(self.newline.as_str()));
+        if let Some(at) = first_break {
+            let (line, rest) = s.split_at(at);
+            self.push_raw(line);
+            self.push_path_comment();
+            self.push_raw(rest);
+        } else {
+            self.push_raw(s);
+        }
+    }
+
+    fn push_raw(&mut self, s: &str) {
         self.buf.push_str(s);
         self.record_chunk(s);
     }
 
+    fn push_path_comment(&mut self) {
+        let Some(path) = self.pending_path.take() else {
+            return;
+        };
+        if let Some(comment) = self.key_paths {
+            self.push_raw(" ");
+            self.push_comment(format!("{comment} {path}"));
+        }
+    }
+
     pub fn push_char(&mut self, c: char) {
         self.buf.push(c);
         let mut buf = [0u8; 4];
@@ -150,17 +237,86 @@ impl<'a> Out<'a> {
     }
 
     pub fn push_newline(&mut self) {
+        self.push_path_comment();
         let nl = self.newline.clone();
         self.buf.push_str(&nl);
         self.record_chunk(&nl);
     }
 
     pub fn push_indent(&mut self, depth: usize) {
+        let mark = LineMark {
+            diff: self.line_mark,
+            source_line: self.opening_line.take(),
+            path: self.opening_path.take(),
+        };
+        self.push_gutters(mark);
+        self.push_indent_only(depth);
+    }
+
+    /// Like `push_indent`, for a line owned by a child node with `mark`.
+    pub fn push_indent_marked(&mut self, depth: usize, mark: LineMark) {
+        self.opening_line = None;
+        self.opening_path = None;
+        self.push_gutters(mark);
+        self.push_indent_only(depth);
+    }
+
+    fn push_gutters(&mut self, mark: LineMark) {
+        if self.key_paths.is_some() {
+            self.pending_path = mark.path;
+        }
+        if let Some(width) = self.source_gutter {
+            let gutter = match mark.source_line {
+                Some(n) => format!("{n:>width$}: "),
+                None => " ".repeat(width + 2),
+            };
+            self.push_str(&gutter);
+        }
+        if self.diff_gutter {
+            let gutter =
+                color::diff_gutter(mark.diff, self.role_colors_enabled);
+            self.push_str(gutter);
+        }
+    }
+
+    fn push_indent_only(&mut self, depth: usize) {
         let s = self.indent_unit.repeat(depth);
         self.record_chunk(&s);
         self.buf.push_str(&s);
     }
 
+    /// Split a line rendered by a child `Out` into its gutters and the rest,
+    /// so templates that re-indent child lines can keep the gutters.
+    pub fn split_gutter<'l>(&self, line: &'l str) -> (&'l str, &'l str) {
+        let source = self.source_gutter.map_or(0, |width| width + 2);
+        let Some(rest) = line.get(source..) else {
+            return ("", line);
+        };
+        let diff = if self.diff_gutter {
+            [
+                None,
+                Some(crate::diff::DiffMark::Added),
+                Some(crate::diff::DiffMark::Removed),
+            ]
+            .into_iter()
+            .map(|m| color::diff_gutter(m, self.role_colors_enabled))
+            .find(|g| rest.starts_with(g))
+            .map_or(0, str::len)
+        } else {
+            0
+        };
+        line.split_at(source + diff)
+    }
+
+    /// Push gutters taken from a child line, then indentation without them.
+    pub fn push_indent_after_gutter(&mut self, gutter: &str, depth: usize) {
+        self.opening_line = None;
+        self.opening_path = None;
+        self.push_str(gutter);
+        let s = self.indent_unit.repeat(depth);
+        self.push_str(&s);
+    }
+
     pub fn push_comment<S: Into<String>>(&mut self, body: S) {
         let s = color::color_comment(body, self.role_colors_enabled);
         self.buf.push_str(&s);
@@ -214,6 +370,10 @@ impl<'a> Out<'a> {
         self.newline.is_empty() && self.indent_unit.is_empty()
     }
 
+    pub fn newline(&self) -> &str {
+        &self.newline
+    }
+
     pub fn style(&self) -> crate::serialization::types::Style {
         self.style
     }
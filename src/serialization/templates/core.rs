@@ -34,9 +34,10 @@ fn maybe_push_internal_gap<S: Style>(
     }
 }
 
-fn push_single_array_item(
+pub(crate) fn push_single_array_item(
     out: &mut Out<'_>,
     ctx: &ArrayCtx<'_>,
+    i: usize,
     kind: NodeKind,
     item: &str,
 ) {
@@ -47,7 +48,7 @@ fn push_single_array_item(
     match kind {
         NodeKind::Array | NodeKind::Object => out.push_str(item),
         _ => {
-            out.push_indent(ctx.depth + 1);
+            out.push_indent_marked(ctx.depth + 1, ctx.child_mark(i));
             out.push_str(item);
         }
     }
@@ -60,7 +61,7 @@ pub(crate) fn push_array_items_with<S: Style>(
     let mut prev_index: Option<usize> = None;
     for (i, (orig_index, (kind, item))) in ctx.children.iter().enumerate() {
         maybe_push_internal_gap::<S>(out, ctx, prev_index, *orig_index);
-        push_single_array_item(out, ctx, *kind, item);
+        push_single_array_item(out, ctx, i, *kind, item);
         if i + 1 < ctx.children_len {
             out.push_char(',');
         }
@@ -69,7 +70,7 @@ pub(crate) fn push_array_items_with<S: Style>(
     }
 }
 
-fn push_value_token(out: &mut Out<'_>, v: &str) {
+pub(crate) fn push_value_token(out: &mut Out<'_>, v: &str) {
     // Preserve exact token text; only color string literals.
     if v.starts_with('"') {
         out.push_string_literal(v);
@@ -80,7 +81,7 @@ fn push_value_token(out: &mut Out<'_>, v: &str) {
 
 pub(crate) fn push_object_items(out: &mut Out<'_>, ctx: &ObjectCtx<'_>) {
     for (i, (_, (k, v))) in ctx.children.iter().enumerate() {
-        out.push_indent(ctx.depth + 1);
+        out.push_indent_marked(ctx.depth + 1, ctx.child_mark(i));
         out.push_key(k);
         out.push_char(':');
         out.push_str(ctx.space);
@@ -92,6 +93,53 @@ pub(crate) fn push_object_items(out: &mut Out<'_>, ctx: &ObjectCtx<'_>) {
     }
 }
 
+// Marks a container that was parsed out of a string value. Emitted as the
+// first line inside the block so it never disturbs the key/opener layout.
+pub(crate) fn push_embedded_marker(
+    out: &mut Out<'_>,
+    depth: usize,
+    embedded: bool,
+    comment: &str,
+) {
+    if embedded {
+        out.push_indent(depth);
+        out.push_comment(comment);
+        out.push_newline();
+    }
+}
+
+// Marks where recovering JSON ingest cut off malformed input. Emitted as the
+// last line inside the block, after any omission marker.
+pub(crate) fn push_truncation_marker(
+    out: &mut Out<'_>,
+    depth: usize,
+    note: Option<&str>,
+    (open, close): (&str, &str),
+) {
+    if let Some(note) = note {
+        out.push_indent(depth);
+        out.push_comment(format!("{open}{note}{close}"));
+        out.push_newline();
+    }
+}
+
+// Inline variant for containers rendered empty, as in `[ /* ... */ ]`;
+// follows the omission marker when there is one.
+pub(crate) fn push_inline_truncation_marker(
+    out: &mut Out<'_>,
+    note: Option<&str>,
+    space: &str,
+    after_omission: bool,
+) {
+    if let Some(note) = note {
+        if !after_omission {
+            out.push_str(space);
+        }
+        out.push_comment(format!("/* {note} */"));
+        out.push_str(space);
+    }
+}
+
 // A no-op style for cases where only the array item printing is desired without gap markers.
 pub struct StyleNoop;
 impl Style for StyleNoop {}
@@ -0,0 +1,112 @@
+//! Flattened `path = value` lines, as gron prints them. A container renders
+//! its lines relative to itself (` = {}`, `.name = "svc"`, `[0] = 1`) and
+//! its parent prefixes each with the container's own segment, so the root
+//! ends up with full paths from `$`.
+
+use super::core::push_value_token;
+use super::{ArrayCtx, ObjectCtx};
+use crate::serialization::key_path::{ROOT, index_segment};
+use crate::serialization::output::Out;
+use crate::serialization::types::Style;
+
+const ASSIGN: &str = " = ";
+
+// Writes newline-separated lines, each starting with the container's base
+// (`$` at the root, empty below it).
+struct Lines<'o, 'a> {
+    out: &'o mut Out<'a>,
+    base: &'static str,
+    started: bool,
+}
+
+impl<'o, 'a> Lines<'o, 'a> {
+    fn new(out: &'o mut Out<'a>, depth: usize) -> Self {
+        let base = if depth == 0 { ROOT } else { "" };
+        Self {
+            out,
+            base,
+            started: false,
+        }
+    }
+
+    fn begin(&mut self) -> &mut Out<'a> {
+        if self.started {
+            self.out.push_newline();
+        }
+        self.started = true;
+        self.out.push_str(self.base);
+        self.out
+    }
+
+    fn own(&mut self, empty: &str) {
+        let out = self.begin();
+        out.push_str(ASSIGN);
+        out.push_str(empty);
+    }
+
+    // A child's lines: a container's come relative and get `segment` in
+    // front; a leaf is a bare token.
+    fn child(&mut self, segment: &str, item: &str) {
+        if !item.starts_with(ASSIGN) {
+            let out = self.begin();
+            out.push_key(segment);
+            out.push_str(ASSIGN);
+            push_value_token(out, item);
+            return;
+        }
+        let newline = self.out.newline().to_string();
+        for line in item.split(newline.as_str()) {
+            let out = self.begin();
+            out.push_key(segment);
+            out.push_str(line);
+        }
+    }
+
+    fn omission(&mut self, (open, close): (&str, &str)) {
+        if matches!(self.out.style(), Style::Strict) {
+            return;
+        }
+        let out = self.begin();
+        out.push_str(open);
+        out.push_omission();
+        out.push_str(close);
+    }
+}
+
+pub(super) fn render_array(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
+    let mut lines = Lines::new(out, ctx.depth);
+    lines.own("[]");
+    let trailing_gap = ctx.omitted > 0 && !ctx.omitted_at_start;
+    if ctx.omitted > 0 && ctx.omitted_at_start {
+        lines.omission(("[", "]"));
+    }
+    let mut prev: Option<usize> = None;
+    for (index, (_, item)) in &ctx.children {
+        if prev.is_some_and(|p| *index > p + 1) {
+            lines.omission(("[", "]"));
+        }
+        lines.child(&index_segment(*index), item);
+        prev = Some(*index);
+    }
+    if trailing_gap {
+        lines.omission(("[", "]"));
+    }
+}
+
+/// Object keys arrive as path segments (see the engine), not JSON strings.
+pub(super) fn render_object(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
+    let mut lines = Lines::new(out, ctx.depth);
+    lines.own("{}");
+    for (_, (segment, value)) in &ctx.children {
+        lines.child(segment, value);
+    }
+    if ctx.omitted > 0 {
+        lines.omission(("{", "}"));
+    }
+}
+
+/// A root that is a leaf renders as the single line `$ = value`.
+pub(crate) fn push_root_leaf_prefix(out: &mut Out<'_>) {
+    out.push_str(ROOT);
+    out.push_str(ASSIGN);
+}
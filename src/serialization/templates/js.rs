@@ -1,9 +1,14 @@
 use super::core::{
-    Style, push_array_items_with, push_object_items, wrap_block,
+    Style, push_array_items_with, push_embedded_marker,
+    push_inline_truncation_marker, push_object_items, push_truncation_marker,
+    wrap_block,
 };
 use super::{ArrayCtx, ObjectCtx};
 use crate::serialization::output::Out;
 
+const EMBEDDED: &str = "/* embedded */";
+const TRUNCATED: (&str, &str) = ("/* ", " */");
+
 struct Js;
 
 impl Style for Js {
@@ -51,11 +56,13 @@ fn render_array_empty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
         out.push_comment(format!("/* {} more items */", ctx.omitted));
         out.push_str(" ");
     }
+    push_inline_truncation_marker(out, ctx.truncation, " ", ctx.omitted > 0);
     out.push_char(']');
 }
 
 fn render_array_nonempty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
     wrap_block(out, ctx.depth, ctx.inline_open, '[', ']', |o| {
+        push_embedded_marker(o, ctx.depth + 1, ctx.embedded, EMBEDDED);
         if ctx.omitted_at_start {
             <Js as Style>::array_push_omitted(o, ctx);
         }
@@ -63,6 +70,7 @@ fn render_array_nonempty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
         if !ctx.omitted_at_start {
             <Js as Style>::array_push_omitted(o, ctx);
         }
+        push_truncation_marker(o, ctx.depth + 1, ctx.truncation, TRUNCATED);
     });
 }
 
@@ -89,13 +97,21 @@ fn render_object_empty(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
         out.push_comment(format!("/* {} more {label} */", ctx.omitted));
         out.push_str(ctx.space);
     }
+    push_inline_truncation_marker(
+        out,
+        ctx.truncation,
+        ctx.space,
+        ctx.omitted > 0,
+    );
     out.push_char('}');
 }
 
 fn render_object_nonempty(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
     wrap_block(out, ctx.depth, ctx.inline_open, '{', '}', |o| {
+        push_embedded_marker(o, ctx.depth + 1, ctx.embedded, EMBEDDED);
         push_object_items(o, ctx);
         <Js as Style>::object_push_omitted(o, ctx);
+        push_truncation_marker(o, ctx.depth + 1, ctx.truncation, TRUNCATED);
     });
 }
 
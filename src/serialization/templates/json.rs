@@ -1,10 +1,24 @@
+use std::borrow::Cow;
+
 use super::{ArrayCtx, ObjectCtx};
-use crate::serialization::output::Out;
+use crate::serialization::output::{LineMark, Out};
 use crate::serialization::templates::core::{
-    StyleNoop, push_array_items_with, push_object_items, wrap_block,
+    StyleNoop, push_array_items_with, push_object_items,
+    push_single_array_item, push_value_token, wrap_block,
 };
+use crate::serialization::types::Style;
+
+const OMITTED_ITEMS_KEY: &str = "\"$omitted_items\"";
+const OMITTED_KEYS_KEY: &str = "\"$omitted_keys\"";
+const TRUNCATED_KEY: &str = "\"$truncated\"";
+// Marker names without their `$`; input keys spelled like one are escaped.
+const MARKER_NAMES: [&str; 3] = ["omitted_items", "omitted_keys", "truncated"];
 
 pub(super) fn render_array(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
+    if out.style() == Style::Annotated {
+        render_array_annotated(ctx, out);
+        return;
+    }
     if ctx.children_len == 0 {
         if !ctx.inline_open {
             out.push_indent(ctx.depth);
@@ -19,6 +33,10 @@ pub(super) fn render_array(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
 }
 
 pub(super) fn render_object(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
+    if out.style() == Style::Annotated {
+        render_object_annotated(ctx, out);
+        return;
+    }
     if ctx.children_len == 0 {
         if !ctx.inline_open {
             out.push_indent(ctx.depth);
@@ -30,3 +48,156 @@ pub(super) fn render_object(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
         push_object_items(o, ctx);
     });
 }
+
+// An array entry in annotated output: a kept item, or a marker object that
+// stands in for omitted items or the point where recovery cut the input.
+enum Entry {
+    Item(usize),
+    Marker(&'static str, String),
+}
+
+// Markers are placed from the original indices of the kept items so each
+// one counts exactly the items missing at its position.
+fn array_entries(ctx: &ArrayCtx<'_>) -> Vec<Entry> {
+    let mut entries = Vec::with_capacity(ctx.children.len() + 2);
+    let total = ctx.children_len + ctx.omitted;
+    let mut next = 0;
+    for (i, (orig_index, _)) in ctx.children.iter().enumerate() {
+        push_omitted(&mut entries, orig_index.saturating_sub(next));
+        entries.push(Entry::Item(i));
+        next = orig_index + 1;
+    }
+    push_omitted(&mut entries, total.saturating_sub(next));
+    if let Some(note) = ctx.truncation {
+        entries.push(Entry::Marker(
+            TRUNCATED_KEY,
+            crate::utils::json::json_string(note),
+        ));
+    }
+    entries
+}
+
+fn push_omitted(entries: &mut Vec<Entry>, n: usize) {
+    if n > 0 {
+        entries.push(Entry::Marker(OMITTED_ITEMS_KEY, n.to_string()));
+    }
+}
+
+fn push_marker(out: &mut Out<'_>, space: &str, key: &str, value: &str) {
+    out.push_char('{');
+    out.push_key(key);
+    out.push_char(':');
+    out.push_str(space);
+    out.push_str(value);
+    out.push_char('}');
+}
+
+fn render_array_annotated(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
+    let entries = array_entries(ctx);
+    if ctx.children_len == 0 {
+        render_markers_inline(ctx, &entries, out);
+        return;
+    }
+    wrap_block(out, ctx.depth, ctx.inline_open, '[', ']', |o| {
+        let last = entries.len() - 1;
+        for (n, entry) in entries.iter().enumerate() {
+            match entry {
+                Entry::Item(i) => {
+                    let (_, (kind, item)) = &ctx.children[*i];
+                    push_single_array_item(o, ctx, *i, *kind, item);
+                }
+                Entry::Marker(key, value) => {
+                    o.push_indent(ctx.depth + 1);
+                    push_marker(o, ctx.space, key, value);
+                }
+            }
+            if n < last {
+                o.push_char(',');
+            }
+            o.push_newline();
+        }
+    });
+}
+
+// An array with no kept items keeps its markers on the opener line, as in
+// `[{"$omitted_items": 5}]`.
+fn render_markers_inline(
+    ctx: &ArrayCtx<'_>,
+    entries: &[Entry],
+    out: &mut Out<'_>,
+) {
+    if !ctx.inline_open {
+        out.push_indent(ctx.depth);
+    }
+    out.push_char('[');
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push_char(',');
+            out.push_str(ctx.space);
+        }
+        if let Entry::Marker(key, value) = entry {
+            push_marker(out, ctx.space, key, value);
+        }
+    }
+    out.push_char(']');
+}
+
+// Objects carry omissions as sidecar keys after the kept properties.
+fn object_sidecars(ctx: &ObjectCtx<'_>) -> Vec<(&'static str, String)> {
+    let mut sidecars = Vec::new();
+    if ctx.omitted > 0 {
+        sidecars.push((OMITTED_KEYS_KEY, ctx.omitted.to_string()));
+    }
+    if let Some(note) = ctx.truncation {
+        sidecars.push((TRUNCATED_KEY, crate::utils::json::json_string(note)));
+    }
+    sidecars
+}
+
+// An input key spelled like a marker (`"$truncated"`, `"$$truncated"`, ...)
+// gets one more leading `$`, so markers are never mistaken for data and the
+// original key is recovered by dropping one `$`.
+fn escape_marker_key(quoted: &str) -> Cow<'_, str> {
+    let Some(inner) =
+        quoted.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+    else {
+        return Cow::Borrowed(quoted);
+    };
+    let name = inner.trim_start_matches('$');
+    if name.len() < inner.len() && MARKER_NAMES.contains(&name) {
+        Cow::Owned(format!("\"${inner}\""))
+    } else {
+        Cow::Borrowed(quoted)
+    }
+}
+
+fn render_object_annotated(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
+    let sidecars = object_sidecars(ctx);
+    if ctx.children_len == 0 && sidecars.is_empty() {
+        if !ctx.inline_open {
+            out.push_indent(ctx.depth);
+        }
+        out.push_str("{}");
+        return;
+    }
+    let kept = ctx.children.iter().enumerate().map(|(i, (_, (k, v)))| {
+        (ctx.child_mark(i), escape_marker_key(k), v.as_str())
+    });
+    let extra = sidecars
+        .iter()
+        .map(|(k, v)| (LineMark::default(), Cow::Borrowed(*k), v.as_str()));
+    let last = ctx.children_len + sidecars.len() - 1;
+    wrap_block(out, ctx.depth, ctx.inline_open, '{', '}', |o| {
+        for (n, (mark, key, value)) in kept.chain(extra).enumerate() {
+            o.push_indent_marked(ctx.depth + 1, mark);
+            o.push_key(&key);
+            o.push_char(':');
+            o.push_str(ctx.space);
+            push_value_token(o, value);
+            if n < last {
+                o.push_char(',');
+            }
+            o.push_newline();
+        }
+    });
+}
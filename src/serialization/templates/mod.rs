@@ -1,10 +1,11 @@
 use crate::OutputTemplate;
 use crate::order::NodeKind;
-use crate::serialization::output::Out;
+use crate::serialization::output::{LineMark, Out};
 use std::sync::Arc;
 
 mod code;
 mod core;
+pub(crate) mod gron;
 mod js;
 mod json;
 mod pseudo;
@@ -17,9 +18,18 @@ pub struct ArrayCtx<'a> {
     pub omitted: usize,
     pub depth: usize,
     pub inline_open: bool,
+    pub space: &'a str,
     pub omitted_at_start: bool,
     pub source_hint: Option<&'a str>,
     pub code_highlight: Option<Arc<Vec<String>>>,
+    // Parsed out of a string value; templates mark it as embedded.
+    pub embedded: bool,
+    // Note for the container still open where recovering ingest cut off
+    // malformed input.
+    pub truncation: Option<&'a str>,
+    // Diff side, source line and key path of each child, parallel to
+    // `children` (empty when no gutter or path comment is shown).
+    pub child_marks: Vec<LineMark>,
 }
 
 pub struct ObjectCtx<'a> {
@@ -30,6 +40,21 @@ pub struct ObjectCtx<'a> {
     pub inline_open: bool,
     pub space: &'a str,
     pub fileset_root: bool,
+    pub embedded: bool,
+    pub truncation: Option<&'a str>,
+    pub child_marks: Vec<LineMark>,
+}
+
+impl ArrayCtx<'_> {
+    pub fn child_mark(&self, i: usize) -> LineMark {
+        self.child_marks.get(i).cloned().unwrap_or_default()
+    }
+}
+
+impl ObjectCtx<'_> {
+    pub fn child_mark(&self, i: usize) -> LineMark {
+        self.child_marks.get(i).cloned().unwrap_or_default()
+    }
 }
 
 // Color helpers facade so templates don't pass flags around.
@@ -42,13 +67,15 @@ pub fn render_array(
 ) {
     match template {
         OutputTemplate::Json => json::render_array(ctx, out),
-        OutputTemplate::Auto | OutputTemplate::Pseudo => {
-            pseudo::render_array(ctx, out)
-        }
+        OutputTemplate::Auto
+        | OutputTemplate::Markdown
+        | OutputTemplate::Html
+        | OutputTemplate::Pseudo => pseudo::render_array(ctx, out),
         OutputTemplate::Js => js::render_array(ctx, out),
         OutputTemplate::Yaml => yaml::render_array(ctx, out),
         OutputTemplate::Text => text::render_array(ctx, out),
         OutputTemplate::Code => code::render_array(ctx, out),
+        OutputTemplate::Gron => gron::render_array(ctx, out),
     }
 }
 
@@ -59,12 +86,14 @@ pub fn render_object(
 ) {
     match template {
         OutputTemplate::Json => json::render_object(ctx, out),
-        OutputTemplate::Auto | OutputTemplate::Pseudo => {
-            pseudo::render_object(ctx, out)
-        }
+        OutputTemplate::Auto
+        | OutputTemplate::Markdown
+        | OutputTemplate::Html
+        | OutputTemplate::Pseudo => pseudo::render_object(ctx, out),
         OutputTemplate::Js => js::render_object(ctx, out),
         OutputTemplate::Yaml => yaml::render_object(ctx, out),
         OutputTemplate::Text => text::render_object(ctx, out),
         OutputTemplate::Code => code::render_object(ctx, out),
+        OutputTemplate::Gron => gron::render_object(ctx, out),
     }
 }
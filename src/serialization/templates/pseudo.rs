@@ -1,9 +1,14 @@
 use super::core::{
-    Style, push_array_items_with, push_object_items, wrap_block,
+    Style, push_array_items_with, push_embedded_marker,
+    push_inline_truncation_marker, push_object_items, push_truncation_marker,
+    wrap_block,
 };
 use super::{ArrayCtx, ObjectCtx};
 use crate::serialization::output::Out;
 
+const EMBEDDED: &str = "/* embedded */";
+const TRUNCATED: (&str, &str) = ("/* ", " */");
+
 struct Pseudo;
 
 impl Style for Pseudo {
@@ -46,11 +51,13 @@ fn render_array_empty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
         out.push_omission();
         out.push_str(" ");
     }
+    push_inline_truncation_marker(out, ctx.truncation, " ", ctx.omitted > 0);
     out.push_char(']');
 }
 
 fn render_array_nonempty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
     wrap_block(out, ctx.depth, ctx.inline_open, '[', ']', |o| {
+        push_embedded_marker(o, ctx.depth + 1, ctx.embedded, EMBEDDED);
         if ctx.omitted_at_start {
             <Pseudo as Style>::array_push_omitted(o, ctx);
         }
@@ -58,6 +65,7 @@ fn render_array_nonempty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
         if !ctx.omitted_at_start {
             <Pseudo as Style>::array_push_omitted(o, ctx);
         }
+        push_truncation_marker(o, ctx.depth + 1, ctx.truncation, TRUNCATED);
     });
 }
 
@@ -80,11 +88,19 @@ pub(super) fn render_object(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
             out.push_omission();
             out.push_str(ctx.space);
         }
+        push_inline_truncation_marker(
+            out,
+            ctx.truncation,
+            ctx.space,
+            ctx.omitted > 0,
+        );
         out.push_char('}');
         return;
     }
     wrap_block(out, ctx.depth, ctx.inline_open, '{', '}', |o| {
+        push_embedded_marker(o, ctx.depth + 1, ctx.embedded, EMBEDDED);
         push_object_items(o, ctx);
         <Pseudo as Style>::object_push_omitted(o, ctx);
+        push_truncation_marker(o, ctx.depth + 1, ctx.truncation, TRUNCATED);
     });
 }
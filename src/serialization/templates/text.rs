@@ -8,7 +8,8 @@ fn push_text_omission_line(out: &mut Out<'_>, omitted: usize) {
             out.push_omission();
             out.push_newline();
         }
-        crate::serialization::types::Style::Detailed => {
+        crate::serialization::types::Style::Detailed
+        | crate::serialization::types::Style::Annotated => {
             out.push_omission();
             out.push_str(" ");
             out.push_str(&format!("{omitted} more lines "));
@@ -1,15 +1,20 @@
 use super::ArrayCtx;
 use super::ObjectCtx;
-use crate::serialization::output::Out;
+use crate::serialization::output::{LineMark, Out};
 use serde_json;
 
 fn has_newline(s: &str) -> bool {
     s.as_bytes().contains(&b'\n') || s.contains('\r')
 }
 
-fn push_yaml_array_item(out: &mut Out<'_>, depth: usize, item: &str) {
+fn push_yaml_array_item(
+    out: &mut Out<'_>,
+    depth: usize,
+    mark: LineMark,
+    item: &str,
+) {
     if !has_newline(item) {
-        out.push_indent(depth);
+        out.push_indent_marked(depth, mark);
         out.push_str("- ");
         push_yaml_scalar(out, item.trim());
         out.push_newline();
@@ -17,18 +22,19 @@ fn push_yaml_array_item(out: &mut Out<'_>, depth: usize, item: &str) {
     }
     // Multi-line item: print first logical line after "- ", and align
     // all following lines under the first character after the dash.
-    let mut iter = item.lines();
-    if let Some(first) = iter.next() {
-        out.push_indent(depth);
-        out.push_str("- ");
-        out.push_str(first.trim_start());
+    for (i, line) in item.lines().enumerate() {
+        push_child_line(out, depth, line);
+        out.push_str(if i == 0 { "- " } else { "  " });
+        out.push_str(out.split_gutter(line).1.trim_start());
         out.push_newline();
     }
-    for rest in iter {
-        out.push_indent(depth);
-        out.push_str("  ");
-        out.push_str(rest.trim_start());
-        out.push_newline();
+}
+
+// Re-indent a line rendered by a child, keeping its diff gutter in front.
+fn push_child_line(out: &mut Out<'_>, depth: usize, line: &str) {
+    match out.split_gutter(line).0 {
+        "" => out.push_indent(depth),
+        gutter => out.push_indent_after_gutter(gutter, depth),
     }
 }
 
@@ -54,7 +60,8 @@ fn push_array_omitted_start(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
                 out.push_comment("# …");
                 out.push_newline();
             }
-            crate::serialization::types::Style::Detailed => {
+            crate::serialization::types::Style::Detailed
+            | crate::serialization::types::Style::Annotated => {
                 out.push_indent(ctx.depth);
                 out.push_comment(format!("# {} more items", ctx.omitted));
                 out.push_newline();
@@ -72,7 +79,8 @@ fn push_array_omitted_end(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
                 out.push_comment("# …");
                 out.push_newline();
             }
-            crate::serialization::types::Style::Detailed => {
+            crate::serialization::types::Style::Detailed
+            | crate::serialization::types::Style::Annotated => {
                 out.push_indent(ctx.depth);
                 out.push_comment(format!("# {} more items", ctx.omitted));
                 out.push_newline();
@@ -81,12 +89,30 @@ fn push_array_omitted_end(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
     }
 }
 
+fn push_embedded_comment(out: &mut Out<'_>, depth: usize, embedded: bool) {
+    if !matches!(out.style(), crate::serialization::types::Style::Strict) {
+        super::core::push_embedded_marker(out, depth, embedded, "# embedded");
+    }
+}
+
+fn push_truncation_comment(
+    out: &mut Out<'_>,
+    depth: usize,
+    note: Option<&str>,
+) {
+    if !matches!(out.style(), crate::serialization::types::Style::Strict) {
+        super::core::push_truncation_marker(out, depth, note, ("# ", ""));
+    }
+}
+
 fn render_array_pretty(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
+    push_embedded_comment(out, ctx.depth, ctx.embedded);
     push_array_omitted_start(ctx, out);
-    for (_, (_, item)) in ctx.children.iter() {
-        push_yaml_array_item(out, ctx.depth, item);
+    for (i, (_, (_, item))) in ctx.children.iter().enumerate() {
+        push_yaml_array_item(out, ctx.depth, ctx.child_mark(i), item);
     }
     push_array_omitted_end(ctx, out);
+    push_truncation_comment(out, ctx.depth, ctx.truncation);
 }
 
 pub(super) fn render_array(ctx: &ArrayCtx<'_>, out: &mut Out<'_>) {
@@ -144,8 +170,14 @@ fn yaml_key_text_from_json_quoted(k: &str) -> String {
     k.to_string()
 }
 
-fn push_object_kv(out: &mut Out<'_>, depth: usize, key_text: &str, v: &str) {
-    out.push_indent(depth);
+fn push_object_kv(
+    out: &mut Out<'_>,
+    depth: usize,
+    mark: LineMark,
+    key_text: &str,
+    v: &str,
+) {
+    out.push_indent_marked(depth, mark);
     out.push_key(key_text);
     if !has_newline(v) {
         out.push_str(": ");
@@ -231,7 +263,8 @@ fn push_object_omitted(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
                 out.push_comment("# …");
                 out.push_newline();
             }
-            crate::serialization::types::Style::Detailed => {
+            crate::serialization::types::Style::Detailed
+            | crate::serialization::types::Style::Annotated => {
                 out.push_indent(ctx.depth);
                 let label = if ctx.fileset_root {
                     "files"
@@ -246,11 +279,13 @@ fn push_object_omitted(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
 }
 
 fn render_object_pretty(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
-    for (_, (k, v)) in ctx.children.iter() {
+    push_embedded_comment(out, ctx.depth, ctx.embedded);
+    for (i, (_, (k, v))) in ctx.children.iter().enumerate() {
         let key_text = yaml_key_text_from_json_quoted(k);
-        push_object_kv(out, ctx.depth, &key_text, v);
+        push_object_kv(out, ctx.depth, ctx.child_mark(i), &key_text, v);
     }
     push_object_omitted(ctx, out);
+    push_truncation_comment(out, ctx.depth, ctx.truncation);
 }
 
 pub(super) fn render_object(ctx: &ObjectCtx<'_>, out: &mut Out<'_>) {
@@ -35,6 +35,7 @@ fn render_cfg(
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..crate::RenderConfig::default()
     }
 }
 
@@ -112,6 +113,7 @@ fn array_omitted_markers_pseudo_head_and_tail() {
         array_bias: crate::ArrayBias::HeadMidTail,
         array_sampler: crate::ArraySamplerStrategy::Default,
         line_budget_only: false,
+        ..crate::PriorityConfig::new(usize::MAX, usize::MAX)
     };
     let arena = crate::ingest::formats::json::build_json_tree_arena(
         "[1,2,3]", &cfg_prio,
@@ -149,6 +151,7 @@ fn array_omitted_markers_js_head_and_tail() {
         array_bias: crate::ArrayBias::HeadMidTail,
         array_sampler: crate::ArraySamplerStrategy::Default,
         line_budget_only: false,
+        ..crate::PriorityConfig::new(usize::MAX, usize::MAX)
     };
     let arena = crate::ingest::formats::json::build_json_tree_arena(
         "[1,2,3]", &cfg_prio,
@@ -186,6 +189,7 @@ fn array_omitted_markers_yaml_head_and_tail() {
         array_bias: crate::ArrayBias::HeadMidTail,
         array_sampler: crate::ArraySamplerStrategy::Default,
         line_budget_only: false,
+        ..crate::PriorityConfig::new(usize::MAX, usize::MAX)
     };
     let arena = crate::ingest::formats::json::build_json_tree_arena(
         "[1,2,3]", &cfg_prio,
@@ -289,9 +293,13 @@ fn mk_gap_ctx() -> super::templates::ArrayCtx<'static> {
         omitted: 0,
         depth: 0,
         inline_open: false,
+        space: " ",
         omitted_at_start: false,
         source_hint: None,
         code_highlight: None,
+        embedded: false,
+        truncation: None,
+        child_marks: Vec::new(),
     }
 }
 
@@ -521,7 +529,12 @@ fn force_child_hooks_removed() {
         total_nodes: 3,
         object_type: vec![ObjectType::Object; 3],
         code_lines: HashMap::new(),
+        truncations: HashMap::new(),
         fileset_children: None,
+        fileset_weights: None,
+        fileset_sizes: None,
+        fileset_kinds: None,
+        input_kind: None,
     };
     let mut flags = Vec::new();
     let render_id = 1u32;
@@ -593,6 +606,7 @@ fn fileset_tree_headers_free_keep_slot_stats_on_body_only() {
         fileset_tree: true,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..crate::RenderConfig::default()
     };
 
     let (rendered, slot_stats) = render_from_render_set_with_slots(
@@ -674,6 +688,7 @@ fn fileset_tree_headers_free_scaffold_does_not_change_slot_stats() {
         fileset_tree: true,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..crate::RenderConfig::default()
     };
 
     let render_with_scaffold = |show_headers: bool| {
@@ -764,6 +779,7 @@ fn fileset_sections_slot_stats_respect_header_budgeting() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..crate::RenderConfig::default()
     };
 
     let render_sections = |count_headers: bool| {
@@ -856,6 +872,7 @@ fn slot_stats_match_render_for_code_and_text() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: true,
         grep_highlight: None,
+        ..crate::RenderConfig::default()
     };
 
     let render_with =
@@ -901,3 +918,24 @@ fn slot_stats_match_render_for_code_and_text() {
         crate::utils::measure::count_output_stats(&text_render, true);
     assert_eq!(text_stats, text_totals);
 }
+
+#[test]
+fn array_internal_gaps_annotated_json() {
+    let mut ctx = mk_gap_ctx();
+    ctx.omitted = 4;
+    let mut s = String::new();
+    let cfg = render_cfg(
+        crate::OutputTemplate::Json,
+        crate::serialization::types::Style::Annotated,
+    );
+    let mut outw = crate::serialization::output::Out::new(&mut s, &cfg, None);
+    super::templates::render_array(
+        crate::OutputTemplate::Json,
+        &ctx,
+        &mut outw,
+    );
+    assert_eq!(
+        s,
+        "[\n  1,\n  {\"$omitted_items\": 2},\n  2,\n  {\"$omitted_items\": 1},\n  3,\n  {\"$omitted_items\": 1}\n]"
+    );
+}
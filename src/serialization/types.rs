@@ -7,6 +7,17 @@ pub enum OutputTemplate {
     Yaml,
     Text,
     Code,
+    /// Each input in a fenced code block (the template `Auto` would pick
+    /// inside), with headings for fileset sections and a nested list for
+    /// the tree view.
+    Markdown,
+    /// A self-contained HTML page: collapsible objects and arrays, themed
+    /// code and anchored fileset sections. Budgets measure the text the
+    /// page shows (as `Auto` renders it), not the markup.
+    Html,
+    /// Flattened `$.path.to[3] = value` lines, one per kept leaf and
+    /// container, with `[…]`/`{…}` lines where values were omitted.
+    Gron,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -14,6 +25,10 @@ pub enum Style {
     Strict,
     Default,
     Detailed,
+    /// Strict JSON that carries omissions as data (`{"$omitted_items": N}`
+    /// elements, `"$omitted_keys"` keys, string suffixes). Other formats
+    /// render as `Detailed`.
+    Annotated,
 }
 
 #[derive(Clone, Debug)]
@@ -47,10 +62,21 @@ pub struct RenderConfig {
     pub show_fileset_headers: bool,
     // When true, render filesets as a directory tree with inline previews.
     pub fileset_tree: bool,
+    // When true, tree entries are annotated with file sizes and line/element
+    // counts (directories with aggregated totals).
+    pub fileset_tree_stats: bool,
     // When true, fileset headers and summaries count toward line budgets.
     pub count_fileset_headers_in_budgets: bool,
     // Optional regex for highlighting grep matches during rendering (color modes only).
     pub grep_highlight: Option<regex::Regex>,
+    // Grep or `--where` is filtering: colors follow `grep_color` even
+    // without a regex to highlight (predicate hits are marked per node).
+    pub grep_active: bool,
+    // How grep highlights combine with syntax colors when color is enabled.
+    pub grep_color: GrepColor,
+    // When true, JSON/YAML lines end with a comment naming the path of the
+    // value they start (`// $.items[3].name`).
+    pub key_paths: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -65,23 +91,80 @@ pub enum ColorStrategy {
     None,
     Syntax,
     HighlightOnly,
+    // Syntax colors with grep matches layered on top (inverse video).
+    Combined,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum GrepColor {
+    // Suppress syntax colors; only grep matches are colored.
+    #[default]
+    HighlightOnly,
+    // Keep syntax colors and mark grep matches in inverse video.
+    Combined,
+}
+
+/// Pretty-printed pseudo output with no color, fileset headers shown and no
+/// grep or annotation features; callers override the fields they need.
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            template: OutputTemplate::Pseudo,
+            indent_unit: "  ".to_string(),
+            space: " ".to_string(),
+            newline: "\n".to_string(),
+            prefer_tail_arrays: false,
+            color_mode: ColorMode::Off,
+            color_enabled: false,
+            style: Style::Default,
+            string_free_prefix_graphemes: None,
+            debug: false,
+            primary_source_name: None,
+            show_fileset_headers: true,
+            fileset_tree: false,
+            fileset_tree_stats: false,
+            count_fileset_headers_in_budgets: false,
+            grep_highlight: None,
+            grep_active: false,
+            grep_color: GrepColor::default(),
+            key_paths: false,
+        }
+    }
 }
 
 impl RenderConfig {
+    /// Whether fileset headers (section titles, tree scaffolding) count
+    /// toward budgets. Markdown always charges them, along with fences.
+    pub(crate) fn charges_fileset_headers(&self) -> bool {
+        self.count_fileset_headers_in_budgets
+            || matches!(self.template, OutputTemplate::Markdown)
+    }
+
     /// Derive the effective color strategy for this render configuration.
     /// Syntax colors apply when color is enabled and no grep highlighting is active.
-    /// Highlight-only applies when color is enabled and a grep highlight regex is present.
+    /// With a grep highlight regex, `grep_color` picks highlight-only or
+    /// combined (syntax colors plus inverse-video matches).
     pub fn color_strategy(&self) -> ColorStrategy {
         if !self.color_enabled {
             ColorStrategy::None
-        } else if self.grep_highlight.is_some() {
-            ColorStrategy::HighlightOnly
-        } else {
+        } else if self.grep_highlight.is_none() && !self.grep_active {
             ColorStrategy::Syntax
+        } else {
+            match self.grep_color {
+                GrepColor::HighlightOnly => ColorStrategy::HighlightOnly,
+                GrepColor::Combined => ColorStrategy::Combined,
+            }
         }
     }
 }
 
+impl ColorStrategy {
+    /// Whether syntax/role colors are emitted under this strategy.
+    pub fn syntax_colors(self) -> bool {
+        matches!(self, ColorStrategy::Syntax | ColorStrategy::Combined)
+    }
+}
+
 impl ColorMode {
     // Returns whether coloring should be enabled given whether stdout is a TTY.
     pub fn effective(self, stdout_is_terminal: bool) -> bool {
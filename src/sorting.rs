@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::env;
 use std::ffi::OsString;
@@ -8,7 +8,21 @@ use std::time::UNIX_EPOCH;
 
 use git2::Repository;
 
-const FRECENFILE_TOP_N: usize = 100;
+pub(crate) const FRECENFILE_TOP_N: usize = 100;
+// History depth scanned for `--sort git-churn`; older commits are ignored so
+// large repositories stay responsive.
+const CHURN_MAX_COMMITS: usize = 5000;
+
+/// Fileset orderings, most relevant first.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum SortKey {
+    Frecency,
+    Mtime,
+    Path,
+    Size,
+    GitChurn,
+    GrepMatches,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct FrecencyContext {
@@ -30,22 +44,138 @@ struct PathOrderEntry {
     modified: Option<u64>,
 }
 
-pub(crate) fn sort_paths_for_fileset(paths: &[PathBuf]) -> Vec<PathBuf> {
+/// Order fileset inputs by `sort`, most relevant first (least relevant first
+/// with `reverse`). Ties keep input order either way. `GrepMatches` needs
+/// file contents, so it leaves paths alone here and is applied at render
+/// time.
+pub(crate) fn sort_paths_for_fileset(
+    paths: &[PathBuf],
+    sort: SortKey,
+    reverse: bool,
+) -> Vec<PathBuf> {
     if paths.len() <= 1 {
         return paths.to_vec();
     }
+    match sort {
+        SortKey::Frecency => {
+            let Ok(cwd) = env::current_dir() else {
+                return paths.to_vec();
+            };
+            let canonical_cwd = cwd.canonicalize().unwrap_or(cwd);
+            let frecency = build_frecency_context(&canonical_cwd);
+            sort_paths_with_context(
+                paths,
+                &canonical_cwd,
+                frecency.as_ref(),
+                reverse,
+            )
+        }
+        SortKey::Mtime => stable_sort_by_key(paths, reverse, |p| {
+            Reverse(path_modified_timestamp(p))
+        }),
+        SortKey::Path => stable_sort_by_key(paths, reverse, Path::to_path_buf),
+        SortKey::Size => stable_sort_by_key(paths, reverse, |p| {
+            Reverse(std::fs::metadata(p).map_or(0, |m| m.len()))
+        }),
+        SortKey::GitChurn => sort_paths_by_churn(paths, reverse),
+        SortKey::GrepMatches => paths.to_vec(),
+    }
+}
+
+/// Frecency rank (0 = most frequent/recent) of each path, when it is among
+/// the top files of the surrounding git repository.
+pub(crate) fn frecency_ranks(paths: &[&Path]) -> Vec<Option<usize>> {
+    let context = env::current_dir().ok().and_then(|cwd| {
+        let canonical_cwd = cwd.canonicalize().unwrap_or(cwd);
+        build_frecency_context(&canonical_cwd).map(|ctx| (canonical_cwd, ctx))
+    });
+    paths
+        .iter()
+        .map(|path| {
+            let (cwd, ctx) = context.as_ref()?;
+            relative_path_in_repo(path, cwd, &ctx.repo_root)
+                .and_then(|rel| ctx.rank_for(&rel))
+        })
+        .collect()
+}
+
+// A stable sort on the key, or on the reversed key, keeps ties in input
+// order in both directions.
+fn stable_sort_by_key<K: Ord>(
+    paths: &[PathBuf],
+    reverse: bool,
+    key: impl Fn(&Path) -> K,
+) -> Vec<PathBuf> {
+    let mut keyed: Vec<(K, &PathBuf)> =
+        paths.iter().map(|p| (key(p), p)).collect();
+    keyed.sort_by(|a, b| directed(a.0.cmp(&b.0), reverse));
+    keyed.into_iter().map(|(_, p)| p.clone()).collect()
+}
+
+fn directed(ord: Ordering, reverse: bool) -> Ordering {
+    if reverse { ord.reverse() } else { ord }
+}
+
+fn sort_paths_by_churn(paths: &[PathBuf], reverse: bool) -> Vec<PathBuf> {
     let Ok(cwd) = env::current_dir() else {
         return paths.to_vec();
     };
     let canonical_cwd = cwd.canonicalize().unwrap_or(cwd);
-    let frecency = build_frecency_context(&canonical_cwd);
-    sort_paths_with_context(paths, &canonical_cwd, frecency.as_ref())
+    let Some((repo_root, counts)) = load_churn_counts(&canonical_cwd) else {
+        return paths.to_vec();
+    };
+    stable_sort_by_key(paths, reverse, |p| {
+        let count = relative_path_in_repo(p, &canonical_cwd, &repo_root)
+            .and_then(|rel| counts.get(&rel).copied())
+            .unwrap_or(0);
+        Reverse(count)
+    })
+}
+
+// Number of commits touching each repository path, over the most recent
+// `CHURN_MAX_COMMITS` commits reachable from HEAD.
+fn load_churn_counts(
+    cwd: &Path,
+) -> Option<(PathBuf, HashMap<PathBuf, usize>)> {
+    let repo = Repository::discover(cwd).ok()?;
+    let workdir = repo.workdir()?;
+    let root = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    let mut walk = repo.revwalk().ok()?;
+    walk.push_head().ok()?;
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for oid in walk.filter_map(Result::ok).take(CHURN_MAX_COMMITS) {
+        count_commit_paths(&repo, oid, &mut counts);
+    }
+    Some((root, counts))
+}
+
+fn count_commit_paths(
+    repo: &Repository,
+    oid: git2::Oid,
+    counts: &mut HashMap<PathBuf, usize>,
+) {
+    let Ok(commit) = repo.find_commit(oid) else {
+        return;
+    };
+    let tree = commit.tree().ok();
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let Ok(diff) =
+        repo.diff_tree_to_tree(parent_tree.as_ref(), tree.as_ref(), None)
+    else {
+        return;
+    };
+    for path in diff.deltas().filter_map(|d| d.new_file().path()) {
+        *counts.entry(path.to_path_buf()).or_default() += 1;
+    }
 }
 
 pub(crate) fn sort_paths_with_context(
     paths: &[PathBuf],
     cwd: &Path,
     frecency: Option<&FrecencyContext>,
+    reverse: bool,
 ) -> Vec<PathBuf> {
     let mut entries: Vec<PathOrderEntry> = paths
         .iter()
@@ -65,7 +195,9 @@ pub(crate) fn sort_paths_with_context(
         })
         .collect();
 
-    entries.sort_by(compare_path_order);
+    entries.sort_by(|a, b| {
+        directed(compare_path_order(a, b), reverse).then(a.idx.cmp(&b.idx))
+    });
     if std::env::var_os("HEADSON_FRECEN_TRACE").is_some() {
         eprintln!(
             "frecen-sort input={:?} ranks={:?}",
@@ -111,7 +243,7 @@ fn compare_path_order(a: &PathOrderEntry, b: &PathOrderEntry) -> Ordering {
         (None, Some(_)) => return Ordering::Greater,
         (None, None) => {}
     }
-    a.idx.cmp(&b.idx)
+    Ordering::Equal
 }
 
 pub(crate) fn build_frecency_context(cwd: &Path) -> Option<FrecencyContext> {
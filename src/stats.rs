@@ -0,0 +1,221 @@
+use serde::Serialize;
+
+use crate::order::{ObjectType, PriorityOrder, ROOT_PQ_ID, RankedNode};
+use crate::{Budget, BudgetKind, Budgets, FilesetInputKind, RenderConfig};
+
+/// Size and coverage of a render: what the output measures, which budgets
+/// applied and stopped it, and how much of each input it shows.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RenderStats {
+    /// Effective output template ("auto" for filesets).
+    pub template: &'static str,
+    /// Size of the rendered text (color codes included).
+    pub bytes: usize,
+    pub chars: usize,
+    pub lines: usize,
+    pub budgets: Vec<BudgetStats>,
+    /// Budgets that stopped the selection from growing, e.g. `bytes` or
+    /// `per-file lines`. Empty when everything fit.
+    pub binding: Vec<&'static str>,
+    /// Nodes (values, keys with values, lines) in the input, counting array
+    /// items sampled away at ingest.
+    pub nodes_total: usize,
+    pub nodes_shown: usize,
+    pub omitted: usize,
+    /// Per-file coverage in multi-file mode, in render order.
+    pub files: Vec<FileStats>,
+}
+
+/// A budget that applied to the render.
+#[derive(Clone, Debug, Serialize)]
+pub struct BudgetStats {
+    /// `global` or `per-file`.
+    pub scope: &'static str,
+    pub kind: &'static str,
+    pub cap: usize,
+}
+
+/// Coverage of one fileset entry.
+#[derive(Clone, Debug, Serialize)]
+pub struct FileStats {
+    pub name: String,
+    /// How the file was ingested: `json`, `yaml`, `text` or `code`.
+    pub format: Option<&'static str>,
+    /// Input size in bytes.
+    pub size: Option<usize>,
+    /// The file made it into the render (at least its header).
+    pub shown: bool,
+    pub nodes_total: usize,
+    pub nodes_shown: usize,
+    pub omitted: usize,
+}
+
+pub(crate) struct StatsArgs<'a> {
+    pub order: &'a PriorityOrder,
+    pub inclusion_flags: &'a [u32],
+    pub render_id: u32,
+    pub config: &'a RenderConfig,
+    pub budgets: Budgets,
+    pub binding: Vec<&'static str>,
+    pub text: &'a str,
+}
+
+pub(crate) fn render_stats(args: StatsArgs<'_>) -> RenderStats {
+    let StatsArgs {
+        order,
+        inclusion_flags,
+        render_id,
+        config,
+        budgets,
+        binding,
+        text,
+    } = args;
+    let coverage = Coverage {
+        order,
+        inclusion_flags,
+        render_id,
+    };
+    let files = file_stats(&coverage);
+    // A fileset root is only scaffolding; count the files instead.
+    let (nodes_total, nodes_shown) = if files.is_empty() {
+        coverage.count(ROOT_PQ_ID)
+    } else {
+        files.iter().fold((0, 0), |(total, shown), file| {
+            (total + file.nodes_total, shown + file.nodes_shown)
+        })
+    };
+    let output = crate::utils::measure::count_output_stats(text, true);
+    RenderStats {
+        template: crate::debug::template_str_for_root(order, config),
+        bytes: output.bytes,
+        chars: output.chars,
+        lines: output.lines,
+        budgets: budget_stats(budgets),
+        binding,
+        nodes_total,
+        nodes_shown,
+        omitted: nodes_total - nodes_shown,
+        files,
+    }
+}
+
+/// Stats for a run that skipped budgeted selection (empty input, no grep
+/// matches, `--grep-show names`): the template and budgets that applied and
+/// the size of `text`.
+pub(crate) fn unselected_render_stats(
+    order: &PriorityOrder,
+    config: &RenderConfig,
+    budgets: Budgets,
+    text: &str,
+) -> RenderStats {
+    let output = crate::utils::measure::count_output_stats(text, true);
+    RenderStats {
+        template: crate::debug::template_str_for_root(order, config),
+        bytes: output.bytes,
+        chars: output.chars,
+        lines: output.lines,
+        budgets: budget_stats(budgets),
+        ..RenderStats::default()
+    }
+}
+
+fn budget_stats(budgets: Budgets) -> Vec<BudgetStats> {
+    let entry = |scope, budget: Budget| BudgetStats {
+        scope,
+        kind: match budget.kind {
+            BudgetKind::Bytes => "bytes",
+            BudgetKind::Chars => "chars",
+            BudgetKind::Lines => "lines",
+        },
+        cap: budget.cap,
+    };
+    budgets
+        .global
+        .map(|b| entry("global", b))
+        .into_iter()
+        .chain(budgets.per_slot.map(|b| entry("per-file", b)))
+        .collect()
+}
+
+fn file_stats(coverage: &Coverage<'_>) -> Vec<FileStats> {
+    let order = coverage.order;
+    if order.object_type.get(ROOT_PQ_ID) != Some(&ObjectType::Fileset) {
+        return Vec::new();
+    }
+    let files = order
+        .fileset_children
+        .as_deref()
+        .unwrap_or(&order.children[ROOT_PQ_ID]);
+    files
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            let (nodes_total, nodes_shown) = coverage.count(id.0);
+            FileStats {
+                name: order.nodes[id.0]
+                    .key_in_object()
+                    .unwrap_or_default()
+                    .to_string(),
+                format: order
+                    .fileset_kinds
+                    .as_ref()
+                    .and_then(|kinds| kinds.get(idx))
+                    .map(|kind| kind_str(*kind)),
+                size: order
+                    .fileset_sizes
+                    .as_ref()
+                    .and_then(|sizes| sizes.get(idx))
+                    .copied(),
+                shown: coverage.shown(id.0),
+                nodes_total,
+                nodes_shown,
+                omitted: nodes_total - nodes_shown,
+            }
+        })
+        .collect()
+}
+
+fn kind_str(kind: FilesetInputKind) -> &'static str {
+    match kind {
+        FilesetInputKind::Json => "json",
+        FilesetInputKind::Yaml => "yaml",
+        FilesetInputKind::Text {
+            atomic_lines: false,
+        } => "text",
+        FilesetInputKind::Text { atomic_lines: true } => "code",
+    }
+}
+
+struct Coverage<'a> {
+    order: &'a PriorityOrder,
+    inclusion_flags: &'a [u32],
+    render_id: u32,
+}
+
+impl Coverage<'_> {
+    fn shown(&self, id: usize) -> bool {
+        self.inclusion_flags.get(id) == Some(&self.render_id)
+    }
+
+    // (total, shown) node counts for the subtree at `id`. String fragments
+    // are part of their string; array items dropped at ingest count as
+    // omitted.
+    fn count(&self, root: usize) -> (usize, usize) {
+        let mut total = 0;
+        let mut shown = 0;
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if matches!(self.order.nodes[id], RankedNode::LeafPart { .. }) {
+                continue;
+            }
+            let children = &self.order.children[id];
+            let sampled_away = self.order.metrics[id]
+                .array_len
+                .map_or(0, |len| len.saturating_sub(children.len()));
+            total += 1 + sampled_away;
+            shown += usize::from(self.shown(id));
+            stack.extend(children.iter().map(|child| child.0));
+        }
+        (total, shown)
+    }
+}
@@ -14,7 +14,30 @@ const CODE_EXTS: &[&str] = &[
     "markdown", "mdown", "mkdn", "mkd", "mdwn", "mdtext",
 ];
 
+// Well-known extensionless (or misleadingly suffixed) source files.
+const CODE_FILENAMES: &[&str] = &[
+    "Dockerfile",
+    "Containerfile",
+    "Makefile",
+    "GNUmakefile",
+    "Jenkinsfile",
+    "Vagrantfile",
+    "Gemfile",
+    "Rakefile",
+    "Podfile",
+    "Brewfile",
+    "Justfile",
+    "CMakeLists.txt",
+];
+
 pub fn is_code_like_name(name: &str) -> bool {
+    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    if CODE_FILENAMES
+        .iter()
+        .any(|known| file_name.eq_ignore_ascii_case(known))
+    {
+        return true;
+    }
     let lower_ext = name.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase());
     match lower_ext.as_deref() {
         Some(ext) => CODE_EXTS.contains(&ext),
@@ -37,4 +60,12 @@ mod tests {
         assert!(!is_code_like_name("notes.txt"));
         assert!(!is_code_like_name("no_extension"));
     }
+
+    #[test]
+    fn recognizes_well_known_code_filenames() {
+        assert!(is_code_like_name("ci/Jenkinsfile"));
+        assert!(is_code_like_name("makefile"));
+        assert!(is_code_like_name("CMakeLists.txt"));
+        assert!(!is_code_like_name("Dockerfile.txt"));
+    }
 }
@@ -2,6 +2,7 @@ pub mod extensions;
 pub(crate) mod graph;
 pub(crate) mod json;
 pub(crate) mod measure;
+pub(crate) mod path;
 pub mod templates;
 pub(crate) mod text;
 pub(crate) mod tree_arena;
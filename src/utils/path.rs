@@ -0,0 +1,150 @@
+use crate::order::{NodeId, ObjectType, PriorityOrder, ROOT_PQ_ID};
+
+/// Location of a node: the fileset entry it belongs to (if any) and its
+/// key path within that entry, e.g. `$.items[3].name`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct NodePath {
+    pub file: Option<String>,
+    pub path: String,
+}
+
+/// One step from a parent to a child: an object key or an array index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn root_is_fileset(order: &PriorityOrder) -> bool {
+    order.object_type.get(ROOT_PQ_ID) == Some(&ObjectType::Fileset)
+}
+
+fn segment_for<'a>(order: &'a PriorityOrder, id: usize) -> Segment<'a> {
+    if let Some(key) = order.nodes[id].key_in_object() {
+        return Segment::Key(key);
+    }
+    let index = order
+        .index_in_parent_array
+        .get(id)
+        .copied()
+        .flatten()
+        .unwrap_or_else(|| position_in_parent(order, id));
+    Segment::Index(index)
+}
+
+fn position_in_parent(order: &PriorityOrder, id: usize) -> usize {
+    order
+        .parent
+        .get(id)
+        .copied()
+        .flatten()
+        .and_then(|p| order.children.get(p.0))
+        .and_then(|kids| kids.iter().position(|k| k.0 == id))
+        .unwrap_or(0)
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn push_segment(out: &mut String, segment: &Segment<'_>) {
+    match segment {
+        Segment::Key(key) if is_identifier(key) => {
+            out.push('.');
+            out.push_str(key);
+        }
+        Segment::Key(key) => {
+            out.push('[');
+            out.push_str(&crate::utils::json::json_string(key));
+            out.push(']');
+        }
+        Segment::Index(idx) => {
+            out.push('[');
+            out.push_str(&idx.to_string());
+            out.push(']');
+        }
+    }
+}
+
+/// Walk parent links from `id` up to the root and return the fileset entry
+/// name (if any) plus the steps below it, outermost first.
+pub(crate) fn node_segments(
+    order: &PriorityOrder,
+    id: usize,
+) -> (Option<&str>, Vec<Segment<'_>>) {
+    let mut chain: Vec<usize> = Vec::new();
+    let mut cursor = Some(NodeId(id));
+    while let Some(node) = cursor {
+        if node.0 == ROOT_PQ_ID {
+            break;
+        }
+        chain.push(node.0);
+        cursor = order.parent.get(node.0).copied().flatten();
+    }
+    chain.reverse();
+    let mut file = None;
+    if root_is_fileset(order) && !chain.is_empty() {
+        let file_root = chain.remove(0);
+        file = order.nodes[file_root].key_in_object();
+    }
+    let segments = chain.into_iter().map(|n| segment_for(order, n)).collect();
+    (file, segments)
+}
+
+/// Build the key path of `id`, e.g. `$.items[3].name`.
+/// For filesets, the top-level key becomes `file` and is not part of `path`.
+pub(crate) fn node_path(order: &PriorityOrder, id: usize) -> NodePath {
+    let (file, segments) = node_segments(order, id);
+    let mut path = String::from("$");
+    for segment in &segments {
+        push_segment(&mut path, segment);
+    }
+    NodePath {
+        file: file.map(str::to_string),
+        path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::build_order;
+    use crate::{PriorityConfig, ingest};
+
+    fn order_for(json: &str) -> PriorityOrder {
+        let cfg = PriorityConfig::new(usize::MAX, usize::MAX);
+        let arena =
+            ingest::parse_json_one(json.as_bytes().to_vec(), &cfg).unwrap();
+        build_order(&arena, &cfg).unwrap()
+    }
+
+    fn find_atomic(order: &PriorityOrder, token: &str) -> usize {
+        order
+            .nodes
+            .iter()
+            .position(|n| {
+                matches!(n, crate::RankedNode::AtomicLeaf { token: t, .. } if t == token)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn builds_dotted_and_indexed_paths() {
+        let order = order_for(r#"{"items":[{"a":1},{"a":2}]}"#);
+        let id = find_atomic(&order, "2");
+        let path = node_path(&order, id);
+        assert_eq!(path.file, None);
+        assert_eq!(path.path, "$.items[1].a");
+    }
+
+    #[test]
+    fn quotes_non_identifier_keys() {
+        let order = order_for(r#"{"a b":{"c-d":true}}"#);
+        let id = find_atomic(&order, "true");
+        assert_eq!(node_path(&order, id).path, r#"$["a b"]["c-d"]"#);
+    }
+}
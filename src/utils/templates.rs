@@ -1,10 +1,26 @@
+use crate::FilesetInputKind;
 use crate::serialization::types::{OutputTemplate, Style};
 
 /// Map a style to its corresponding JSON-family output template.
 pub fn map_json_template_for_style(style: Style) -> OutputTemplate {
     match style {
-        Style::Strict => OutputTemplate::Json,
+        Style::Strict | Style::Annotated => OutputTemplate::Json,
         Style::Default => OutputTemplate::Pseudo,
         Style::Detailed => OutputTemplate::Js,
     }
 }
+
+/// Template `Auto` picks for an input ingested as `kind`.
+pub(crate) fn template_for_input_kind(
+    kind: FilesetInputKind,
+    style: Style,
+) -> OutputTemplate {
+    match kind {
+        FilesetInputKind::Json => map_json_template_for_style(style),
+        FilesetInputKind::Yaml => OutputTemplate::Yaml,
+        FilesetInputKind::Text { atomic_lines: true } => OutputTemplate::Code,
+        FilesetInputKind::Text {
+            atomic_lines: false,
+        } => OutputTemplate::Text,
+    }
+}
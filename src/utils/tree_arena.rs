@@ -1,4 +1,4 @@
-use crate::{ArrayBias, order::NodeKind};
+use crate::{ArrayBias, diff::DiffMark, order::NodeKind};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -17,6 +17,26 @@ pub struct JsonTreeArena {
     // Optional full text lines for arrays (by arena node id) to support
     // downstream features like syntax highlighting even after sampling.
     pub code_lines: HashMap<usize, Arc<Vec<String>>>,
+    // Containers (by arena node id) still open where recovering ingest cut
+    // off malformed input, with a note on where and why.
+    pub truncations: HashMap<usize, String>,
+    // Fileset only: share of a global budget for each file, parallel to the
+    // root's children. Empty means an equal share.
+    pub fileset_weights: Vec<f64>,
+    // Fileset only: input size in bytes of each file, parallel to the root's
+    // children.
+    pub fileset_sizes: Vec<usize>,
+    // Fileset only: how each file was ingested, parallel to the root's
+    // children; rendering picks a matching template.
+    pub fileset_kinds: Vec<crate::FilesetInputKind>,
+    // Fileset only: files that fell back to text ingest because they did not
+    // parse as their format.
+    pub fileset_parse_failures: Vec<crate::FilesetParseFailure>,
+    // Fileset only: files recovered from malformed JSON, as
+    // "name: truncation note".
+    pub fileset_recovered: Vec<String>,
+    // Single input only: how it was ingested.
+    pub input_kind: Option<crate::FilesetInputKind>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +57,15 @@ pub struct JsonTreeNode {
     pub arr_indices_len: usize,
     pub array_bias_override: Option<ArrayBias>,
     pub prefers_parent_line: bool,
+    // True for containers parsed out of a string value (see
+    // `ingest::embedded`); rendered with an "embedded" marker.
+    pub embedded: bool,
+    // Side of a structural diff (`crate::diff`); None outside diffs and for
+    // shared ancestors.
+    pub diff: Option<DiffMark>,
+    // 1-based line where the value starts in its input; only recorded when
+    // `PriorityConfig::source_lines` is set.
+    pub source_line: Option<usize>,
 }
 
 impl Default for JsonTreeNode {
@@ -55,6 +84,9 @@ impl Default for JsonTreeNode {
             arr_indices_len: 0,
             array_bias_override: None,
             prefers_parent_line: false,
+            embedded: false,
+            diff: None,
+            source_line: None,
         }
     }
 }
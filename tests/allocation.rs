@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn write_object(dir: &Path, name: &str, keys: usize) {
+    let body: Vec<String> =
+        (0..keys).map(|i| format!("\"k{i}\": {i}")).collect();
+    fs::write(dir.join(name), format!("{{{}}}", body.join(",")))
+        .expect("write");
+}
+
+fn run(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort", "-C", "600"])
+        .args(args)
+        .args(["big.json", "small.json"])
+        .assert()
+}
+
+// Number of rendered keys in each file's section: (big, small).
+fn shown_keys(assert: &assert_cmd::assert::Assert) -> (usize, usize) {
+    let out = String::from_utf8_lossy(&assert.get_output().stdout);
+    let (big, small) = out
+        .split_once("==> small.json <==")
+        .expect("small.json section");
+    (big.matches("\"k").count(), small.matches("\"k").count())
+}
+
+fn setup() -> tempfile::TempDir {
+    let dir = tempdir().expect("tmp");
+    write_object(dir.path(), "big.json", 200);
+    write_object(dir.path(), "small.json", 60);
+    dir
+}
+
+#[test]
+fn equal_allocation_splits_the_global_budget_evenly() {
+    let dir = setup();
+    let (big, small) = shown_keys(&run(dir.path(), &[]).success());
+    assert!(big.abs_diff(small) <= 1, "big={big} small={small}");
+}
+
+#[test]
+fn size_allocation_favors_larger_files() {
+    let dir = setup();
+    let (big, small) =
+        shown_keys(&run(dir.path(), &["--allocation", "size"]).success());
+    assert!(big > 2 * small, "big={big} small={small}");
+    assert!(small > 0, "every file keeps a share");
+}
+
+#[test]
+fn weight_override_applies_to_matching_files() {
+    let dir = setup();
+    let (big, small) = shown_keys(
+        &run(
+            dir.path(),
+            &["--allocation", "size", "--weight", "small*=1e9"],
+        )
+        .success(),
+    );
+    assert!(small > 2 * big, "big={big} small={small}");
+}
+
+#[test]
+fn invalid_weight_is_rejected() {
+    let dir = setup();
+    let assert = run(dir.path(), &["--weight", "*.json=-1"]).failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(stderr.contains("invalid --weight"), "{stderr}");
+}
+
+#[test]
+fn grep_hits_allocation_counts_predicate_hits() {
+    let dir = setup();
+    let args = ["--where", "k150 >= 0", "--grep-show", "all"];
+    let (_, equal_small) = shown_keys(
+        &run(
+            dir.path(),
+            &[&args[..], &["--allocation", "equal"]].concat(),
+        )
+        .success(),
+    );
+    let (_, hits_small) = shown_keys(
+        &run(
+            dir.path(),
+            &[&args[..], &["--allocation", "grep-hits"]].concat(),
+        )
+        .success(),
+    );
+    assert!(hits_small < equal_small, "{hits_small} vs {equal_small}");
+}
+
+#[test]
+fn grep_hits_allocation_requires_a_filter() {
+    let dir = setup();
+    let assert = run(dir.path(), &["--allocation", "grep-hits"]).failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(stderr.contains("requires --grep"), "{stderr}");
+}
@@ -0,0 +1,117 @@
+use serde_json::Value;
+
+fn run(stdin: &str, args: &[&str]) -> (bool, String) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "-t", "annotated"])
+        .args(args)
+        .write_stdin(stdin)
+        .output()
+        .expect("run hson");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+    )
+}
+
+fn parse(out: &str) -> Value {
+    serde_json::from_str(out).unwrap_or_else(|e| panic!("{e}: {out}"))
+}
+
+fn input() -> String {
+    let items: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+    let props: Vec<String> =
+        (0..30).map(|i| format!("\"k{i:02}\": {i}")).collect();
+    format!(
+        "{{\"items\": [{}], \"props\": {{{}}}, \"text\": \"{}\"}}",
+        items.join(","),
+        props.join(","),
+        "x".repeat(400)
+    )
+}
+
+// Kept items plus every `$omitted_items` count add up to the original
+// length.
+fn accounted_len(items: &[Value]) -> u64 {
+    items
+        .iter()
+        .map(|item| {
+            item.get("$omitted_items")
+                .and_then(Value::as_u64)
+                .unwrap_or(1)
+        })
+        .sum()
+}
+
+#[test]
+fn arrays_account_for_every_omitted_item() {
+    for extra in [&[][..], &["--head"], &["--tail"], &["-m"]] {
+        let (ok, out) = run(&input(), &[&["-c", "300"], extra].concat());
+        assert!(ok, "{out}");
+        let value = parse(&out);
+        let items = value["items"].as_array().expect("items array");
+        assert!(items.len() < 1000, "{out}");
+        assert_eq!(accounted_len(items), 1000, "{extra:?}: {out}");
+    }
+}
+
+#[test]
+fn pruned_objects_in_arrays_are_not_counted_as_markers() {
+    let objects: Vec<String> = (0..400)
+        .map(|i| format!("{{\"id\": {i}, \"name\": \"item {i}\"}}"))
+        .collect();
+    let (ok, out) = run(&format!("[{}]", objects.join(",")), &["-c", "300"]);
+    assert!(ok, "{out}");
+    let value = parse(&out);
+    let items = value.as_array().expect("array");
+    assert!(items.len() < 400, "{out}");
+    assert_eq!(accounted_len(items), 400, "{out}");
+}
+
+#[test]
+fn input_keys_spelled_like_markers_are_escaped() {
+    let input = r#"[{"$omitted_items": 7, "$$truncated": "x", "$id": 1}]"#;
+    let (ok, out) = run(input, &["-c", "1000"]);
+    assert!(ok, "{out}");
+    assert_eq!(
+        parse(&out),
+        serde_json::json!([
+            {"$$omitted_items": 7, "$$$truncated": "x", "$id": 1}
+        ])
+    );
+}
+
+#[test]
+fn objects_and_strings_record_what_was_cut() {
+    let (ok, out) = run(&input(), &["-c", "300"]);
+    assert!(ok);
+    let value = parse(&out);
+    let props = value["props"].as_object().expect("props object");
+    let omitted = props["$omitted_keys"].as_u64().expect("$omitted_keys key");
+    assert_eq!(props.len() as u64 - 1 + omitted, 30, "{out}");
+    let text = value["text"].as_str().expect("text string");
+    let (kept, suffix) = text.split_once('…').expect("cut marker");
+    assert_eq!(suffix, format!("[+{} chars]", 400 - kept.len()), "{out}");
+}
+
+#[test]
+fn complete_output_is_plain_json() {
+    let (ok, out) = run(r#"{"a": [1, 2], "b": "x"}"#, &["-c", "1000"]);
+    assert!(ok);
+    assert!(!out.contains('$'), "{out}");
+    assert_eq!(parse(&out), serde_json::json!({"a": [1, 2], "b": "x"}));
+}
+
+#[test]
+fn recovered_input_records_the_truncation() {
+    let (_, out) = run(r#"{"a": [1, 2, 3"#, &["--recover"]);
+    let value = parse(&out);
+    assert_eq!(
+        value["a"],
+        serde_json::json!([
+            1,
+            2,
+            {"$truncated": "input truncated at byte 14: unexpected EOF"}
+        ])
+    );
+}
@@ -126,6 +126,7 @@ fn array_tail_json_contains_last_k_values() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..headson::RenderConfig::default()
     };
     let mut prio = headson::PriorityConfig::new(usize::MAX, 15);
     prio.prefer_tail_arrays = true;
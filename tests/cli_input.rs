@@ -68,7 +68,7 @@ fn unreadable_file_path_errors_with_stderr() {
 }
 
 #[test]
-fn directories_and_binary_files_are_ignored_with_notices() {
+fn empty_directories_and_binary_files_are_reported_with_notices() {
     let tmpdir = tempfile::tempdir().expect("tmpdir");
 
     let dir_path = tmpdir.path().join("subdir");
@@ -100,7 +100,7 @@ fn directories_and_binary_files_are_ignored_with_notices() {
     assert!(ok, "cli should succeed: {err}");
     assert!(out.contains("\n") || out.contains('{'));
     assert!(
-        err.contains("Ignored directory:")
+        err.contains("No files found in directory:")
             && err.contains("Ignored binary file:"),
         "stderr should contain ignore notices, got: {err:?}"
     );
@@ -137,7 +137,7 @@ fn only_ignored_inputs_result_in_empty_output_and_notices() {
     let err1 = String::from_utf8_lossy(&assert1.get_output().stderr);
     assert!(ok1, "cli should succeed: {err1}");
     assert_eq!(out1, "\n", "expected empty output when nothing included");
-    assert!(err1.contains("Ignored directory:"));
+    assert!(err1.contains("No files found in directory:"));
 
     // Case 2: multiple ignored paths -> no included inputs, empty output
     let mut cmd2 = assert_cmd::cargo::cargo_bin_cmd!("hson");
@@ -159,7 +159,7 @@ fn only_ignored_inputs_result_in_empty_output_and_notices() {
     assert!(ok2, "cli should succeed: {err2}");
     assert_eq!(out2, "\n", "expected empty output when nothing included");
     assert!(
-        err2.contains("Ignored directory:")
+        err2.contains("No files found in directory:")
             && err2.contains("Ignored binary file:"),
         "stderr should contain both ignore notices, got: {err2:?}"
     );
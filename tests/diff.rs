@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Repository, Signature, Time};
+use tempfile::tempdir;
+
+fn hson_diff(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .arg("diff")
+        .args(["--no-color", "-c", "10000"])
+        .args(args)
+        .assert()
+}
+
+fn stdout_of(assert: &assert_cmd::assert::Assert) -> String {
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+fn stderr_of(assert: &assert_cmd::assert::Assert) -> String {
+    String::from_utf8_lossy(&assert.get_output().stderr).into_owned()
+}
+
+fn write_pair(dir: &Path, old: &str, new: &str) {
+    fs::write(dir.join("old.json"), old).expect("write old");
+    fs::write(dir.join("new.json"), new).expect("write new");
+}
+
+#[test]
+fn shows_only_changed_keys_with_markers() {
+    let dir = tempdir().expect("tmp");
+    write_pair(
+        dir.path(),
+        r#"{"keep":1,"gone":true,"edit":"a"}"#,
+        r#"{"keep":1,"edit":"b","fresh":[1]}"#,
+    );
+    let assert = hson_diff(dir.path(), &["old.json", "new.json"]).success();
+    let out = stdout_of(&assert);
+    for line in [
+        "-   \"gone\": true",
+        "-   \"edit\": \"a\"",
+        "+   \"edit\": \"b\"",
+        "+   \"fresh\": [",
+    ] {
+        assert!(out.contains(line), "missing {line}: {out}");
+    }
+    assert!(!out.contains("keep"), "unchanged keys fold away: {out}");
+    assert!(out.contains('…'), "unchanged sibling is counted: {out}");
+}
+
+#[test]
+fn arrays_align_by_identity_field() {
+    let dir = tempdir().expect("tmp");
+    write_pair(
+        dir.path(),
+        r#"[{"id":1,"v":"a"},{"id":2,"v":"b"}]"#,
+        r#"[{"id":0,"v":"new"},{"id":1,"v":"a"},{"id":2,"v":"b"}]"#,
+    );
+    let assert = hson_diff(dir.path(), &["old.json", "new.json"]).success();
+    let out = stdout_of(&assert);
+    assert!(out.contains("+     \"v\": \"new\""), "{out}");
+    assert!(!out.contains("- "), "shifted elements are unchanged: {out}");
+}
+
+#[test]
+fn identical_documents_report_no_differences() {
+    let dir = tempdir().expect("tmp");
+    write_pair(dir.path(), r#"{"a":[1,2]}"#, r#"{"a":[1,2]}"#);
+    let assert = hson_diff(dir.path(), &["old.json", "new.json"]).success();
+    assert!(stderr_of(&assert).contains("No differences"));
+}
+
+#[test]
+fn yaml_output_keeps_gutter_on_nested_lines() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("old.yaml"), "items:\n  - a\n").expect("old");
+    fs::write(dir.path().join("new.yaml"), "items:\n  - a\n  - {x: 1}\n")
+        .expect("new");
+    let assert = hson_diff(dir.path(), &["old.yaml", "new.yaml"]).success();
+    let out = stdout_of(&assert);
+    assert!(out.contains("+   - x: 1"), "{out}");
+    assert!(out.starts_with("  items:"), "{out}");
+}
+
+#[test]
+fn fail_on_truncated_exits_six_when_the_diff_is_cut() {
+    let dir = tempdir().expect("tmp");
+    let items: Vec<String> = (0..2000).map(|i| i.to_string()).collect();
+    write_pair(dir.path(), "[]", &format!("[{}]", items.join(",")));
+    let args = ["--fail-on", "truncated", "old.json", "new.json"];
+    hson_diff(dir.path(), &args).code(6);
+    write_pair(dir.path(), "[1]", "[2]");
+    hson_diff(dir.path(), &args).success();
+}
+
+#[test]
+fn requires_two_inputs() {
+    let dir = tempdir().expect("tmp");
+    write_pair(dir.path(), "{}", "{}");
+    let assert = hson_diff(dir.path(), &["old.json"]).failure();
+    assert!(stderr_of(&assert).contains("exactly two inputs"));
+}
+
+#[test]
+fn rejects_flags_of_the_preview_mode() {
+    let dir = tempdir().expect("tmp");
+    write_pair(dir.path(), "{}", "{}");
+    let assert =
+        hson_diff(dir.path(), &["--grep", "a", "old.json", "new.json"])
+            .failure();
+    assert!(stderr_of(&assert).contains("unexpected argument '--grep'"));
+}
+
+// After a preview flag, `diff` is an input path rather than the subcommand.
+#[test]
+fn a_file_named_diff_is_a_regular_input() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("diff"), r#"{"a":1}"#).expect("write");
+    let assert = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "diff"])
+        .assert()
+        .success();
+    assert!(stdout_of(&assert).contains(r#"{"a":1}"#));
+}
+
+fn commit_file(dir: &Path, rel: &str, body: &str) {
+    let repo = Repository::init(dir).expect("init");
+    fs::write(dir.join(rel), body).expect("write");
+    let mut index = repo.index().expect("index");
+    index.add_path(Path::new(rel)).expect("add");
+    index.write().expect("write index");
+    let tree_id = index.write_tree().expect("write tree");
+    let tree = repo.find_tree(tree_id).expect("tree");
+    let sig = Signature::new("tester", "t@example.com", &Time::new(1, 0))
+        .expect("sig");
+    repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+        .expect("commit");
+}
+
+#[test]
+fn compares_revision_with_working_tree() {
+    let dir = tempdir().expect("tmp");
+    commit_file(dir.path(), "conf.json", r#"{"port":80}"#);
+    fs::write(dir.path().join("conf.json"), r#"{"port":8080}"#).expect("edit");
+    let assert =
+        hson_diff(dir.path(), &["--rev", "HEAD", "conf.json"]).success();
+    let out = stdout_of(&assert);
+    assert!(out.contains("-   \"port\": 80"), "{out}");
+    assert!(out.contains("+   \"port\": 8080"), "{out}");
+}
+
+#[test]
+fn accepts_rev_colon_path_sides() {
+    let dir = tempdir().expect("tmp");
+    commit_file(dir.path(), "conf.json", r#"{"port":80}"#);
+    fs::write(dir.path().join("conf.json"), r#"{"port":81}"#).expect("edit");
+    let assert =
+        hson_diff(dir.path(), &["HEAD:conf.json", "conf.json"]).success();
+    assert!(stdout_of(&assert).contains("+   \"port\": 81"));
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use tempfile::tempdir;
+
+fn run(dir: &std::path::Path, args: &[&str]) -> Option<i32> {
+    hson(dir, args).status.code()
+}
+
+fn hson(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort"])
+        .args(args)
+        .output()
+        .expect("run hson")
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let items: Vec<String> = (0..200).map(|i| i.to_string()).collect();
+    fs::write(
+        dir.path().join("data.json"),
+        format!("{{\"items\": [{}]}}", items.join(", ")),
+    )
+    .expect("write json");
+    fs::write(dir.path().join("blob.bin"), [0x00, 0xFF, 0x00, 0x01])
+        .expect("write bin");
+    dir
+}
+
+#[test]
+fn conditions_exit_zero_unless_requested() {
+    let dir = fixture();
+    assert_eq!(
+        run(dir.path(), &["-c", "40", "data.json", "blob.bin"]),
+        Some(0)
+    );
+    assert_eq!(
+        run(dir.path(), &["--grep", "nothing-here", "data.json"]),
+        Some(0)
+    );
+}
+
+#[test]
+fn fail_on_selects_the_reported_conditions() {
+    let dir = fixture();
+    let cases: [(&[&str], i32); 5] = [
+        (&["--fail-on", "truncated", "-c", "40", "data.json"], 6),
+        (&["--fail-on", "truncated", "-c", "10000", "data.json"], 0),
+        (&["--fail-on", "ignored", "data.json", "blob.bin"], 4),
+        (
+            &[
+                "--fail-on",
+                "no-match",
+                "--grep",
+                "nothing-here",
+                "data.json",
+            ],
+            5,
+        ),
+        (
+            &[
+                "--fail-on",
+                "truncated,ignored",
+                "-c",
+                "40",
+                "data.json",
+                "blob.bin",
+            ],
+            4,
+        ),
+    ];
+    for (args, code) in cases {
+        assert_eq!(run(dir.path(), args), Some(code), "{args:?}");
+    }
+}
+
+#[test]
+fn truncation_status_is_silent_on_stderr() {
+    let dir = fixture();
+    let output = hson(
+        dir.path(),
+        &["--fail-on", "truncated", "-c", "40", "data.json"],
+    );
+    assert_eq!(output.status.code(), Some(6));
+    assert!(!output.stdout.is_empty());
+    assert!(output.stderr.is_empty(), "{:?}", output.stderr);
+}
+
+#[test]
+fn parse_failures_exit_three_when_requested() {
+    let dir = fixture();
+    fs::write(dir.path().join("cut.json"), "{\"a\": [1, 2").expect("write");
+    let recover = ["--recover", "cut.json"];
+    assert_eq!(hson(dir.path(), &recover).status.code(), Some(0));
+    let output = hson(
+        dir.path(),
+        &[&["--fail-on", "parse-error"][..], &recover].concat(),
+    );
+    assert_eq!(output.status.code(), Some(3));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(err.contains("Recovered cut.json: input truncated"), "{err}");
+}
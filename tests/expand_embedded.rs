@@ -0,0 +1,44 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+const LOG: &str = r#"{"level":"error","message":"{\"request\":{\"path\":\"/login\",\"status\":503},\"trace\":[\"a\",\"b\",\"c\"]}"}"#;
+
+fn run(args: &[&str]) -> String {
+    let assert = cargo_bin_cmd!("hson")
+        .arg("--no-color")
+        .args(args)
+        .write_stdin(LOG)
+        .assert()
+        .success();
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+#[test]
+fn embedded_json_string_becomes_marked_subtree() {
+    let out = run(&["--expand-embedded"]);
+    assert!(
+        out.contains("\"message\": {\n    /* embedded */\n"),
+        "got: {out}"
+    );
+    assert!(out.contains("\"status\": 503"), "got: {out}");
+}
+
+#[test]
+fn strong_grep_reaches_inside_embedded_values() {
+    let out = run(&["--expand-embedded", "-c", "40", "--grep", "login"]);
+    assert!(out.contains("\"path\": \"/login\""), "got: {out}");
+    assert!(!out.contains("\"b\""), "budget applies elsewhere: {out}");
+}
+
+#[test]
+fn strict_output_stays_valid_json() {
+    let out = run(&["--expand-embedded", "-t", "strict"]);
+    let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["message"]["request"]["status"], 503);
+}
+
+#[test]
+fn strings_stay_opaque_without_the_flag() {
+    let out = run(&["-t", "strict"]);
+    let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert!(v["message"].is_string(), "got: {out}");
+}
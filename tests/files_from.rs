@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn run_in(cwd: &Path, args: &[&str], stdin: &[u8]) -> (bool, String, String) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hson");
+    let assert = cmd
+        .current_dir(cwd)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "-c", "100000"])
+        .args(args)
+        .write_stdin(stdin.to_vec())
+        .assert();
+    let ok = assert.get_output().status.success();
+    let out =
+        String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    let err =
+        String::from_utf8_lossy(&assert.get_output().stderr).into_owned();
+    (ok, out, err)
+}
+
+fn write_files(root: &Path) {
+    fs::write(root.join("a.json"), b"{\"a\":1}").expect("write");
+    fs::write(root.join("b c.yaml"), b"b: 2\n").expect("write");
+    fs::write(root.join("bin.dat"), [0u8, 159, 146, 150]).expect("write");
+}
+
+#[test]
+fn newline_list_from_stdin_feeds_the_fileset() {
+    let dir = tempdir().expect("tmp");
+    write_files(dir.path());
+    let (ok, out, err) = run_in(
+        dir.path(),
+        &["--no-sort", "--files-from", "-"],
+        b"a.json\r\nb c.yaml\n\nbin.dat\n",
+    );
+    assert!(ok, "should succeed: {err}");
+    assert!(out.contains("==> a.json <=="), "{out}");
+    assert!(out.contains("==> b c.yaml <=="), "{out}");
+    assert!(err.contains("Ignored binary file: bin.dat"), "{err}");
+}
+
+#[test]
+fn nul_separated_list_from_file() {
+    let dir = tempdir().expect("tmp");
+    write_files(dir.path());
+    fs::write(dir.path().join("list"), b"b c.yaml\0a.json\0").expect("list");
+    let (ok, out, err) = run_in(
+        dir.path(),
+        &["--no-sort", "--files-from", "list", "-0"],
+        b"",
+    );
+    assert!(ok, "should succeed: {err}");
+    let b = out.find("==> b c.yaml <==").expect("yaml header");
+    let a = out.find("==> a.json <==").expect("json header");
+    assert!(b < a, "--no-sort keeps list order: {out}");
+}
+
+#[test]
+fn empty_list_does_not_read_stdin_as_document() {
+    let dir = tempdir().expect("tmp");
+    let (ok, out, err) = run_in(dir.path(), &["--files-from", "-"], b"");
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(out, "\n");
+    assert!(err.contains("No paths listed by --files-from"), "{err}");
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths_are_read() {
+    use std::os::unix::ffi::OsStrExt;
+    let dir = tempdir().expect("tmp");
+    write_files(dir.path());
+    let name = std::ffi::OsStr::from_bytes(b"caf\xe9.json");
+    fs::write(dir.path().join(name), b"{\"latin1\":true}").expect("write");
+    let (ok, out, err) = run_in(
+        dir.path(),
+        &["--no-sort", "--files-from", "-", "-0"],
+        b"caf\xe9.json\0a.json\0",
+    );
+    assert!(ok, "should succeed: {err}");
+    assert!(out.contains("\"latin1\": true"), "{out}");
+    assert!(out.contains("==> a.json <=="), "{out}");
+}
@@ -21,6 +21,7 @@ fn render_config() -> RenderConfig {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..RenderConfig::default()
     }
 }
 
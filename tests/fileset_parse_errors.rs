@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn setup() -> tempfile::TempDir {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("good.json"), "{\"ok\": 1}\n").expect("write");
+    fs::write(dir.path().join("bad.json"), "a: 1,\n b: [1, 2\n")
+        .expect("write");
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort", "-C", "2000"])
+        .args(args)
+        .args(["good.json", "bad.json"])
+        .assert()
+}
+
+#[test]
+fn malformed_file_falls_back_to_text_with_notice() {
+    let dir = setup();
+    let assert = run(dir.path(), &["--fail-on", "parse-error"]).code(3);
+    let out = String::from_utf8_lossy(&assert.get_output().stdout);
+    let err = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(out.contains("\"ok\": 1"), "good file still parsed: {out}");
+    assert!(
+        out.contains("==> bad.json <==\na: 1,\n b: [1, 2\n"),
+        "bad file previewed as text: {out}"
+    );
+    assert!(
+        err.contains(
+            "Could not parse bad.json; showing it as text\n\
+             bad.json:1:1: invalid JSON: unexpected character 'a'\n \
+             1 | a: 1,\n   | ^\n"
+        ),
+        "notice names the file and points at the error: {err}"
+    );
+}
+
+#[test]
+fn strict_parse_keeps_fail_fast_behavior() {
+    let dir = setup();
+    let assert = run(dir.path(), &["--strict-parse"]).failure();
+    let err = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(err.contains("failed to parse bad.json"), "{err}");
+}
+
+#[test]
+fn library_reports_parse_failures() {
+    let inputs = vec![
+        headson::FilesetInput {
+            name: "ok.yaml".to_string(),
+            bytes: b"a: 1\n".to_vec(),
+            kind: headson::FilesetInputKind::Yaml,
+        },
+        headson::FilesetInput {
+            name: "broken.yaml".to_string(),
+            bytes: b"a: [1\n".to_vec(),
+            kind: headson::FilesetInputKind::Yaml,
+        },
+    ];
+    let cfg = headson::RenderConfig {
+        template: headson::OutputTemplate::Auto,
+        indent_unit: "  ".to_string(),
+        space: " ".to_string(),
+        newline: "\n".to_string(),
+        prefer_tail_arrays: false,
+        color_mode: headson::ColorMode::Off,
+        color_enabled: false,
+        style: headson::Style::Default,
+        string_free_prefix_graphemes: None,
+        debug: false,
+        primary_source_name: None,
+        show_fileset_headers: true,
+        fileset_tree: false,
+        count_fileset_headers_in_budgets: false,
+        grep_highlight: None,
+        ..headson::RenderConfig::default()
+    };
+    let rendered = headson::headson_with_report(
+        headson::InputKind::Fileset(inputs),
+        &cfg,
+        &headson::PriorityConfig::new(100, 100),
+        &headson::GrepConfig::default(),
+        headson::Budgets::default(),
+    )
+    .expect("fileset renders despite the broken file");
+    let names: Vec<&str> = rendered
+        .parse_failures
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["broken.yaml"]);
+    let diagnostic = rendered.parse_failures[0]
+        .diagnostic
+        .as_ref()
+        .expect("located failure");
+    assert_eq!(
+        (
+            diagnostic.file.as_deref(),
+            diagnostic.line,
+            diagnostic.column
+        ),
+        (Some("broken.yaml"), 1, 6)
+    );
+    assert!(rendered.text.contains("a: [1"), "{}", rendered.text);
+}
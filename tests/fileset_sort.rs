@@ -0,0 +1,211 @@
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use git2::{Repository, Signature, Time};
+use tempfile::tempdir;
+
+fn run(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "-c", "1000"])
+        .args(args)
+        .assert()
+}
+
+fn header_order(assert: &assert_cmd::assert::Assert) -> Vec<String> {
+    String::from_utf8_lossy(&assert.get_output().stdout)
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("==> ")
+                .and_then(|rest| rest.strip_suffix(" <=="))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+// b.txt is the largest file and has the most lines matching "hit"; a.txt
+// the smallest.
+fn setup() -> tempfile::TempDir {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("c.txt"), "hit\nmiss\n").expect("c");
+    fs::write(dir.path().join("a.txt"), "x\n").expect("a");
+    fs::write(dir.path().join("b.txt"), "hit one\nhit two\nhit three\n")
+        .expect("b");
+    dir
+}
+
+#[test]
+fn sort_path_orders_by_name() {
+    let dir = setup();
+    let assert =
+        run(dir.path(), &["--sort", "path", "c.txt", "b.txt", "a.txt"])
+            .success();
+    assert_eq!(header_order(&assert), ["a.txt", "b.txt", "c.txt"]);
+}
+
+#[test]
+fn reverse_flips_the_chosen_order() {
+    let dir = setup();
+    let assert = run(
+        dir.path(),
+        &["--sort", "path", "--reverse", "a.txt", "b.txt", "c.txt"],
+    )
+    .success();
+    assert_eq!(header_order(&assert), ["c.txt", "b.txt", "a.txt"]);
+}
+
+#[test]
+fn reverse_keeps_ties_in_input_order() {
+    let dir = setup();
+    fs::write(dir.path().join("d.txt"), "miss\nhit\n").expect("d");
+    let assert = run(
+        dir.path(),
+        &[
+            "--sort",
+            "size",
+            "--reverse",
+            "d.txt",
+            "b.txt",
+            "c.txt",
+            "a.txt",
+        ],
+    )
+    .success();
+    assert_eq!(header_order(&assert), ["a.txt", "d.txt", "c.txt", "b.txt"]);
+}
+
+#[test]
+fn sort_grep_matches_counts_matching_values_not_raw_hits() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("a.json"), r#"{"k":"hit hit hit hit"}"#)
+        .expect("a");
+    fs::write(dir.path().join("b.json"), r#"{"k":"hit","l":"hit"}"#)
+        .expect("b");
+    let assert = run(
+        dir.path(),
+        &[
+            "--sort",
+            "grep-matches",
+            "--grep",
+            "hit",
+            "--grep-show",
+            "all",
+            "a.json",
+            "b.json",
+        ],
+    )
+    .success();
+    assert_eq!(header_order(&assert), ["b.json", "a.json"]);
+}
+
+#[test]
+fn sort_size_puts_largest_first() {
+    let dir = setup();
+    let assert =
+        run(dir.path(), &["--sort", "size", "a.txt", "c.txt", "b.txt"])
+            .success();
+    assert_eq!(header_order(&assert), ["b.txt", "c.txt", "a.txt"]);
+}
+
+#[test]
+fn sort_grep_matches_puts_most_hits_first() {
+    let dir = setup();
+    let assert = run(
+        dir.path(),
+        &[
+            "--sort",
+            "grep-matches",
+            "--weak-grep",
+            "hit",
+            "a.txt",
+            "c.txt",
+            "b.txt",
+        ],
+    )
+    .success();
+    assert_eq!(header_order(&assert), ["b.txt", "c.txt", "a.txt"]);
+}
+
+#[test]
+fn sort_grep_matches_counts_predicate_hits() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("a.json"), r#"[{"ok":false},{"ok":true}]"#)
+        .expect("a");
+    fs::write(
+        dir.path().join("b.json"),
+        r#"[{"ok":false},{"ok":false},{"ok":true}]"#,
+    )
+    .expect("b");
+    let assert = run(
+        dir.path(),
+        &[
+            "--sort",
+            "grep-matches",
+            "--where",
+            "ok == false",
+            "a.json",
+            "b.json",
+        ],
+    )
+    .success();
+    assert_eq!(header_order(&assert), ["b.json", "a.json"]);
+}
+
+#[test]
+fn sort_grep_matches_requires_a_filter() {
+    let dir = setup();
+    let assert =
+        run(dir.path(), &["--sort", "grep-matches", "a.txt", "b.txt"])
+            .failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(stderr.contains("requires --grep"), "{stderr}");
+}
+
+#[test]
+fn sort_applies_to_tree_view() {
+    let dir = setup();
+    let assert = run(
+        dir.path(),
+        &["--tree", "--sort", "size", "a.txt", "c.txt", "b.txt"],
+    )
+    .success();
+    let out = String::from_utf8_lossy(&assert.get_output().stdout);
+    let pos = |name: &str| out.find(name).expect(name);
+    assert!(pos("b.txt") < pos("c.txt") && pos("c.txt") < pos("a.txt"));
+}
+
+fn commit(repo: &Repository, root: &Path, rel: &str, body: &str) {
+    fs::write(root.join(rel), body).expect("write");
+    let mut index = repo.index().expect("index");
+    index.add_path(Path::new(rel)).expect("add");
+    index.write().expect("write index");
+    let tree = repo
+        .find_tree(index.write_tree().expect("write tree"))
+        .expect("tree");
+    let sig = Signature::new("tester", "t@example.com", &Time::new(1, 0))
+        .expect("sig");
+    let parent = repo
+        .head()
+        .ok()
+        .and_then(|h| h.target())
+        .and_then(|oid| repo.find_commit(oid).ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, "edit", &tree, &parents)
+        .expect("commit");
+}
+
+#[test]
+fn sort_git_churn_puts_most_committed_first() {
+    let dir = tempdir().expect("tmp");
+    let repo = Repository::init(dir.path()).expect("init");
+    commit(&repo, dir.path(), "calm.txt", "1");
+    for rev in 0..3 {
+        commit(&repo, dir.path(), "busy.txt", &rev.to_string());
+    }
+    let assert =
+        run(dir.path(), &["--sort", "git-churn", "calm.txt", "busy.txt"])
+            .success();
+    assert_eq!(header_order(&assert), ["busy.txt", "calm.txt"]);
+}
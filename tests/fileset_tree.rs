@@ -28,6 +28,7 @@ fn tree_cfg() -> headson::RenderConfig {
         fileset_tree: true,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..headson::RenderConfig::default()
     }
 }
 
@@ -447,11 +448,11 @@ fn tree_reports_omissions_when_every_file_is_dropped() {
         .success();
 
     let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
-    let expected = concat!(".\n", "├─ dir/\n", "│ └─ … 2 more items\n", "\n",);
+    let expected = concat!(".\n", "└─ dir/ (2 files: 2 .txt)\n", "\n",);
     assert_eq!(
         stdout.as_ref(),
         expected,
-        "when all files are pruned, omission counts should still render under their folder"
+        "when all files are pruned, the folder should collapse into a summary line"
     );
 }
 
@@ -658,6 +659,7 @@ fn tree_omitted_folders_render_in_input_order() {
             array_bias: headson::ArrayBias::HeadMidTail,
             array_sampler: headson::ArraySamplerStrategy::Default,
             line_budget_only: true,
+            ..headson::PriorityConfig::new(usize::MAX, usize::MAX)
         };
         let grep_cfg = headson::GrepConfig::default();
         let budgets = headson::Budgets {
@@ -691,3 +693,195 @@ fn tree_omitted_folders_render_in_input_order() {
         "tree output with only omissions should be stable; saw variants: {variants:?}"
     );
 }
+
+fn write_stats_fixture(root: &Path) {
+    write_file(&root.join("notes.txt"), "a\nb\nc\n");
+    write_file(&root.join("data/one.json"), r#"{"a":1,"b":[1,2,3]}"#);
+    write_file(&root.join("data/two.json"), "[1,2,3,4]");
+}
+
+fn run_tree_stats(root: &Path, extra: &[&str]) -> String {
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(root)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--tree", "--no-sort"])
+        .args(extra)
+        .args(["notes.txt", "data/one.json", "data/two.json"])
+        .assert()
+        .success();
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+#[test]
+fn tree_stats_annotate_files_and_directories() {
+    let dir = tempdir().expect("tmp");
+    write_stats_fixture(dir.path());
+    let out = run_tree_stats(dir.path(), &["--tree-stats", "-C", "2000"]);
+    let headers: Vec<&str> =
+        out.lines().filter(|l| l.contains(" (")).collect();
+    assert_eq!(
+        headers,
+        vec![
+            ". (3 files, 34 B)",
+            "├─ notes.txt (6 B, 3 lines)",
+            "├─ data/ (2 files, 28 B)",
+            "│ ├─ one.json (19 B, 2 keys)",
+            "│ ├─ two.json (9 B, 4 items)",
+        ],
+        "unexpected annotations:\n{out}"
+    );
+}
+
+#[test]
+fn tree_stats_directory_totals_include_omitted_files() {
+    let dir = tempdir().expect("tmp");
+    write_stats_fixture(dir.path());
+    let out = run_tree_stats(dir.path(), &["--tree-stats", "-N", "2"]);
+    assert!(out.contains("more items"), "expected omissions:\n{out}");
+    assert!(
+        out.starts_with(". (3 files, 34 B)\n"),
+        "root totals should cover omitted files:\n{out}"
+    );
+}
+
+#[test]
+fn tree_stats_are_free_unless_headers_are_counted() {
+    let dir = tempdir().expect("tmp");
+    write_stats_fixture(dir.path());
+    let plain = run_tree_stats(dir.path(), &["-C", "60"]);
+    let annotated = run_tree_stats(dir.path(), &["--tree-stats", "-C", "60"]);
+    let strip = |s: &str| {
+        s.lines()
+            .map(|l| l.split(" (").next().unwrap_or(l).to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(strip(&plain), strip(&annotated));
+
+    let counted = run_tree_stats(
+        dir.path(),
+        &["--tree-stats", "--count-headers", "-C", "120"],
+    );
+    assert!(
+        counted.len() <= 120,
+        "annotations should count toward the budget: {} bytes\n{counted}",
+        counted.len()
+    );
+}
+
+#[test]
+fn tree_collapses_folders_without_kept_files_into_summaries() {
+    let dir = tempdir().expect("tmp");
+    write_file(&dir.path().join("src/main.rs"), "fn main(){}\n");
+    let mut args: Vec<String> = vec!["src/main.rs".to_string()];
+    for i in 0..3 {
+        let name = format!("fixtures/json/f{i}.json");
+        write_file(&dir.path().join(&name), "{}");
+        args.push(name);
+    }
+    for (i, ext) in ["yaml", "yaml", "toml", "csv", "lock"].iter().enumerate()
+    {
+        let name = format!("fixtures/misc/g{i}.{ext}");
+        write_file(&dir.path().join(&name), "x\n");
+        args.push(name);
+    }
+
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--tree", "--no-sort", "-N", "0"])
+        .args(&args)
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    let expected = concat!(
+        ".\n",
+        "├─ src/ (1 file: 1 .rs)\n",
+        "├─ fixtures/ (8 files: 3 .json, 2 .yaml, 1 .toml, 2 other)\n",
+        "│ ├─ json/ (3 files: 3 .json)\n",
+        "│ └─ misc/ (5 files: 2 .yaml, 1 .toml, 1 .csv, 1 other)\n",
+        "\n",
+    );
+    assert_eq!(stdout.as_ref(), expected);
+}
+
+#[test]
+fn tree_keeps_top_level_outline_when_nothing_fits() {
+    let dir = tempdir().expect("tmp");
+    for i in 0..10 {
+        write_file(&dir.path().join(format!("t/api/v1/e{i}.json")), "{}");
+        write_file(&dir.path().join(format!("t/data/d{i}.json")), "{}");
+    }
+    for i in 0..3 {
+        write_file(&dir.path().join(format!("t/config/c{i}.yaml")), "k: 1\n");
+    }
+    write_file(&dir.path().join("t/root.json"), "{}");
+
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--tree", "--sort", "path", "-N", "0", "t"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    let expected = concat!(
+        ".\n",
+        "├─ t/ (24 files: 21 .json, 3 .yaml)\n",
+        "│ ├─ api/ (10 files: 10 .json)\n",
+        "│ ├─ config/ (3 files: 3 .yaml)\n",
+        "│ ├─ data/ (10 files: 10 .json)\n",
+        "│ └─ … 1 more items\n",
+        "\n",
+    );
+    assert_eq!(
+        stdout.as_ref(),
+        expected,
+        "only subfolders that do not fit should fold into summaries"
+    );
+}
+
+#[test]
+fn tree_summaries_replace_nested_omission_counts() {
+    let dir = tempdir().expect("tmp");
+    let files = [
+        ("src/main.rs", "fn main(){}\n"),
+        ("fixtures/top.yaml", "x: 1\n"),
+        ("fixtures/a/f1.json", "{\"i\":1}\n"),
+        ("fixtures/a/f2.json", "{\"i\":2}\n"),
+        ("fixtures/a/f3.json", "{\"i\":3}\n"),
+        ("fixtures/b/g1.yaml", "k: 1\n"),
+        ("fixtures/b/g2.yaml", "k: 2\n"),
+    ];
+    for (name, body) in files {
+        write_file(&dir.path().join(name), body);
+    }
+
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--tree", "--no-sort", "-N", "4"])
+        .args(files.iter().map(|(name, _)| *name))
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    let expected = concat!(
+        ".\n",
+        "├─ src/main.rs\n",
+        "├─ fixtures/\n",
+        "│ ├─ top.yaml\n",
+        "│ │ {}\n",
+        "│ ├─ a/\n",
+        "│ │ ├─ f1.json\n",
+        "│ │ │ { … }\n",
+        "│ │ └─ … 2 more items\n",
+        "│ └─ b/ (2 files: 2 .yaml)\n",
+        "\n",
+    );
+    assert_eq!(
+        stdout.as_ref(),
+        expected,
+        "folders should only count their own omitted files"
+    );
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Repository, Signature, Time};
+use tempfile::tempdir;
+
+fn write(root: &Path, rel: &str, body: &str) {
+    let abs = root.join(rel);
+    if let Some(parent) = abs.parent() {
+        fs::create_dir_all(parent).expect("mkdir");
+    }
+    fs::write(abs, body).expect("write");
+}
+
+/// Stage every file in the working tree and commit it.
+fn commit_all(repo: &Repository, message: &str) {
+    let mut index = repo.index().expect("index");
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .expect("add all");
+    index.write().expect("write index");
+    let tree_id = index.write_tree().expect("write tree");
+    let tree = repo.find_tree(tree_id).expect("tree");
+    let sig = Signature::new("tester", "t@example.com", &Time::new(1, 0))
+        .expect("sig");
+    let parent = repo
+        .head()
+        .ok()
+        .and_then(|h| h.target())
+        .and_then(|oid| repo.find_commit(oid).ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .expect("commit");
+}
+
+// Two commits: the first has config/{a,b}.json, the second edits a.json and
+// adds notes.txt. The working tree then edits b.json without committing.
+fn setup() -> (tempfile::TempDir, Repository) {
+    let dir = tempdir().expect("tmp");
+    let repo = Repository::init(dir.path()).expect("init");
+    write(dir.path(), "config/a.json", r#"{"a":"old"}"#);
+    write(dir.path(), "config/b.json", r#"{"b":"old"}"#);
+    commit_all(&repo, "first");
+    write(dir.path(), "config/a.json", r#"{"a":"new"}"#);
+    write(dir.path(), "notes.txt", "hello\n");
+    commit_all(&repo, "second");
+    write(dir.path(), "config/b.json", r#"{"b":"dirty"}"#);
+    (dir, repo)
+}
+
+fn run_in(cwd: &Path, args: &[&str]) -> (bool, String, String) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hson");
+    let assert = cmd
+        .current_dir(cwd)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort", "-c", "10000"])
+        .args(args)
+        .assert();
+    let ok = assert.get_output().status.success();
+    let out =
+        String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    let err =
+        String::from_utf8_lossy(&assert.get_output().stderr).into_owned();
+    (ok, out, err)
+}
+
+fn headers(out: &str) -> Vec<String> {
+    out.lines()
+        .filter_map(|l| l.strip_prefix("==> "))
+        .filter_map(|l| l.strip_suffix(" <=="))
+        .map(|l| l.replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn rev_reads_directory_contents_from_the_commit_tree() {
+    let (dir, _repo) = setup();
+    let (ok, out, err) = run_in(dir.path(), &["--rev", "HEAD~1", "config/"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(headers(&out), ["config/a.json", "config/b.json"]);
+    assert!(out.contains("\"old\""), "{out}");
+    assert!(!out.contains("new") && !out.contains("dirty"), "{out}");
+}
+
+#[test]
+fn rev_single_file_renders_without_header() {
+    let (dir, _repo) = setup();
+    let (ok, out, err) = run_in(
+        dir.path().join("config").as_path(),
+        &["--rev", "HEAD", "a.json"],
+    );
+    assert!(ok, "should succeed: {err}");
+    assert!(headers(&out).is_empty(), "{out}");
+    assert!(out.contains("\"new\""), "{out}");
+}
+
+#[test]
+fn rev_reports_missing_paths() {
+    let (dir, _repo) = setup();
+    let (ok, _, err) = run_in(dir.path(), &["--rev", "HEAD~1", "notes.txt"]);
+    assert!(ok, "should succeed: {err}");
+    assert!(err.contains("Not found in HEAD~1: notes.txt"), "{err}");
+}
+
+#[test]
+fn rev_rejects_unknown_revisions() {
+    let (dir, _repo) = setup();
+    let (ok, _, err) = run_in(dir.path(), &["--rev", "nope"]);
+    assert!(!ok);
+    assert!(err.contains("unknown git revision: nope"), "{err}");
+}
+
+#[test]
+fn changed_defaults_to_working_tree_changes_since_head() {
+    let (dir, _repo) = setup();
+    let (ok, out, err) = run_in(dir.path(), &["--changed"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(headers(&out), ["config/b.json"]);
+    assert!(out.contains("\"dirty\""), "{out}");
+}
+
+#[test]
+fn changed_includes_untracked_files() {
+    let (dir, _repo) = setup();
+    write(dir.path(), "new/c.json", r#"{"c":"fresh"}"#);
+    write(dir.path(), "skip.log", "ignored\n");
+    write(dir.path(), ".gitignore", "*.log\n");
+    let (ok, out, err) = run_in(dir.path(), &["--changed"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(headers(&out), [".gitignore", "config/b.json", "new/c.json"]);
+}
+
+#[test]
+fn changed_filters_explicit_inputs_against_base() {
+    let (dir, _repo) = setup();
+    let (ok, out, err) = run_in(dir.path(), &["--changed=HEAD~1", "config"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(headers(&out), ["config/a.json", "config/b.json"]);
+}
+
+#[test]
+fn changed_with_rev_diffs_two_trees() {
+    let (dir, _repo) = setup();
+    let (ok, out, err) =
+        run_in(dir.path(), &["--rev", "HEAD", "--changed=HEAD~1"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(headers(&out), ["config/a.json", "notes.txt"]);
+}
@@ -733,6 +733,7 @@ fn grep_highlights_for_library_calls_without_extra_config() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..RenderConfig::default()
     };
     let prio = PriorityConfig::new(usize::MAX, usize::MAX);
     let budgets = Budgets {
@@ -744,6 +745,7 @@ fn grep_highlights_for_library_calls_without_extra_config() {
     };
     let grep = GrepConfig {
         regex: Some(regex::Regex::new("needle").unwrap()),
+        predicates: Vec::new(),
         weak: false,
         show: headson::GrepShow::Matching,
     };
@@ -950,3 +952,60 @@ fn strong_grep_obeys_zero_global_line_budget_for_non_matches() {
         "non-matching content should be excluded when no global line headroom remains: {stdout}"
     );
 }
+
+#[test]
+fn combined_grep_color_keeps_syntax_colors() {
+    let input = br#"{"k":"foo bar","x":"baz"}"#.to_vec();
+    let assert = cargo_bin_cmd!("hson")
+        .args([
+            "-f",
+            "json",
+            "-t",
+            "default",
+            "--grep",
+            "foo",
+            "--grep-color",
+            "combined",
+            "--no-sort",
+        ])
+        .env("FORCE_COLOR", "1")
+        .write_stdin(input)
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    assert!(
+        stdout.contains("\u{001b}[32m\"\u{001b}[7mfoo\u{001b}[27m bar\""),
+        "match should be inverse inside the green string: {stdout:?}"
+    );
+    assert!(
+        stdout.contains("\u{001b}[1;34m"),
+        "key colors stay on: {stdout:?}"
+    );
+}
+
+#[test]
+fn combined_grep_color_layers_over_code_highlighting() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("lib.rs"),
+        "fn build_order() {}\nlet x = 1;\n",
+    )
+    .unwrap();
+    let color = |mode: &str| {
+        let assert = cargo_bin_cmd!("hson")
+            .current_dir(dir.path())
+            .args(["--grep", "build", "--grep-color", mode, "--color"])
+            .args(["--no-sort", "--no-header", "lib.rs"])
+            .assert()
+            .success();
+        String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+    };
+    let combined = color("combined");
+    assert!(
+        combined.contains("\u{001b}[38;2;")
+            && combined.contains("\u{001b}[7mbuild\u{001b}[27m"),
+        "expected syntect colors plus inverse match: {combined:?}"
+    );
+    let plain = color("highlight-only");
+    assert!(!plain.contains("\u{001b}[38;2;"), "got: {plain:?}");
+}
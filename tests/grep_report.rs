@@ -0,0 +1,109 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn run_in(dir: &std::path::Path, args: &[&str]) -> String {
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort"])
+        .args(args)
+        .assert()
+        .success();
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+#[test]
+fn summary_footer_lists_matches_per_file() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a.json"),
+        r#"{"a":"needle","b":["needle",1]}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("b.yaml"), "k: needle\n").unwrap();
+    let out = run_in(
+        dir.path(),
+        &[
+            "--grep",
+            "needle",
+            "--grep-report",
+            "summary",
+            "a.json",
+            "b.yaml",
+        ],
+    );
+    assert!(
+        out.ends_with(
+            "==> 3 matches in 2 files <==\na.json: 2 matches\nb.yaml: 1 match\n"
+        ),
+        "footer should follow the preview; got: {out:?}"
+    );
+}
+
+#[test]
+fn json_report_carries_paths_values_and_visibility() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a.json"),
+        r#"{"items":[{"name":"needle"},{"name":"other"}],"tail":"needle too"}"#,
+    )
+    .unwrap();
+    let out = run_in(
+        dir.path(),
+        &[
+            "--weak-grep",
+            "needle",
+            "-c",
+            "5",
+            "--grep-report",
+            "json",
+            "a.json",
+        ],
+    );
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let first = &report["matches"][0];
+    assert_eq!(
+        (&first["file"], &first["path"], &first["value"]),
+        (
+            &serde_json::json!("a.json"),
+            &serde_json::json!("$.items[0].name"),
+            &serde_json::json!("needle")
+        )
+    );
+    assert_eq!(report["matches"][1]["path"], "$.tail");
+    assert!(
+        report["hidden"].as_u64().unwrap() > 0,
+        "weak grep under a tiny budget should report hidden matches: {out}"
+    );
+}
+
+#[test]
+fn strong_grep_report_shows_every_match() {
+    let assert = cargo_bin_cmd!("hson")
+        .args([
+            "--no-color",
+            "-c",
+            "5",
+            "--grep",
+            "x",
+            "--grep-report",
+            "json",
+        ])
+        .write_stdin(r#"{"a":"x","b":{"c":"xx"}}"#)
+        .assert()
+        .success();
+    let out = String::from_utf8_lossy(&assert.get_output().stdout);
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(report["total"], 2);
+    assert_eq!(report["hidden"], 0);
+    assert!(report["matches"][0]["file"].is_null());
+}
+
+#[test]
+fn grep_report_requires_a_pattern() {
+    cargo_bin_cmd!("hson")
+        .args(["--grep-report", "summary"])
+        .write_stdin("{}")
+        .assert()
+        .failure();
+}
@@ -0,0 +1,92 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn run_in(dir: &std::path::Path, args: &[&str]) -> (String, String) {
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort"])
+        .args(args)
+        .assert()
+        .success();
+    let out = assert.get_output();
+    (
+        String::from_utf8_lossy(&out.stdout).into_owned(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+    )
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a.json"),
+        r#"{"a":"needle","b":{"c":"needle","d":[1,2,3,4,5,6]},"e":"x"}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("b.yaml"), "k: hay\n").unwrap();
+    std::fs::write(dir.path().join("c.yaml"), "x: needle\ny: [1, 2]\n")
+        .unwrap();
+    dir
+}
+
+#[test]
+fn names_lists_matching_files_with_counts() {
+    let dir = fixture();
+    let (out, _) = run_in(
+        dir.path(),
+        &[
+            "--grep",
+            "needle",
+            "--grep-show",
+            "names",
+            "a.json",
+            "b.yaml",
+            "c.yaml",
+        ],
+    );
+    assert_eq!(out, "a.json: 2 matches\nc.yaml: 1 match\n");
+}
+
+#[test]
+fn names_without_matches_prints_notice() {
+    let dir = fixture();
+    let (out, err) = run_in(
+        dir.path(),
+        &["--grep", "zzz", "--grep-show", "names", "a.json", "b.yaml"],
+    );
+    assert_eq!(out.trim(), "");
+    assert!(err.contains("No grep matches found"), "stderr: {err}");
+}
+
+#[test]
+fn summary_renders_only_the_must_keep_skeleton() {
+    let dir = fixture();
+    let (out, _) = run_in(
+        dir.path(),
+        &[
+            "--grep",
+            "needle",
+            "--grep-show",
+            "summary",
+            "-c",
+            "100000",
+            "a.json",
+            "b.yaml",
+        ],
+    );
+    assert!(out.contains("\"c\": \"needle\""), "got: {out}");
+    assert!(
+        !out.contains("\"d\"") && !out.contains("\"e\""),
+        "no context beyond matches despite a large budget: {out}"
+    );
+    assert!(!out.contains("b.yaml"), "unmatched files dropped: {out}");
+}
+
+#[test]
+fn names_requires_strong_grep() {
+    cargo_bin_cmd!("hson")
+        .args(["--weak-grep", "x", "--grep-show", "names"])
+        .write_stdin("{}")
+        .assert()
+        .failure();
+}
@@ -0,0 +1,90 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn run(args: &[&str], stdin: &str) -> String {
+    let assert = cargo_bin_cmd!("hson")
+        .args(args)
+        .write_stdin(stdin.to_string())
+        .assert()
+        .success();
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+const LOGS: &str = r#"{"requests":[
+  {"path":"/a","latency_ms":120,"ok":true,"user":null},
+  {"path":"/b","latency_ms":870,"ok":false,"user":"kim"},
+  {"path":"/c","latency_ms":50,"ok":true,"user":null},
+  {"path":"/d","latency_ms":1500,"ok":true,"user":"lee"}
+]}"#;
+
+#[test]
+fn numeric_predicate_keeps_matches_under_tiny_budget() {
+    let out = run(
+        &["--no-color", "-c", "10", "--where", "latency_ms > 500"],
+        LOGS,
+    );
+    assert!(out.contains("870") && out.contains("1500"), "got: {out}");
+    assert!(
+        !out.contains("120"),
+        "non-matching values are pruned: {out}"
+    );
+}
+
+#[test]
+fn anchored_boolean_and_string_predicates_are_ored() {
+    let out = run(
+        &[
+            "--no-color",
+            "-c",
+            "10",
+            "--where",
+            "$.requests[*].ok == false",
+            "--where",
+            "user == \"lee\"",
+        ],
+        LOGS,
+    );
+    assert!(
+        out.contains("false") && out.contains("\"lee\""),
+        "got: {out}"
+    );
+    assert!(!out.contains("\"kim\""), "got: {out}");
+}
+
+#[test]
+fn predicate_hits_are_highlighted_whole() {
+    let out = run(
+        &["--color", "-c", "10", "--where", "latency_ms >= 1500"],
+        LOGS,
+    );
+    assert!(out.contains("\u{1b}[31m1500\u{1b}[39m"), "got: {out:?}");
+}
+
+#[test]
+fn where_misses_name_the_filter() {
+    let assert = cargo_bin_cmd!("hson")
+        .args(["--where", "latency_ms > 9000"])
+        .write_stdin(LOGS)
+        .assert()
+        .success();
+    let err = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(err.contains("No --where matches found"), "{err}");
+    assert!(!err.contains("grep"), "{err}");
+}
+
+#[test]
+fn invalid_predicate_is_rejected() {
+    cargo_bin_cmd!("hson")
+        .args(["--where", "latency_ms"])
+        .write_stdin("{}")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn where_conflicts_with_weak_grep() {
+    cargo_bin_cmd!("hson")
+        .args(["--where", "a == 1", "--weak-grep", "x"])
+        .write_stdin("{}")
+        .assert()
+        .failure();
+}
@@ -18,6 +18,7 @@ fn headson_many_text_smoke() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..headson::RenderConfig::default()
     };
     let prio = headson::PriorityConfig::new(100, 100);
     let inputs = vec![
@@ -0,0 +1,142 @@
+use std::fs;
+use tempfile::tempdir;
+
+fn run(dir: &std::path::Path, args: &[&str]) -> (bool, String, String) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-sort", "-f", "html"])
+        .args(args)
+        .output()
+        .expect("run hson");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let items: Vec<String> = (0..40).map(|i| i.to_string()).collect();
+    fs::write(
+        dir.path().join("data.json"),
+        format!("{{\"name\": \"a<b\", \"items\": [{}]}}", items.join(", ")),
+    )
+    .expect("write json");
+    fs::write(dir.path().join("conf.yaml"), "a: 1\nb: 2\n")
+        .expect("write yaml");
+    fs::create_dir(dir.path().join("src")).expect("mkdir");
+    fs::write(
+        dir.path().join("src/main.rs"),
+        "fn main() {\n    println!(\"hi\");\n}\n",
+    )
+    .expect("write rust");
+    dir
+}
+
+// Visible text of the page body, without tags and entities.
+fn body_text(page: &str) -> String {
+    let body = page.split("<body>").nth(1).expect("body");
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn pages_are_self_contained() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["data.json"]);
+    assert!(ok);
+    assert!(out.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+    assert!(out.contains("<style>:root{--bg:#"), "{out}");
+    assert!(!out.contains("<script") && !out.contains('\u{1b}'), "{out}");
+}
+
+#[test]
+fn containers_collapse_and_omissions_stay_visible() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["-n", "10", "data.json"]);
+    assert!(ok);
+    for needle in [
+        "<details open><summary>",
+        "<span class=\"cnt\"> 40 items </span>",
+        "more items</li>",
+        "&quot;a&lt;b&quot;",
+    ] {
+        assert!(out.contains(needle), "{needle}: {out}");
+    }
+}
+
+#[test]
+fn code_is_highlighted_with_line_numbers() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["src/main.rs"]);
+    assert!(ok);
+    assert!(
+        out.contains("<span class=\"ln\">1</span>  <span style=\"color:#")
+    );
+    assert!(out.contains("<span class=\"ln\">3</span>"), "{out}");
+}
+
+#[test]
+fn fileset_sections_are_anchor_linked() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["conf.yaml", "src/main.rs"]);
+    assert!(ok);
+    assert!(
+        out.contains("<li><a href=\"#file-src-main-rs\">src/main.rs</a></li>")
+    );
+    assert!(out.contains(
+        "<section id=\"file-conf-yaml\">\n<h2><a href=\"#file-conf-yaml\">conf.yaml</a></h2>"
+    ));
+}
+
+// Array items the plain `auto` preview keeps under the same budget.
+fn plain_item_count(dir: &std::path::Path) -> usize {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-sort", "--no-color", "-c", "120", "data.json"])
+        .output()
+        .expect("run hson");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| {
+            line.trim().trim_end_matches(',').parse::<u32>().is_ok()
+        })
+        .count()
+}
+
+#[test]
+fn budgets_apply_to_text_not_markup() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["-c", "120", "data.json"]);
+    assert!(ok);
+    assert!(out.len() > 120, "markup is not charged");
+    let shown = body_text(&out);
+    assert!(shown.contains("\"a<b\"") && shown.contains("more items"));
+    let kept = out.matches("<span class=\"n\">").count();
+    assert_eq!(kept, plain_item_count(dir.path()));
+}
+
+#[test]
+fn tree_mode_is_rejected() {
+    let dir = fixture();
+    let (ok, _, err) = run(dir.path(), &["--tree", "conf.yaml"]);
+    assert!(!ok);
+    assert!(
+        err.contains("--format html does not support --tree"),
+        "{err}"
+    );
+}
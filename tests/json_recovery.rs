@@ -0,0 +1,79 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+const TRUNCATED: &str = r#"{"a": [1, 2, {"b": "x", "c": [3, 4"#;
+
+fn run(stdin: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("hson")
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "-c", "10000", "--recover"])
+        .args(args)
+        .write_stdin(stdin)
+        .assert()
+}
+
+fn stdout(assert: &assert_cmd::assert::Assert) -> String {
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+#[test]
+fn truncated_json_is_closed_and_marked() {
+    let out = stdout(&run(TRUNCATED, &["--fail-on", "parse-error"]).code(3));
+    assert!(
+        out.contains(
+            "      \"c\": [\n        3\n        /* input truncated at byte 34: unexpected EOF */\n      ]\n"
+        ),
+        "marker sits inside the innermost open array: {out}"
+    );
+    assert!(
+        !out.contains("        4"),
+        "partial number is dropped: {out}"
+    );
+}
+
+#[test]
+fn yaml_output_marks_truncation_with_a_comment() {
+    let out = stdout(&run(TRUNCATED, &["-f", "yaml", "-i", "json"]).success());
+    assert!(
+        out.contains(
+            "    - 3\n    # input truncated at byte 34: unexpected EOF\n"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn strict_output_stays_valid_json() {
+    let out =
+        stdout(&run(TRUNCATED, &["-f", "json", "-t", "strict"]).success());
+    let value: serde_json::Value =
+        serde_json::from_str(&out).expect("strict output parses");
+    assert_eq!(
+        value,
+        serde_json::json!({"a": [1, 2, {"b": "x", "c": [3]}]})
+    );
+}
+
+#[test]
+fn syntax_errors_name_the_offending_character() {
+    let out = stdout(&run(r#"{"a": 1 "b": 2}"#, &[]).success());
+    assert!(
+        out.contains(
+            "/* input truncated at byte 8: unexpected character '\"' */"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn recovery_is_opt_in() {
+    cargo_bin_cmd!("hson")
+        .args(["--no-color", "-c", "10000"])
+        .write_stdin(TRUNCATED)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn unrecoverable_input_still_fails() {
+    run("not json", &[]).failure();
+}
@@ -0,0 +1,134 @@
+use std::fs;
+use tempfile::tempdir;
+
+fn run(dir: &std::path::Path, args: &[&str]) -> (bool, String, String) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort"])
+        .args(args)
+        .output()
+        .expect("run hson");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let items: Vec<String> = (0..20)
+        .map(|i| format!("{{\"spec\": {{\"image\": \"img-{i}\"}}}}"))
+        .collect();
+    fs::write(
+        dir.path().join("data.json"),
+        format!(
+            "{{\"kind\": \"List\", \"a b\": 1, \"items\": [{}]}}",
+            items.join(", ")
+        ),
+    )
+    .expect("write json");
+    fs::write(
+        dir.path().join("conf.yaml"),
+        "a: 1\nlist:\n  - x\n  - y: 2\n",
+    )
+    .expect("write yaml");
+    dir
+}
+
+#[test]
+fn gron_flattens_to_full_paths() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["-f", "gron", "conf.yaml"]);
+    assert!(ok);
+    assert_eq!(
+        out,
+        "$ = {}\n$.a = 1\n$.list = []\n$.list[0] = \"x\"\n$.list[1] = {}\n$.list[1].y = 2\n"
+    );
+}
+
+#[test]
+fn gron_keeps_the_budget_and_marks_omissions() {
+    let dir = fixture();
+    let (ok, out, _) =
+        run(dir.path(), &["-f", "gron", "-n", "12", "data.json"]);
+    assert!(ok);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines.len() <= 12, "{out}");
+    for needle in ["$.kind = \"List\"", "$[\"a b\"] = 1", "$.items[…]"] {
+        assert!(lines.contains(&needle), "{needle}: {out}");
+    }
+}
+
+#[test]
+fn gron_grep_shows_the_path_of_each_match() {
+    let dir = fixture();
+    let (ok, out, _) = run(
+        dir.path(),
+        &["-f", "gron", "-n", "5", "--grep", "img-17", "data.json"],
+    );
+    assert!(ok);
+    assert!(
+        out.lines()
+            .any(|l| l == "$.items[17].spec.image = \"img-17\""),
+        "{out}"
+    );
+}
+
+#[test]
+fn strict_gron_drops_omission_lines() {
+    let dir = fixture();
+    let (ok, out, _) = run(
+        dir.path(),
+        &["-f", "gron", "-t", "strict", "-n", "8", "data.json"],
+    );
+    assert!(ok);
+    assert!(!out.contains('…') && out.starts_with("$ = {}\n"), "{out}");
+}
+
+#[test]
+fn yaml_lines_end_with_their_key_path() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["--key-paths", "conf.yaml"]);
+    assert!(ok);
+    assert_eq!(
+        out,
+        "a: 1 # $.a\nlist: # $.list\n  - x # $.list[0]\n  - \"y\": 2 # $.list[1].y\n\n"
+    );
+}
+
+#[test]
+fn json_lines_end_with_their_key_path() {
+    let dir = fixture();
+    let (ok, out, _) =
+        run(dir.path(), &["--key-paths", "-c", "100000", "data.json"]);
+    assert!(ok);
+    for needle in [
+        "{ // $",
+        "  \"a b\": 1, // $[\"a b\"]",
+        "    { // $.items[0]",
+        "        \"image\": \"img-0\" // $.items[0].spec.image",
+    ] {
+        assert!(out.lines().any(|l| l == needle), "{needle}: {out}");
+    }
+}
+
+#[test]
+fn strict_and_annotated_json_stay_parseable() {
+    let dir = fixture();
+    for args in [
+        &["-t", "strict"][..],
+        &["-t", "annotated"][..],
+        &["-f", "json", "-t", "strict"][..],
+    ] {
+        let (ok, out, _) = run(
+            dir.path(),
+            &[args, &["--key-paths", "-n", "12", "data.json"]].concat(),
+        );
+        assert!(ok);
+        assert!(!out.contains("// $"), "{args:?}: {out}");
+        serde_json::from_str::<serde_json::Value>(&out)
+            .unwrap_or_else(|e| panic!("{args:?}: {e}: {out}"));
+    }
+}
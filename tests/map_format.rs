@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn setup() -> tempfile::TempDir {
+    let dir = tempdir().expect("tmp");
+    let files = [
+        ("config.json.tmpl", "name: demo\nports: [80, 443]\n"),
+        ("Dockerfile", "FROM alpine\nRUN echo hi\n"),
+        (".babelrc", "{\"presets\": [\"env\"]}\n"),
+        ("settings.conf", "{\"debug\": true}\n"),
+    ];
+    for (name, body) in files {
+        fs::write(dir.path().join(name), body).expect("write");
+    }
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort", "-C", "2000"])
+        .args(args)
+        .assert()
+}
+
+fn stdout(assert: &assert_cmd::assert::Assert) -> String {
+    String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+}
+
+#[test]
+fn map_format_overrides_fileset_detection() {
+    let dir = setup();
+    let out = stdout(
+        &run(
+            dir.path(),
+            &[
+                "--map-format",
+                "*.json.tmpl=yaml",
+                "config.json.tmpl",
+                ".babelrc",
+            ],
+        )
+        .success(),
+    );
+    assert!(out.contains("name: demo\nports:\n  - 80\n"), "{out}");
+}
+
+#[test]
+fn input_format_flag_disables_map_format_in_filesets() {
+    let dir = setup();
+    let out = stdout(
+        &run(
+            dir.path(),
+            &[
+                "-i",
+                "json",
+                "--map-format",
+                "*.json.tmpl=yaml",
+                "config.json.tmpl",
+                ".babelrc",
+            ],
+        )
+        .success(),
+    );
+    assert!(out.contains("name: demo\nports: [80, 443]"), "{out}");
+}
+
+#[test]
+fn well_known_filenames_pick_code_and_json() {
+    let dir = setup();
+    let out = stdout(&run(dir.path(), &["Dockerfile", ".babelrc"]).success());
+    assert!(out.contains("1: FROM alpine\n2: RUN echo hi"), "{out}");
+    assert!(out.contains("\"presets\": [\n    \"env\"\n  ]"), "{out}");
+}
+
+#[test]
+fn map_format_applies_to_single_files() {
+    let dir = setup();
+    let out = stdout(
+        &run(
+            dir.path(),
+            &["--map-format", "*.conf=json", "settings.conf"],
+        )
+        .success(),
+    );
+    assert_eq!(out.trim_end(), "{\n  \"debug\": true\n}");
+}
+
+#[test]
+fn map_format_last_matching_rule_wins() {
+    let dir = setup();
+    let out = stdout(
+        &run(
+            dir.path(),
+            &[
+                "--map-format",
+                "Dockerfile=text",
+                "--map-format",
+                "Docker*=code",
+                "Dockerfile",
+            ],
+        )
+        .success(),
+    );
+    assert!(out.contains("1: FROM alpine"), "{out}");
+}
+
+#[test]
+fn map_format_rejects_unknown_formats() {
+    let dir = setup();
+    let assert =
+        run(dir.path(), &["--map-format", "*.conf=xml", "settings.conf"])
+            .failure();
+    let err = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(err.contains("invalid --map-format '*.conf=xml'"), "{err}");
+}
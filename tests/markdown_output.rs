@@ -0,0 +1,109 @@
+use std::fs;
+use tempfile::tempdir;
+
+fn run(dir: &std::path::Path, args: &[&str]) -> (bool, String, String) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-sort", "--no-color", "-f", "markdown"])
+        .args(args)
+        .output()
+        .expect("run hson");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let items: Vec<String> = (0..40).map(|i| i.to_string()).collect();
+    fs::write(
+        dir.path().join("data.json"),
+        format!("{{\"items\": [{}]}}", items.join(", ")),
+    )
+    .expect("write json");
+    fs::write(dir.path().join("conf.yaml"), "a: 1\nb: 2\n")
+        .expect("write yaml");
+    fs::create_dir(dir.path().join("src")).expect("mkdir");
+    fs::write(
+        dir.path().join("src/main.rs"),
+        "fn main() {\n    println!(\"hi\");\n}\n",
+    )
+    .expect("write rust");
+    dir
+}
+
+#[test]
+fn single_inputs_are_fenced_with_their_language() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["conf.yaml"]);
+    assert!(ok);
+    assert_eq!(out, "```yaml\na: 1\nb: 2\n```\n");
+}
+
+#[test]
+fn code_fences_take_the_language_from_the_file_name() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["src/main.rs"]);
+    assert!(ok);
+    assert!(out.starts_with("```rust\n1: fn main() {\n"), "{out}");
+    assert!(out.ends_with("\n```\n"), "{out}");
+}
+
+#[test]
+#[allow(
+    clippy::cognitive_complexity,
+    reason = "checks both sections of one run side by side"
+)]
+fn filesets_use_headings_and_count_them_against_budgets() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["-n", "8", "conf.yaml", "data.json"]);
+    assert!(ok);
+    let sections: Vec<&str> = out.trim_end().split("\n\n").collect();
+    assert_eq!(sections[0], "## conf.yaml\n```yaml\na: 1\nb: 2\n```");
+    let json = sections[1];
+    assert!(json.starts_with("## data.json\n```json\n"), "{json}");
+    assert!(json.ends_with("\n```"), "{json}");
+    assert!(json.lines().count() <= 8, "heading and fences fit: {json}");
+}
+
+#[test]
+fn omitted_files_are_summarized() {
+    let dir = fixture();
+    let (ok, out, _) = run(
+        dir.path(),
+        &[
+            "--global-lines",
+            "6",
+            "conf.yaml",
+            "data.json",
+            "src/main.rs",
+        ],
+    );
+    assert!(ok);
+    assert!(out.contains("## conf.yaml\n"), "{out}");
+    assert!(out.trim_end().ends_with("more files_"), "{out}");
+    assert!(!out.contains("==>"), "{out}");
+}
+
+#[test]
+fn tree_renders_a_nested_list() {
+    let dir = fixture();
+    let (ok, out, _) =
+        run(dir.path(), &["--tree", "conf.yaml", "src/main.rs"]);
+    assert!(ok);
+    let expected = "- `conf.yaml`\n  ```yaml\n  a: 1\n  b: 2\n  ```\n\
+                    - `src/main.rs`\n  ```rust\n  1: fn main() {\n";
+    assert!(out.starts_with(expected), "{out}");
+    assert!(!out.contains('│'), "{out}");
+}
+
+#[test]
+fn compact_markdown_is_rejected() {
+    let dir = fixture();
+    let (ok, _, err) = run(dir.path(), &["--compact", "conf.yaml"]);
+    assert!(!ok);
+    assert!(err.contains("--format markdown needs newlines"), "{err}");
+}
@@ -1,13 +1,14 @@
 use std::fs;
+use std::path::Path;
 use tempfile::tempdir;
 
-fn run_with_paths_json(paths: &[&str]) -> (bool, String, String) {
+fn run_in(cwd: &Path, extra: &[&str]) -> (bool, String, String) {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hson");
     // large budget to avoid truncation
     let mut args =
         vec!["--no-color", "--no-sort", "-c", "100000", "-f", "auto"];
-    args.extend_from_slice(paths);
-    let assert = cmd.args(args).assert();
+    args.extend_from_slice(extra);
+    let assert = cmd.current_dir(cwd).args(args).assert();
     let ok = assert.get_output().status.success();
     let out =
         String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
@@ -16,32 +17,89 @@ fn run_with_paths_json(paths: &[&str]) -> (bool, String, String) {
     (ok, out, err)
 }
 
+fn header(path: &Path) -> String {
+    format!("==> {} <==", path.display())
+}
+
+// Layout:
+//   data/top.json
+//   data/nested/deep.json
+//   data/nested/skipped.json   (gitignored)
+//   data/.hidden/secret.json
+fn write_tree(root: &Path) {
+    let data = root.join("data");
+    fs::create_dir_all(data.join("nested")).expect("mkdir nested");
+    fs::create_dir_all(data.join(".hidden")).expect("mkdir hidden");
+    fs::write(data.join("top.json"), b"{\"top\":1}").expect("write");
+    fs::write(data.join("nested/deep.json"), b"{\"deep\":2}").expect("write");
+    fs::write(data.join("nested/skipped.json"), b"{\"skip\":3}")
+        .expect("write");
+    fs::write(data.join(".hidden/secret.json"), b"{\"secret\":4}")
+        .expect("write");
+    fs::write(root.join(".gitignore"), "skipped.json\n").expect("gitignore");
+}
+
+fn data_path(rel: &str) -> std::path::PathBuf {
+    Path::new("data").join(rel)
+}
+
 #[test]
 #[allow(
     clippy::cognitive_complexity,
-    reason = "single test composes setup + assertions succinctly"
+    reason = "single test checks included and skipped entries together"
 )]
-fn directory_inputs_are_ignored_and_reported() {
+fn directory_inputs_are_walked_recursively_respecting_gitignore() {
     let dir = tempdir().expect("tmp");
-    let sub = dir.path().join("subdir");
-    fs::create_dir_all(&sub).expect("mkdir");
-
-    let json = dir.path().join("ok.json");
-    fs::write(&json, b"{\"ok\":true}").expect("write json");
+    write_tree(dir.path());
+    let (ok, out, err) = run_in(dir.path(), &["data"]);
+    assert!(ok, "should succeed: {err}");
+    assert!(out.contains(&header(&data_path("top.json"))), "{out}");
+    assert!(
+        out.contains(&header(&data_path("nested/deep.json"))),
+        "{out}"
+    );
+    assert!(!out.contains("skipped.json"), "gitignored: {out}");
+    assert!(!out.contains("secret.json"), "hidden: {out}");
+}
 
-    let json_s = json.to_string_lossy().to_string();
-    let sub_s = sub.to_string_lossy().to_string();
+#[test]
+fn hidden_and_no_ignore_widen_the_walk() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) =
+        run_in(dir.path(), &["--hidden", "--no-ignore", "data"]);
+    assert!(ok, "should succeed: {err}");
+    assert!(out.contains(&header(&data_path(".hidden/secret.json"))));
+    assert!(out.contains(&header(&data_path("nested/skipped.json"))));
+}
 
-    let (ok, out, err) = run_with_paths_json(&[&json_s, &sub_s]);
+#[test]
+fn max_depth_limits_directory_traversal() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) = run_in(dir.path(), &["--max-depth", "1", "data"]);
     assert!(ok, "should succeed: {err}");
+    assert!(out.contains(&header(&data_path("top.json"))), "{out}");
+    assert!(!out.contains("deep.json"), "too deep: {out}");
+}
 
-    assert!(out.contains("==> "));
-    assert!(out.contains(&json_s));
-    assert!(!out.contains(&format!("==> {sub_s} <==")));
+#[test]
+fn max_depth_zero_keeps_only_explicit_paths() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) = run_in(dir.path(), &["--max-depth", "0", "data"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(out, "\n", "depth 0 keeps only explicit paths");
+    assert!(err.contains("No files found in directory: data"), "{err}");
+}
 
-    let err_t = err.trim_end();
-    assert!(
-        err_t.ends_with(&format!("Ignored directory: {sub_s}")),
-        "stderr should end with directory ignore notice. stderr: {err_t}"
-    );
+#[test]
+fn single_file_directory_still_renders_with_header() {
+    let dir = tempdir().expect("tmp");
+    let only = dir.path().join("only");
+    fs::create_dir_all(&only).expect("mkdir");
+    fs::write(only.join("a.json"), b"{\"a\":1}").expect("write");
+    let (ok, out, err) = run_in(dir.path(), &["only"]);
+    assert!(ok, "should succeed: {err}");
+    assert!(out.contains(&header(&Path::new("only").join("a.json"))));
 }
@@ -0,0 +1,129 @@
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+fn envelope(dir: &std::path::Path, args: &[&str]) -> (Option<i32>, Value) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-sort", "--output", "json-envelope"])
+        .args(args)
+        .output()
+        .expect("run hson");
+    let value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    (output.status.code(), value)
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    let items: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+    fs::write(
+        dir.path().join("data.json"),
+        format!(
+            "{{\"items\": [{}], \"name\": \"needle\"}}",
+            items.join(", ")
+        ),
+    )
+    .expect("write json");
+    fs::write(dir.path().join("conf.yaml"), "a: 1\n").expect("write yaml");
+    fs::write(dir.path().join("blob.bin"), [0x00, 0xFF, 0x00, 0x01])
+        .expect("write bin");
+    dir
+}
+
+#[test]
+#[allow(
+    clippy::cognitive_complexity,
+    reason = "checks several envelope fields of one run side by side"
+)]
+fn single_file_envelope_reports_budgets_and_omissions() {
+    let dir = fixture();
+    let (code, env) = envelope(dir.path(), &["-c", "60", "data.json"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(env["version"], 1);
+    assert_eq!(env["template"], "pseudo");
+    assert_eq!(env["truncated"], true);
+    assert_eq!(
+        env["binding"],
+        serde_json::json!(["bytes", "per-file bytes"])
+    );
+    let text = env["text"].as_str().expect("text");
+    assert!(text.contains("\"items\""), "{text}");
+    assert!(!text.contains('\u{1b}'), "envelope text is uncolored");
+    assert_eq!(env["bytes"], text.len());
+    assert_eq!(env["nodes_total"], 53);
+    let shown = env["nodes_shown"].as_u64().expect("shown");
+    assert_eq!(env["omitted"], 53 - shown);
+}
+
+#[test]
+#[allow(
+    clippy::cognitive_complexity,
+    reason = "checks several envelope fields of one run side by side"
+)]
+fn fileset_envelope_lists_files_and_ignored_inputs() {
+    let dir = fixture();
+    let (code, env) = envelope(
+        dir.path(),
+        &["-c", "10000", "data.json", "conf.yaml", "blob.bin"],
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(env["truncated"], false);
+    assert_eq!(env["binding"], serde_json::json!([]));
+    let files = env["files"].as_array().expect("files");
+    let names: Vec<&str> =
+        files.iter().map(|f| f["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["data.json", "conf.yaml"]);
+    assert_eq!(files[1]["format"], "yaml");
+    assert_eq!(files[1]["size"], 5);
+    assert_eq!(files[1]["omitted"], 0);
+    assert_eq!(env["ignored"][0]["file"], "blob.bin");
+}
+
+#[test]
+fn envelope_carries_grep_matches_and_exit_code() {
+    let dir = fixture();
+    let (code, env) = envelope(
+        dir.path(),
+        &[
+            "-c",
+            "40",
+            "--grep",
+            "needle",
+            "--fail-on",
+            "truncated",
+            "data.json",
+        ],
+    );
+    assert_eq!(code, Some(6));
+    assert_eq!(env["exit_code"], 6);
+    assert_eq!(env["grep"]["total"], 1);
+    assert_eq!(env["grep"]["matches"][0]["path"], "$.name");
+}
+
+#[test]
+fn envelope_is_printed_when_nothing_matches() {
+    let dir = fixture();
+    let (code, env) = envelope(dir.path(), &["--glob", "*.toml"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(env["text"], "");
+    assert_eq!(env["notices"][0]["kind"], "info");
+}
+
+#[test]
+fn envelope_keeps_template_and_budgets_when_grep_drops_every_file() {
+    let dir = fixture();
+    let (_, env) = envelope(
+        dir.path(),
+        &["-c", "500", "--grep", "zzz", "data.json", "conf.yaml"],
+    );
+    assert_eq!(env["text"], "");
+    assert_eq!(env["template"], "auto");
+    assert_eq!(
+        env["budgets"],
+        serde_json::json!([
+            {"scope": "global", "kind": "bytes", "cap": 1000},
+            {"scope": "per-file", "kind": "bytes", "cap": 500},
+        ])
+    );
+}
@@ -0,0 +1,67 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+fn stderr(assert: &assert_cmd::assert::Assert) -> String {
+    String::from_utf8_lossy(&assert.get_output().stderr).into_owned()
+}
+
+#[test]
+fn file_errors_show_location_and_excerpt() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("data.json"), "{\n  \"a\": 1 \"b\": 2\n}\n")
+        .expect("write");
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("RUST_BACKTRACE", "0")
+        .args(["--no-color", "--strict-parse", "data.json"])
+        .assert()
+        .failure();
+    let err = stderr(&assert);
+    assert!(
+        err.contains(
+            "data.json:2:10: invalid JSON: unexpected character '\"'\n \
+             1 | {\n \
+             2 |   \"a\": 1 \"b\": 2\n   \
+             |          ^\n"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn yaml_errors_use_the_same_diagnostic() {
+    let dir = tempdir().expect("tmp");
+    fs::write(dir.path().join("conf.yaml"), "name: x\nitems: [1, 2\n")
+        .expect("write");
+    let assert = cargo_bin_cmd!("hson")
+        .current_dir(dir.path())
+        .env("RUST_BACKTRACE", "0")
+        .args(["--no-color", "conf.yaml"])
+        .assert()
+        .failure();
+    let err = stderr(&assert);
+    assert!(err.contains("conf.yaml:2:13: invalid YAML: "), "{err}");
+    assert!(
+        err.contains(" 2 | items: [1, 2\n   |             ^\n"),
+        "{err}"
+    );
+}
+
+#[test]
+fn stdin_errors_report_line_and_column() {
+    let assert = cargo_bin_cmd!("hson")
+        .env("RUST_BACKTRACE", "0")
+        .args(["--no-color", "--strict-parse"])
+        .write_stdin("[1,\n 2,\n oops]")
+        .assert()
+        .failure();
+    let err = stderr(&assert);
+    assert!(
+        err.contains(
+            "line 3, column 2: invalid JSON: unexpected character 'o'"
+        ),
+        "{err}"
+    );
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use tempfile::tempdir;
+
+fn run(dir: &std::path::Path, args: &[&str]) -> (bool, String, String) {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("hson")
+        .current_dir(dir)
+        .env("HEADSON_SKIP_FRECEN", "1")
+        .args(["--no-color", "--no-sort", "--line-numbers"])
+        .args(args)
+        .output()
+        .expect("run hson");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempdir().expect("tempdir");
+    fs::write(
+        dir.path().join("data.json"),
+        "{\n  \"name\": \"svc\",\n  \"items\": [\n    1,\n    2,\n    3,\n    4,\n    5,\n    6,\n    7\n  ],\n  \"meta\": {\"a\": true}\n}\n",
+    )
+    .expect("write json");
+    fs::write(
+        dir.path().join("conf.yaml"),
+        "a: 1\nlist:\n  - x\n  - y: 2\n    z: 3\nm:\n  k: v\n",
+    )
+    .expect("write yaml");
+    dir
+}
+
+#[test]
+fn json_lines_carry_their_source_line() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["-n", "12", "data.json"]);
+    assert!(ok);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.first(), Some(&" 1: {"));
+    // Omission markers and closers have no source line of their own.
+    for needle in [
+        " 3:   \"items\": [",
+        " 7:     4,",
+        "12:   \"meta\": { … },",
+        "        …",
+        "    }",
+    ] {
+        assert!(lines.contains(&needle), "{needle}: {out}");
+    }
+}
+
+#[test]
+fn yaml_keys_and_items_carry_their_source_line() {
+    let dir = fixture();
+    let (ok, out, _) = run(dir.path(), &["conf.yaml"]);
+    assert!(ok);
+    assert_eq!(
+        out,
+        "1: a: 1\n2: list:\n3:   - x\n4:   - \"y\": 2\n5:     z: 3\n6: m:\n7:   k: v\n\n"
+    );
+}
+
+#[test]
+fn html_rejects_the_gutter() {
+    let dir = fixture();
+    let (ok, _, err) = run(dir.path(), &["-f", "html", "data.json"]);
+    assert!(
+        !ok && err.contains("does not support --line-numbers"),
+        "{err}"
+    );
+}
+
+#[test]
+fn expanded_embedded_values_keep_the_line_of_their_string() {
+    let dir = fixture();
+    fs::write(
+        dir.path().join("log.json"),
+        "{\n  \"a\": 1,\n  \"b\": \"{\\\"c\\\": [1, 2]}\"\n}\n",
+    )
+    .expect("write log");
+    let (ok, out, _) = run(dir.path(), &["--expand-embedded", "log.json"]);
+    assert!(ok);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines.contains(&"3:   \"b\": {"), "{out}");
+    assert!(lines.contains(&"       \"c\": ["), "{out}");
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn run_in(cwd: &Path, extra: &[&str]) -> (bool, String, String) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hson");
+    let mut args = vec!["--no-color", "--no-sort", "-c", "100000"];
+    args.extend_from_slice(extra);
+    let assert = cmd.current_dir(cwd).args(args).assert();
+    let ok = assert.get_output().status.success();
+    let out =
+        String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    let err =
+        String::from_utf8_lossy(&assert.get_output().stderr).into_owned();
+    (ok, out, err)
+}
+
+// Layout:
+//   src/a.json, src/b.yaml, src/c.rs, src/d.snap
+//   src/fixtures/e.json
+fn write_tree(root: &Path) {
+    let src = root.join("src");
+    fs::create_dir_all(src.join("fixtures")).expect("mkdir");
+    fs::write(src.join("a.json"), b"{\"a\":1}").expect("write");
+    fs::write(src.join("b.yaml"), b"b: 2\n").expect("write");
+    fs::write(src.join("c.rs"), b"fn main() {}\n").expect("write");
+    fs::write(src.join("d.snap"), b"snapshot\n").expect("write");
+    fs::write(src.join("fixtures/e.json"), b"{\"e\":5}").expect("write");
+}
+
+fn listed(out: &str) -> Vec<String> {
+    out.lines()
+        .filter_map(|l| l.strip_prefix("==> "))
+        .filter_map(|l| l.strip_suffix(" <=="))
+        .map(|l| l.replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn exclude_skips_files_and_directories_in_globs() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) = run_in(
+        dir.path(),
+        &[
+            "-g",
+            "src/**",
+            "--exclude",
+            "*.snap",
+            "--exclude",
+            "fixtures/",
+        ],
+    );
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(listed(&out), ["src/a.json", "src/b.yaml", "src/c.rs"]);
+}
+
+#[test]
+fn exclude_applies_to_directory_inputs() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) =
+        run_in(dir.path(), &["src", "--exclude", "fixtures/"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(
+        listed(&out),
+        ["src/a.json", "src/b.yaml", "src/c.rs", "src/d.snap"]
+    );
+}
+
+#[test]
+fn type_filters_select_by_extension() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) =
+        run_in(dir.path(), &["src", "--type", "json", "--type", "code"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(
+        listed(&out),
+        ["src/a.json", "src/c.rs", "src/fixtures/e.json"]
+    );
+}
+
+#[test]
+fn type_filters_do_not_drop_explicit_files() {
+    let dir = tempdir().expect("tmp");
+    write_tree(dir.path());
+    let (ok, out, err) =
+        run_in(dir.path(), &["src/d.snap", "-g", "src/*", "--type", "yaml"]);
+    assert!(ok, "should succeed: {err}");
+    assert_eq!(listed(&out), ["src/d.snap", "src/b.yaml"]);
+}
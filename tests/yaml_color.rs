@@ -45,6 +45,7 @@ fn yaml_coloring_applies_and_strips_to_plain() {
         fileset_tree: false,
         count_fileset_headers_in_budgets: false,
         grep_highlight: None,
+        ..RenderConfig::default()
     };
     let cfg_color = RenderConfig {
         color_enabled: true,